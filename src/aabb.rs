@@ -0,0 +1,153 @@
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// An axis-aligned bounding box.
+#[derive(Clone)]
+pub struct Aabb {
+    minimum: Vector,
+    maximum: Vector,
+}
+
+impl Aabb {
+    pub fn new(minimum: &Vector, maximum: &Vector) -> Aabb {
+        Aabb {
+            minimum: minimum.clone(),
+            maximum: maximum.clone(),
+        }
+    }
+
+    /// The smallest box containing all of `points`.
+    pub fn from_points(points: &[Vector]) -> Aabb {
+        let mut minimum = Vector::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut maximum = -&minimum;
+        for point in points {
+            minimum = Vector::new(
+                minimum.x().min(point.x()),
+                minimum.y().min(point.y()),
+                minimum.z().min(point.z()),
+            );
+            maximum = Vector::new(
+                maximum.x().max(point.x()),
+                maximum.y().max(point.y()),
+                maximum.z().max(point.z()),
+            );
+        }
+        Aabb { minimum, maximum }
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn surrounding(&self, other: &Aabb) -> Aabb {
+        Self::from_points(&[
+            self.minimum.clone(),
+            self.maximum.clone(),
+            other.minimum.clone(),
+            other.maximum.clone(),
+        ])
+    }
+
+    /// Widens any side thinner than `delta`, so that flat objects still have a box that rays hit.
+    pub fn pad(&self, delta: f64) -> Aabb {
+        let widen = |low: f64, high: f64| {
+            if high - low < delta {
+                (low - delta / 2.0, high + delta / 2.0)
+            } else {
+                (low, high)
+            }
+        };
+        let (x0, x1) = widen(self.minimum.x(), self.maximum.x());
+        let (y0, y1) = widen(self.minimum.y(), self.maximum.y());
+        let (z0, z1) = widen(self.minimum.z(), self.maximum.z());
+        Aabb::new(&Vector::new(x0, y0, z0), &Vector::new(x1, y1, z1))
+    }
+
+    pub fn minimum(&self) -> &Vector {
+        &self.minimum
+    }
+
+    pub fn maximum(&self) -> &Vector {
+        &self.maximum
+    }
+
+    /// Intersects the ray with the box using the slab method, returning the parametric interval
+    /// where the ray is inside the box.
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, f64, f64) {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+        let origin = [ray.origin().x(), ray.origin().y(), ray.origin().z()];
+        let direction = [
+            ray.direction().x(),
+            ray.direction().y(),
+            ray.direction().z(),
+        ];
+        let minimum = [self.minimum.x(), self.minimum.y(), self.minimum.z()];
+        let maximum = [self.maximum.x(), self.maximum.y(), self.maximum.z()];
+        for axis in 0..3 {
+            let inv_d = 1.0 / direction[axis];
+            let mut t0 = (minimum[axis] - origin[axis]) * inv_d;
+            let mut t1 = (maximum[axis] - origin[axis]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return (false, t_min, t_max);
+            }
+        }
+        (true, t_min, t_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn ray_through_box_reports_interval() {
+        let aabb = Aabb::new(&Vector::new(-1., -1., -1.), &Vector::new(1., 1., 1.));
+        let ray = Ray::new(&Vector::new(0., 0., -5.), &Vector::new(0., 0., 1.));
+        let (hit, t0, t1) = aabb.hit(&ray, 0., f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 4., t0));
+        assert!(approx_eq!(f64, 6., t1));
+    }
+
+    #[test]
+    fn surrounding_box_contains_both() {
+        let a = Aabb::new(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.));
+        let b = Aabb::new(&Vector::new(-1., 0.5, 0.), &Vector::new(0.5, 2., 0.5));
+        let both = a.surrounding(&b);
+        assert_eq!(-1., both.minimum().x());
+        assert_eq!(0., both.minimum().y());
+        assert_eq!(2., both.maximum().y());
+        assert_eq!(1., both.maximum().z());
+    }
+
+    #[test]
+    fn padding_only_widens_flat_sides() {
+        let flat = Aabb::new(&Vector::new(0., 0., 0.), &Vector::new(1., 0., 1.)).pad(0.01);
+        assert!(approx_eq!(f64, -0.005, flat.minimum().y()));
+        assert!(approx_eq!(f64, 0.005, flat.maximum().y()));
+        assert_eq!(0., flat.minimum().x());
+    }
+
+    #[test]
+    fn ray_beside_box_misses() {
+        let aabb = Aabb::new(&Vector::new(-1., -1., -1.), &Vector::new(1., 1., 1.));
+        let ray = Ray::new(&Vector::new(2., 0., -5.), &Vector::new(0., 0., 1.));
+        let (hit, _, _) = aabb.hit(&ray, 0., f64::INFINITY);
+        assert!(!hit);
+    }
+
+    #[test]
+    fn ray_starting_inside_box_is_clipped_to_t_min() {
+        let aabb = Aabb::new(&Vector::new(-1., -1., -1.), &Vector::new(1., 1., 1.));
+        let ray = Ray::new(&Vector::new(0., 0., 0.), &Vector::new(1., 0., 0.));
+        let (hit, t0, t1) = aabb.hit(&ray, 0., f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 0., t0));
+        assert!(approx_eq!(f64, 1., t1));
+    }
+}
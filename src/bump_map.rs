@@ -0,0 +1,160 @@
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Perturbs the shading normal of another material as if the surface were displaced along its
+/// normal by a height texture, using the mean of the texture's channels as the height.
+pub struct BumpMap {
+    material: Rc<dyn Material>,
+    height: Rc<dyn Texture>,
+    scale: f64,
+}
+
+impl BumpMap {
+    pub fn new(material: Rc<dyn Material>, height: Rc<dyn Texture>, scale: f64) -> BumpMap {
+        BumpMap {
+            material,
+            height,
+            scale,
+        }
+    }
+
+    fn height_at(&self, u: f64, v: f64, p: &Vector) -> f64 {
+        let value = self.height.value(u, v, p);
+        self.scale * (value.x() + value.y() + value.z()) / 3.0
+    }
+
+    fn perturb(&self, hit_record: &HitRecord) -> HitRecord {
+        let (u, v, p) = (hit_record.u, hit_record.v, &hit_record.p);
+        let (dpdu, dpdv) = (&hit_record.dpdu, &hit_record.dpdv);
+        let normal = &hit_record.normal;
+
+        // Forward differences, stepping the point along with the texture coordinates so that
+        // solid textures are differentiated too.
+        let du = 1e-4;
+        let dv = 1e-4;
+        let height = self.height_at(u, v, p);
+        let dh_du = (self.height_at(u + du, v, &(p + du * dpdu)) - height) / du;
+        let dh_dv = (self.height_at(u, v + dv, &(p + dv * dpdv)) - height) / dv;
+
+        let displaced_dpdu = dpdu + dh_du * normal;
+        let displaced_dpdv = dpdv + dh_dv * normal;
+        let perturbed = Vector::cross(&displaced_dpdu, &displaced_dpdv);
+
+        let mut perturbed_record = hit_record.clone();
+        if perturbed.length_squared() > 0.0 {
+            let perturbed = perturbed.unit_vector();
+            perturbed_record.normal = if Vector::dot(&perturbed, normal) < 0.0 {
+                -perturbed
+            } else {
+                perturbed
+            };
+        }
+        perturbed_record
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        self.material.scatter(ray_in, &self.perturb(hit_record))
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        self.material.eval(&self.perturb(hit_record), wo, wi)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        self.material.pdf(&self.perturb(hit_record), wo, wi)
+    }
+
+    fn is_opaque(&self, hit_record: &HitRecord) -> bool {
+        self.material.is_opaque(hit_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::textures::solid_color::SolidColor;
+
+    struct Ramp;
+
+    // Height increases linearly with u.
+    impl Texture for Ramp {
+        fn value(&self, u: f64, _v: f64, _p: &Vector) -> Vector {
+            Vector::new(u, u, u)
+        }
+    }
+
+    fn make_record() -> HitRecord {
+        let mut record = HitRecord::empty();
+        record.normal = Vector::new(0., 1., 0.);
+        record.geometric_normal = Vector::new(0., 1., 0.);
+        record.dpdu = Vector::new(1., 0., 0.);
+        record.dpdv = Vector::new(0., 0., -1.);
+        record
+    }
+
+    #[test]
+    fn constant_height_leaves_normal_unchanged() {
+        let bump = BumpMap::new(
+            Rc::new(Lambertian::new(&Vector::new(1., 1., 1.))),
+            Rc::new(SolidColor::new(&Vector::new(0.7, 0.7, 0.7))),
+            1.0,
+        );
+        let perturbed = bump.perturb(&make_record());
+        assert!(approx_eq!(f64, 1., perturbed.normal.y()));
+    }
+
+    #[test]
+    fn slope_tilts_normal_away_from_uphill() {
+        let bump = BumpMap::new(
+            Rc::new(Lambertian::new(&Vector::new(1., 1., 1.))),
+            Rc::new(Ramp),
+            1.0,
+        );
+        let perturbed = bump.perturb(&make_record());
+        // A 45 degree slope rising along +x.
+        let expected = Vector::new(-1., 1., 0.).unit_vector();
+        assert!(approx_eq!(
+            f64,
+            expected.x(),
+            perturbed.normal.x(),
+            epsilon = 1e-6
+        ));
+        assert!(approx_eq!(
+            f64,
+            expected.y(),
+            perturbed.normal.y(),
+            epsilon = 1e-6
+        ));
+        assert!(approx_eq!(f64, 1., perturbed.geometric_normal.y()));
+    }
+
+    #[test]
+    fn evaluates_the_material_with_the_perturbed_normal() {
+        let inner = Rc::new(Lambertian::new(&Vector::new(1., 1., 1.)));
+        let bump = BumpMap::new(inner.clone(), Rc::new(Ramp), 1.0);
+        let (record, perturbed) = (make_record(), bump.perturb(&make_record()));
+        let wo = Vector::new(0., 1., 0.);
+        let wi = Vector::new(-1., 1., 0.).unit_vector();
+        let expected = inner.eval(&perturbed, &wo, &wi).unwrap();
+        assert!(approx_eq!(
+            f64,
+            expected.x(),
+            bump.eval(&record, &wo, &wi).unwrap().x()
+        ));
+        assert!(approx_eq!(
+            f64,
+            inner.pdf(&perturbed, &wo, &wi),
+            bump.pdf(&record, &wo, &wi)
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use rand::Rng;
+
 use crate::ray::Ray;
 use crate::vector::Vector;
 
@@ -20,6 +22,8 @@ pub struct Camera {
     v: Vector,
     //w: Vector,
     lens_radius: f64,
+    /// The times the shutter opens and closes. Each ray is sent at a random time in between.
+    shutter: (f64, f64),
 }
 
 impl Camera {
@@ -38,20 +42,21 @@ impl Camera {
         let half_height = (theta / 2.0).tan();
         let half_width = aspect * half_height;
         let w = (lookfrom - lookat).unit_vector();
-        let u = Vector::cross(&vup, &w).unit_vector();
+        let u = Vector::cross(vup, &w).unit_vector();
         let v = Vector::cross(&w, &u);
         Camera {
             lower_left_corner: &origin
                 - (half_width * focus_dist * &u)
                 - (half_height * focus_dist * &v)
                 - (focus_dist * &w),
-            origin: origin,
+            origin,
             horizontal: 2.0 * half_width * focus_dist * &u,
             vertical: 2.0 * half_height * focus_dist * &v,
-            u: u,
-            v: v,
+            u,
+            v,
             //w: w,
-            lens_radius: lens_radius,
+            lens_radius,
+            shutter: (0.0, 0.0),
         }
     }
 
@@ -67,6 +72,13 @@ impl Camera {
         )
     }
 
+    /// Keeps the shutter open from `open` to `close`, so that objects moving in that time are
+    /// blurred.
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Camera {
+        self.shutter = (open, close);
+        self
+    }
+
     pub fn get_ray(&self, s: f64, t: f64) -> Ray {
         let rd = self.lens_radius * Vector::random_in_unit_disk();
         let offset = (&self.u * rd.x()) + (&self.v * rd.y());
@@ -76,6 +88,16 @@ impl Camera {
                 - &self.origin
                 - offset),
         )
+        .with_time(self.shutter_time())
+    }
+
+    fn shutter_time(&self) -> f64 {
+        let (open, close) = self.shutter;
+        if close > open {
+            rand::thread_rng().gen_range(open, close)
+        } else {
+            open
+        }
     }
 
     fn degrees_to_radians(degrees: f64) -> f64 {
@@ -0,0 +1,250 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::fresnel;
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::microfacet;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// The most times light may cross the coating before it is given up on.
+const MAX_BOUNCES: usize = 16;
+
+/// A smooth, thin dielectric coating over another material, like varnish or car paint. Light is
+/// reflected off the coating or refracted into it following Fresnel, absorbed on its way through
+/// the coating, scattered by the base, and may bounce between the base and the underside of the
+/// coating several times before escaping.
+pub struct Coated {
+    base: Rc<dyn Material>,
+    refraction_index: f64,
+    tint: Vector,
+}
+
+impl Coated {
+    pub fn new(base: Rc<dyn Material>, refraction_index: f64) -> Coated {
+        Self::tinted(base, refraction_index, &Vector::new(1.0, 1.0, 1.0))
+    }
+
+    /// A coating that lets through `tint` of each color when crossed at normal incidence.
+    pub fn tinted(base: Rc<dyn Material>, refraction_index: f64, tint: &Vector) -> Coated {
+        Coated {
+            base,
+            refraction_index,
+            tint: tint.clone(),
+        }
+    }
+
+    /// The fraction of light along `direction`, pointing away from the hit, that crosses the
+    /// coating rather than being reflected off it or absorbed in it.
+    fn through_coating(&self, hit_record: &HitRecord, direction: &Vector) -> Vector {
+        let cos_theta = Vector::dot(direction, &hit_record.normal.unit_vector());
+        if cos_theta <= 0.0 {
+            return Vector::empty();
+        }
+        (1.0 - fresnel::dielectric(cos_theta, self.refraction_index))
+            * self.transmittance(cos_theta)
+    }
+
+    /// The fraction of light left after crossing the coating at an angle with cosine `cos_theta`
+    /// to the normal; slanted paths are longer.
+    fn transmittance(&self, cos_theta: f64) -> Vector {
+        let exponent = 1.0 / cos_theta.abs().max(1e-3);
+        Vector::new(
+            self.tint.x().powf(exponent),
+            self.tint.y().powf(exponent),
+            self.tint.z().powf(exponent),
+        )
+    }
+}
+
+impl Material for Coated {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        if !hit_record.front_face {
+            return self.base.scatter(ray_in, hit_record);
+        }
+        let mut rng = rand::thread_rng();
+        let n = hit_record.normal.unit_vector();
+        let wo = -ray_in.direction().unit_vector();
+        let reflectance = fresnel::dielectric(Vector::dot(&wo, &n), self.refraction_index);
+        if rng.gen::<f64>() < reflectance {
+            let reflected = microfacet::reflect(&wo, &n);
+            return (
+                true,
+                Vector::new(1.0, 1.0, 1.0),
+                ray_in.spawn(&hit_record.p, &reflected),
+            );
+        }
+        let mut inside = match microfacet::refract(&wo, &n, self.refraction_index) {
+            Some(refracted) => refracted,
+            None => return (false, Vector::empty(), ray_in.clone()),
+        };
+
+        // Walk between the base and the underside of the coating. The coating is thin enough that
+        // the walk never leaves the hit point.
+        let mut attenuation = Vector::new(1.0, 1.0, 1.0);
+        for _ in 0..MAX_BOUNCES {
+            attenuation = attenuation * self.transmittance(Vector::dot(&inside, &n));
+            let (scatter, base_attenuation, scattered) = self
+                .base
+                .scatter(&ray_in.spawn(&hit_record.p, &inside), hit_record);
+            if !scatter {
+                return (false, Vector::empty(), scattered);
+            }
+            attenuation = attenuation * base_attenuation;
+            let up = scattered.direction().unit_vector();
+            let cos_theta = Vector::dot(&up, &n);
+            if cos_theta <= 0.0 {
+                // The base let light through, as glass would; it leaves through the base.
+                return (true, attenuation, scattered);
+            }
+            attenuation = attenuation * self.transmittance(cos_theta);
+
+            let inner_reflectance = fresnel::dielectric(cos_theta, 1.0 / self.refraction_index);
+            if rng.gen::<f64>() >= inner_reflectance {
+                if let Some(outside) = microfacet::refract(&-&up, &-&n, 1.0 / self.refraction_index)
+                {
+                    return (true, attenuation, ray_in.spawn(&hit_record.p, &outside));
+                }
+            }
+            inside = microfacet::reflect(&-&up, &-&n);
+        }
+        (false, Vector::empty(), ray_in.clone())
+    }
+
+    /// Light given off by the base, wherever it is there.
+    fn emitted(&self, hit_record: &HitRecord) -> Vector {
+        if self.is_opaque(hit_record) {
+            self.base.emitted(hit_record)
+        } else {
+            Vector::empty()
+        }
+    }
+
+    /// The base, seen through the coating: light reaching it from `wi` and leaving towards `wo`
+    /// is only what the coating lets through on the way in and out. Reflections off the coating
+    /// itself are mirror-like, and left to `scatter`.
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let f = self.base.eval(hit_record, wo, wi)?;
+        if !hit_record.front_face {
+            return Some(f);
+        }
+        Some(self.through_coating(hit_record, wo) * self.through_coating(hit_record, wi) * f)
+    }
+
+    /// `scatter` goes through the coating to the base unless the coating reflects the light.
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        let pdf = self.base.pdf(hit_record, wo, wi);
+        if !hit_record.front_face {
+            return pdf;
+        }
+        let n = hit_record.normal.unit_vector();
+        (1.0 - fresnel::dielectric(Vector::dot(wo, &n), self.refraction_index)) * pdf
+    }
+
+    /// The coating covers the base, holes and all.
+    fn is_opaque(&self, hit_record: &HitRecord) -> bool {
+        self.base.is_opaque(hit_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cutout::{AlphaMode, Cutout};
+    use crate::diffuse_light::DiffuseLight;
+    use crate::lambertian::Lambertian;
+    use crate::textures::solid_color::SolidColor;
+
+    fn record() -> HitRecord {
+        let mut hit_record = HitRecord::empty();
+        hit_record.normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.geometric_normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.front_face = true;
+        hit_record
+    }
+
+    // Returns the mean weight of all scattered light, and of the mirror reflections alone.
+    fn albedo(coated: &Coated, direction: &Vector) -> (f64, f64) {
+        let ray = Ray::new(&Vector::empty(), direction);
+        let mirror = microfacet::reflect(&-direction, &Vector::new(0.0, 0.0, 1.0));
+        let samples = 50_000;
+        let (mut total, mut specular) = (0.0, 0.0);
+        for _ in 0..samples {
+            let (scatter, attenuation, scattered) = coated.scatter(&ray, &record());
+            if scatter {
+                total += attenuation.x();
+                if (scattered.direction() - &mirror).length() < 1e-9 {
+                    specular += attenuation.x();
+                }
+            }
+        }
+        (total / samples as f64, specular / samples as f64)
+    }
+
+    #[test]
+    fn clear_coat_over_white_base_conserves_energy() {
+        let white = Rc::new(Lambertian::new(&Vector::new(1.0, 1.0, 1.0)));
+        let coated = Coated::new(white, 1.5);
+        let (total, specular) = albedo(&coated, &Vector::new(0.0, 0.0, -1.0));
+        assert!(total <= 1.0 && total > 0.97, "{}", total);
+        assert!((specular - 0.04).abs() < 0.005, "{}", specular);
+    }
+
+    #[test]
+    fn coating_reflects_more_at_grazing_angles() {
+        let black = Rc::new(Lambertian::new(&Vector::new(0.0, 0.0, 0.0)));
+        let coated = Coated::new(black, 1.5);
+        let (_, head_on) = albedo(&coated, &Vector::new(0.0, 0.0, -1.0));
+        let (_, grazing) = albedo(&coated, &Vector::new(0.98, 0.0, -0.2).unit_vector());
+        assert!(grazing > 5.0 * head_on, "{} {}", grazing, head_on);
+    }
+
+    #[test]
+    fn tinted_coating_absorbs_light_on_the_way_through() {
+        let white = Rc::new(Lambertian::new(&Vector::new(1.0, 1.0, 1.0)));
+        let coated = Coated::tinted(white, 1.5, &Vector::new(0.5, 0.5, 0.5));
+        let (total, specular) = albedo(&coated, &Vector::new(0.0, 0.0, -1.0));
+        assert!(total - specular < 0.25, "{}", total - specular);
+    }
+
+    #[test]
+    fn evaluates_the_base_through_the_coating() {
+        let white: Rc<dyn Material> = Rc::new(Lambertian::new(&Vector::new(1.0, 1.0, 1.0)));
+        let coated = Coated::tinted(white.clone(), 1.5, &Vector::new(0.5, 0.5, 0.5));
+        let normal = Vector::new(0.0, 0.0, 1.0);
+        let base = white.eval(&record(), &normal, &normal).unwrap();
+        let f = coated.eval(&record(), &normal, &normal).unwrap();
+        // Crossing twice head-on, reflecting 4% and absorbing half each time.
+        let expected = (0.96_f64 * 0.5).powi(2) * base.x();
+        assert!((expected - f.x()).abs() < 1e-12, "{} {}", expected, f.x());
+        let pdf = coated.pdf(&record(), &normal, &normal);
+        assert!((0.96 * white.pdf(&record(), &normal, &normal) - pdf).abs() < 1e-12);
+
+        let mut back = record();
+        back.front_face = false;
+        let f = coated.eval(&back, &normal, &normal).unwrap();
+        assert_eq!(base.x(), f.x());
+    }
+
+    #[test]
+    fn coated_lights_still_shine() {
+        let light = Rc::new(DiffuseLight::new(&Vector::new(4.0, 4.0, 4.0)));
+        let coated = Coated::new(light, 1.5);
+        assert_eq!(4.0, coated.emitted(&record()).x());
+    }
+
+    #[test]
+    fn coating_keeps_the_holes_of_its_base() {
+        let white = Rc::new(Lambertian::new(&Vector::new(1.0, 1.0, 1.0)));
+        let solid = Coated::new(white.clone(), 1.5);
+        assert!(solid.is_opaque(&record()));
+        let holes = Cutout::new(
+            white,
+            Rc::new(SolidColor::uniform(0.0)),
+            AlphaMode::Threshold(0.5),
+        );
+        let cut = Coated::new(Rc::new(holes), 1.5);
+        assert!(!cut.is_opaque(&record()));
+    }
+}
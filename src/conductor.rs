@@ -0,0 +1,270 @@
+use rand::Rng;
+
+use crate::fresnel;
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::microfacet::{self, Ggx};
+use crate::ray::Ray;
+use crate::thin_film::{self, ThinFilm};
+use crate::vector::Vector;
+
+/// A rough metal described by its complex index of refraction `eta + ik` for red, green and
+/// blue light, with a GGX microfacet distribution and optionally a thin film on top.
+pub struct Conductor {
+    eta: Vector,
+    k: Vector,
+    distribution: Ggx,
+    thin_film: Option<ThinFilm>,
+}
+
+impl Conductor {
+    pub fn new(eta: &Vector, k: &Vector, roughness: f64) -> Conductor {
+        Conductor {
+            eta: eta.clone(),
+            k: k.clone(),
+            distribution: Ggx::from_roughness(roughness),
+            thin_film: None,
+        }
+    }
+
+    /// Coats the metal with a thin film, such as an oxide layer, that tints its reflections.
+    pub fn with_thin_film(mut self, thin_film: ThinFilm) -> Conductor {
+        self.thin_film = Some(thin_film);
+        self
+    }
+
+    pub fn gold(roughness: f64) -> Conductor {
+        Self::new(
+            &Vector::new(0.143, 0.374, 1.442),
+            &Vector::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f64) -> Conductor {
+        Self::new(
+            &Vector::new(0.200, 0.924, 1.102),
+            &Vector::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f64) -> Conductor {
+        Self::new(
+            &Vector::new(1.657, 0.880, 0.521),
+            &Vector::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: f64) -> Conductor {
+        Self::new(
+            &Vector::new(0.155, 0.117, 0.138),
+            &Vector::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
+    /// The reflectance for red, green and blue, or at a single `wavelength` when rendering
+    /// spectrally and a thin film makes reflections vary across the spectrum.
+    fn fresnel(&self, cos_theta: f64, wavelength: Option<f64>) -> Vector {
+        match (&self.thin_film, wavelength) {
+            (None, _) => Vector::new(
+                fresnel::conductor(cos_theta, self.eta.x(), self.k.x()),
+                fresnel::conductor(cos_theta, self.eta.y(), self.k.y()),
+                fresnel::conductor(cos_theta, self.eta.z(), self.k.z()),
+            ),
+            (Some(film), None) => film.reflectance_rgb(cos_theta, 1.0, &self.eta, &self.k),
+            (Some(film), Some(wavelength)) => {
+                let eta = thin_film::rgb_at(&self.eta, wavelength);
+                let k = thin_film::rgb_at(&self.k, wavelength);
+                let reflectance = film.reflectance(cos_theta, 1.0, eta, k, wavelength);
+                Vector::new(reflectance, reflectance, reflectance)
+            }
+        }
+    }
+
+    /// The BRDF for light arriving from `wi` and leaving towards `wo`, in the local shading frame.
+    fn brdf(&self, wo: &Vector, wi: &Vector) -> Vector {
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Vector::empty();
+        }
+        let h = (wo + wi).unit_vector();
+        let d = self.distribution.d(&h);
+        let g = self.distribution.g2(wo, wi);
+        self.fresnel(Vector::dot(wo, &h), None) * (d * g / (4.0 * wo.z() * wi.z()))
+    }
+
+    /// The density with which `sample` picks `wi`, in the local shading frame.
+    fn sample_pdf(&self, wo: &Vector, wi: &Vector) -> f64 {
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+        let h = (wo + wi).unit_vector();
+        self.distribution.visible_d(wo, &h) / (4.0 * Vector::dot(wo, &h))
+    }
+
+    /// Samples an incoming direction by reflecting about a visible microfacet normal. Returns the
+    /// direction and its weight, the BRDF times the cosine term over the pdf.
+    fn sample(
+        &self,
+        wo: &Vector,
+        u1: f64,
+        u2: f64,
+        wavelength: Option<f64>,
+    ) -> Option<(Vector, Vector)> {
+        if wo.z() <= 0.0 {
+            return None;
+        }
+        let h = self.distribution.sample_visible_normal(wo, u1, u2);
+        let wi = microfacet::reflect(wo, &h);
+        if wi.z() <= 0.0 {
+            return None;
+        }
+        let shadowing = self.distribution.g2(wo, &wi) / self.distribution.g1(wo);
+        Some((
+            wi,
+            self.fresnel(Vector::dot(wo, &h), wavelength) * shadowing,
+        ))
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let frame = hit_record.shading_frame();
+        let wo = frame.to_local(&-ray_in.direction().unit_vector());
+        let mut rng = rand::thread_rng();
+        let wavelength = ray_in.wavelengths().map(|wavelengths| wavelengths.hero());
+        match self.sample(&wo, rng.gen(), rng.gen(), wavelength) {
+            Some((wi, weight)) => {
+                let direction = frame.local(wi.x(), wi.y(), wi.z());
+                let scatter = Vector::dot(&direction, &hit_record.geometric_normal) > 0.0;
+                let mut scattered = ray_in.spawn(&hit_record.p, &direction);
+                // The film's reflectance was only found for the hero wavelength.
+                if self.thin_film.is_some() {
+                    scattered.terminate_secondary_wavelengths();
+                }
+                (scatter, weight, scattered)
+            }
+            None => (false, Vector::empty(), ray_in.clone()),
+        }
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        if Vector::dot(wi, &hit_record.geometric_normal) <= 0.0 {
+            return Some(Vector::empty());
+        }
+        let frame = hit_record.shading_frame();
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+        Some(self.brdf(&wo, &wi) * wi.z().max(0.0))
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        let frame = hit_record.shading_frame();
+        self.sample_pdf(&frame.to_local(wo), &frame.to_local(wi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    // A conductor so absorbing that it reflects all light, isolating the microfacet model.
+    fn perfect_mirror(roughness: f64) -> Conductor {
+        Conductor::new(
+            &Vector::new(1.0, 1.0, 1.0),
+            &Vector::new(1e4, 1e4, 1e4),
+            roughness,
+        )
+    }
+
+    fn direction(theta: f64) -> Vector {
+        Vector::new(theta.sin(), 0.0, theta.cos())
+    }
+
+    fn mean_weight(conductor: &Conductor, wo: &Vector, rng: &mut StdRng) -> f64 {
+        let samples = 20_000;
+        (0..samples)
+            .map(|_| match conductor.sample(wo, rng.gen(), rng.gen(), None) {
+                Some((_, weight)) => weight.x(),
+                None => 0.0,
+            })
+            .sum::<f64>()
+            / samples as f64
+    }
+
+    #[test]
+    fn smooth_perfect_conductor_passes_white_furnace_test() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let conductor = perfect_mirror(0.0);
+        for &theta in &[0.0, 0.7, 1.4] {
+            let albedo = mean_weight(&conductor, &direction(theta), &mut rng);
+            assert!((albedo - 1.0).abs() < 1e-3, "theta {}: {}", theta, albedo);
+        }
+    }
+
+    #[test]
+    fn rough_perfect_conductor_never_gains_energy() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for &roughness in &[0.3, 0.6, 1.0] {
+            let conductor = perfect_mirror(roughness);
+            for &theta in &[0.0, 0.7, 1.4] {
+                let albedo = mean_weight(&conductor, &direction(theta), &mut rng);
+                assert!(albedo <= 1.0 && albedo > 0.2, "{} {}", roughness, albedo);
+            }
+        }
+    }
+
+    // Importance sampling and direct integration of the BRDF should agree on the albedo.
+    #[test]
+    fn sampling_weights_match_brdf_integral() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let conductor = Conductor::gold(0.5);
+        let wo = direction(0.8);
+        let sampled = mean_weight(&conductor, &wo, &mut rng);
+        let samples = 100_000;
+        let integrated = (0..samples)
+            .map(|_| {
+                // Uniform hemisphere sampling.
+                let z: f64 = rng.gen();
+                let phi = 2.0 * std::f64::consts::PI * rng.gen::<f64>();
+                let r = (1.0 - z * z).sqrt();
+                let wi = Vector::new(r * phi.cos(), r * phi.sin(), z);
+                conductor.brdf(&wo, &wi).x() * wi.z()
+            })
+            .sum::<f64>()
+            * 2.0
+            * std::f64::consts::PI
+            / samples as f64;
+        assert!(
+            (sampled - integrated).abs() < 0.02,
+            "{} {}",
+            sampled,
+            integrated
+        );
+    }
+
+    #[test]
+    fn pdf_integrates_to_one_over_reflected_directions() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let conductor = perfect_mirror(0.4);
+        let wo = direction(0.3);
+        let samples = 100_000;
+        let integral = (0..samples)
+            .map(|_| {
+                let z: f64 = rng.gen();
+                let phi = 2.0 * std::f64::consts::PI * rng.gen::<f64>();
+                let r = (1.0 - z * z).sqrt();
+                conductor.sample_pdf(&wo, &Vector::new(r * phi.cos(), r * phi.sin(), z))
+            })
+            .sum::<f64>()
+            * 2.0
+            * std::f64::consts::PI
+            / samples as f64;
+        // Some visible normals reflect below the horizon, so slightly less than one.
+        assert!(integral <= 1.01 && integral > 0.95, "{}", integral);
+    }
+}
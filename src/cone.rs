@@ -0,0 +1,177 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::cylinder;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::math;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A cone standing on a circular base at `base` and narrowing up the y axis to its apex. It is
+/// open at the bottom unless given a cap. `u` runs around the side and `v` runs from the base to
+/// the apex.
+pub struct Cone {
+    base: Vector,
+    radius: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+    cap: Option<Box<dyn Hittable>>,
+}
+
+impl Cone {
+    pub fn new(base: &Vector, radius: f64, height: f64, material: Rc<dyn Material>) -> Cone {
+        Cone {
+            base: base.clone(),
+            radius,
+            height,
+            material,
+            cap: None,
+        }
+    }
+
+    /// Closes the base with a disk of the same material.
+    pub fn with_cap(mut self) -> Cone {
+        self.cap = Some(cylinder::bottom_cap(
+            &self.base,
+            self.radius,
+            self.material.clone(),
+        ));
+        self
+    }
+
+    fn side_hit(&self, ray: &Ray, t: f64) -> Option<HitRecord> {
+        let p = ray.at(t);
+        let local = &p - &self.base;
+        if local.y() < 0.0 || local.y() > self.height {
+            return None;
+        }
+        let (x, z) = (local.x(), local.z());
+        let phi = (-z).atan2(x).rem_euclid(2.0 * PI);
+        let ring_radius = (x * x + z * z).sqrt();
+        let (cos_phi, sin_phi) = if ring_radius > 0.0 {
+            (x / ring_radius, -z / ring_radius)
+        } else {
+            (1.0, 0.0)
+        };
+        // The side leans in by the ratio of radius to height, and its normal leans up as much.
+        let slope = self.radius / self.height;
+        let outward_normal = Vector::new(cos_phi, slope, -sin_phi).unit_vector();
+        let mut hit_record = HitRecord {
+            t,
+            u: phi / (2.0 * PI),
+            v: local.y() / self.height,
+            p,
+            dpdu: 2.0 * PI * Vector::new(z, 0.0, -x),
+            dpdv: Vector::new(-self.radius * cos_phi, self.height, self.radius * sin_phi),
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        Some(hit_record)
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // x² + z² = k² (h - y)², with k the ratio of radius to height.
+        let origin = ray.origin() - &self.base;
+        let direction = ray.direction();
+        let k2 = (self.radius / self.height).powi(2);
+        let above = self.height - origin.y();
+        let a = direction.x() * direction.x() + direction.z() * direction.z()
+            - k2 * direction.y() * direction.y();
+        let b = 2.0
+            * (origin.x() * direction.x()
+                + origin.z() * direction.z()
+                + k2 * above * direction.y());
+        let c = origin.x() * origin.x() + origin.z() * origin.z() - k2 * above * above;
+
+        let mut closest = t_max;
+        let mut result = (false, HitRecord::empty());
+        for t in math::solve_quadratic(a, b, c) {
+            if t <= t_min || t >= closest {
+                continue;
+            }
+            if let Some(hit_record) = self.side_hit(ray, t) {
+                if hit_record.is_opaque() {
+                    closest = t;
+                    result = (true, hit_record);
+                    break;
+                }
+            }
+        }
+        if let Some(cap) = &self.cap {
+            let (hit, hit_record) = cap.hit(ray, t_min, closest);
+            if hit {
+                result = (true, hit_record);
+            }
+        }
+        result
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vector::new(self.radius, 0.0, self.radius);
+        Some(Aabb::new(
+            &(&self.base - &extent),
+            &(&self.base + &extent + Vector::new(0.0, self.height, 0.0)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    fn cone() -> Cone {
+        Cone::new(
+            &Vector::empty(),
+            1.0,
+            2.0,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn side_narrows_towards_the_apex() {
+        let cone = cone();
+        // Halfway up, the radius is halved.
+        let ray = Ray::new(&Vector::new(-3.0, 1.0, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        let (hit, hit_record) = cone.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.5, hit_record.t));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 0.5, hit_record.v));
+        // The normal is perpendicular to the slant and leans up.
+        let slant = Vector::new(0.5, 1.0, 0.0);
+        assert!(approx_eq!(
+            f64,
+            0.0,
+            Vector::dot(&hit_record.normal, &slant),
+            epsilon = 1e-12
+        ));
+        assert!(hit_record.normal.x() < 0.0 && hit_record.normal.y() > 0.0);
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(Vector::dot(&cross, &hit_record.normal) > 0.0);
+        // The mirror-image cone above the apex is not part of the shape.
+        let above = Ray::new(&Vector::new(-3.0, 3.0, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        assert!(!cone.hit(&above, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn cap_closes_the_base() {
+        let up = Ray::new(&Vector::new(0.2, -1.0, 0.0), &Vector::new(0.0, 1.0, 0.0));
+        let (hit, hit_record) = cone().hit(&up, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        let (hit, hit_record) = cone().with_cap().hit(&up, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.t, epsilon = 1e-12));
+    }
+}
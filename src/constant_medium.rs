@@ -0,0 +1,123 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::isotropic::Isotropic;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A volume of uniform density bounded by a closed `Hittable`, such as fog or smoke.
+pub struct ConstantMedium {
+    boundary: Box<dyn Hittable>,
+    neg_inv_density: f64,
+    phase_function: Rc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hittable>, density: f64, albedo: &Vector) -> ConstantMedium {
+        ConstantMedium {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Rc::new(Isotropic::new(albedo)),
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // Find where the ray enters and leaves the boundary, even if it starts inside.
+        let (hit, entry) = self.boundary.hit(ray, -f64::INFINITY, f64::INFINITY);
+        if !hit {
+            return (false, HitRecord::empty());
+        }
+        let (hit, exit) = self.boundary.hit(ray, entry.t + 0.0001, f64::INFINITY);
+        if !hit {
+            return (false, HitRecord::empty());
+        }
+
+        let t_enter = entry.t.max(t_min).max(0.0);
+        let t_exit = exit.t.min(t_max);
+        if t_enter >= t_exit {
+            return (false, HitRecord::empty());
+        }
+
+        // Sample a free-flight distance and see whether it falls inside the volume.
+        let ray_length = ray.direction().length();
+        let distance_inside_boundary = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * rand::thread_rng().gen::<f64>().ln();
+        if hit_distance > distance_inside_boundary {
+            return (false, HitRecord::empty());
+        }
+
+        let t = t_enter + hit_distance / ray_length;
+        let hit_record = HitRecord {
+            t,
+            u: 0.0,
+            v: 0.0,
+            p: ray.at(t),
+            normal: Vector::new(1.0, 0.0, 0.0), // arbitrary
+            front_face: true,                   // also arbitrary
+            material: Some(self.phase_function.clone()),
+            ..HitRecord::empty()
+        };
+        (true, hit_record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cuboid::Cuboid;
+    use crate::lambertian::Lambertian;
+
+    fn slab(density: f64) -> ConstantMedium {
+        let boundary = Cuboid::new(
+            &Vector::new(-5.0, -5.0, 0.0),
+            &Vector::new(5.0, 5.0, 2.0),
+            Rc::new(Lambertian::new(&Vector::empty())),
+        );
+        ConstantMedium::new(Box::new(boundary), density, &Vector::new(0.5, 0.5, 0.5))
+    }
+
+    // The chance of crossing the slab without scattering is exp(-density * thickness), whatever
+    // the length of the ray's direction.
+    #[test]
+    fn transmittance_through_a_slab_follows_beer_lambert() {
+        let medium = slab(0.4);
+        let ray = Ray::new(&Vector::new(0.0, 0.0, -1.0), &Vector::new(0.0, 0.0, 3.0));
+        let samples = 20_000;
+        let passed = (0..samples)
+            .filter(|_| !medium.hit(&ray, 0.001, f64::INFINITY).0)
+            .count();
+        let transmittance = passed as f64 / samples as f64;
+        let expected = (-0.4_f64 * 2.0).exp();
+        assert!(
+            (transmittance - expected).abs() < 0.015,
+            "{}",
+            transmittance
+        );
+    }
+
+    #[test]
+    fn scattering_happens_inside_the_volume_and_in_range() {
+        let medium = slab(1.0);
+        // Starting inside the slab, hits lie between the origin and the far side.
+        let ray = Ray::new(&Vector::new(0.0, 0.0, 1.0), &Vector::new(0.0, 0.0, 1.0));
+        for _ in 0..1000 {
+            let (hit, hit_record) = medium.hit(&ray, 0.001, 0.5);
+            if hit {
+                assert!(hit_record.t > 0.001 && hit_record.t < 0.5);
+                assert!(hit_record.p.z() > 1.0 && hit_record.p.z() < 1.5);
+            }
+        }
+        let above = Ray::new(&Vector::new(0.0, 0.0, 3.0), &Vector::new(0.0, 0.0, 1.0));
+        assert!(!medium.hit(&above, 0.001, f64::INFINITY).0);
+    }
+}
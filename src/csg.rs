@@ -0,0 +1,220 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable, Interval};
+use crate::ray::Ray;
+
+/// How a `Csg` combines the space inside its operands.
+#[derive(Clone, Copy)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+/// A boolean combination of two closed objects: everything inside either, inside both, or inside
+/// the first but not the second. Each surface of the result keeps the material of the operand it
+/// came from. Where the second operand is cut out of the first, its surface faces the other way,
+/// so that it bounds the hollow it leaves behind.
+pub struct Csg {
+    a: Rc<dyn Hittable>,
+    b: Rc<dyn Hittable>,
+    operation: Operation,
+}
+
+impl Csg {
+    pub fn union(a: Rc<dyn Hittable>, b: Rc<dyn Hittable>) -> Csg {
+        Csg {
+            a,
+            b,
+            operation: Operation::Union,
+        }
+    }
+
+    pub fn intersection(a: Rc<dyn Hittable>, b: Rc<dyn Hittable>) -> Csg {
+        Csg {
+            a,
+            b,
+            operation: Operation::Intersection,
+        }
+    }
+
+    /// Everything inside `a` that is not inside `b`.
+    pub fn difference(a: Rc<dyn Hittable>, b: Rc<dyn Hittable>) -> Csg {
+        Csg {
+            a,
+            b,
+            operation: Operation::Difference,
+        }
+    }
+
+    fn contains(&self, in_a: bool, in_b: bool) -> bool {
+        match self.operation {
+            Operation::Union => in_a || in_b,
+            Operation::Intersection => in_a && in_b,
+            Operation::Difference => in_a && !in_b,
+        }
+    }
+}
+
+/// Turns a hit on the surface of a cut out object inside out. The normal already faces against the
+/// ray, so only which side counts as outside changes, along with the tangents that imply it.
+fn flip(hit_record: &mut HitRecord) {
+    hit_record.front_face = !hit_record.front_face;
+    hit_record.dpdv = -&hit_record.dpdv;
+}
+
+impl Hittable for Csg {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // The ends of stretches cut short by the range lie on no surface.
+        let boundaries = self
+            .intervals(ray, t_min, t_max)
+            .into_iter()
+            .flat_map(|interval| [interval.enter, interval.exit]);
+        for hit_record in boundaries {
+            if hit_record.t > t_min && hit_record.t < t_max {
+                return (true, hit_record);
+            }
+        }
+        (false, HitRecord::empty())
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.operation {
+            Operation::Union => {
+                let a = self.a.bounding_box()?;
+                Some(a.surrounding(&self.b.bounding_box()?))
+            }
+            // Whatever is left lies inside either operand.
+            Operation::Intersection => self.a.bounding_box().or_else(|| self.b.bounding_box()),
+            Operation::Difference => self.a.bounding_box(),
+        }
+    }
+
+    /// Sweeps along the ray through the hits on both operands, keeping track of which operands the
+    /// ray is inside of, and notes where that takes it in or out of the combination.
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<Interval> {
+        // Each hit, with whether it is on `b` and whether the ray enters the operand there.
+        let mut crossings: Vec<(HitRecord, bool, bool)> = Vec::new();
+        for (on_b, operand) in [(false, &self.a), (true, &self.b)] {
+            for interval in operand.intervals(ray, t_min, t_max) {
+                crossings.push((interval.enter, on_b, true));
+                crossings.push((interval.exit, on_b, false));
+            }
+        }
+        crossings.sort_by(|x, y| x.0.t.total_cmp(&y.0.t));
+
+        let mut intervals = Vec::new();
+        let (mut in_a, mut in_b) = (false, false);
+        let mut enter: Option<HitRecord> = None;
+        for (mut hit_record, on_b, entering) in crossings {
+            let was_inside = self.contains(in_a, in_b);
+            if on_b {
+                in_b = entering;
+            } else {
+                in_a = entering;
+            }
+            let inside = self.contains(in_a, in_b);
+            if inside == was_inside {
+                continue;
+            }
+            if on_b && matches!(self.operation, Operation::Difference) {
+                flip(&mut hit_record);
+            }
+            if inside {
+                enter = Some(hit_record);
+            } else if let Some(enter) = enter.take() {
+                intervals.push(Interval {
+                    enter,
+                    exit: hit_record,
+                });
+            }
+        }
+        intervals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::material::Material;
+    use crate::sphere::Sphere;
+    use crate::vector::Vector;
+
+    fn ball(x: f64, material: &Rc<dyn Material>) -> Rc<dyn Hittable> {
+        Rc::new(Sphere::new(
+            &Vector::new(x, 0.0, 0.0),
+            1.0,
+            material.clone(),
+        ))
+    }
+
+    fn along_x(x: f64) -> Ray {
+        Ray::new(&Vector::new(x, 0.0, 0.0), &Vector::new(1.0, 0.0, 0.0))
+    }
+
+    fn materials() -> (Rc<dyn Material>, Rc<dyn Material>) {
+        (
+            Rc::new(Lambertian::new(&Vector::new(0.8, 0.2, 0.2))),
+            Rc::new(Lambertian::new(&Vector::new(0.2, 0.2, 0.8))),
+        )
+    }
+
+    #[test]
+    fn union_merges_overlapping_stretches() {
+        let (red, blue) = materials();
+        let both = Csg::union(ball(0.0, &red), ball(1.0, &blue));
+        let intervals = both.intervals(&along_x(-5.0), 0.001, f64::INFINITY);
+        assert_eq!(1, intervals.len());
+        assert!(approx_eq!(f64, 4.0, intervals[0].enter.t));
+        assert!(approx_eq!(f64, 7.0, intervals[0].exit.t));
+        // From the middle, the ray passes the inner surfaces and leaves through the blue ball.
+        let (hit, hit_record) = both.hit(&along_x(0.5), 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, 1.5, hit_record.t));
+        let material = hit_record.material.unwrap();
+        assert!(Rc::ptr_eq(&blue, &material));
+    }
+
+    #[test]
+    fn intersection_keeps_only_the_lens() {
+        let (red, blue) = materials();
+        let lens = Csg::intersection(ball(0.0, &red), ball(1.0, &blue));
+        let (hit, hit_record) = lens.hit(&along_x(-5.0), 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 5.0, hit_record.t));
+        assert!(Rc::ptr_eq(&blue, &hit_record.material.unwrap()));
+        let above = Ray::new(&Vector::new(-0.5, 0.0, 5.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!lens.hit(&above, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn difference_turns_the_cut_out_surface_inside_out() {
+        let (red, blue) = materials();
+        let bitten = Csg::difference(ball(0.0, &red), ball(1.0, &blue));
+        let ray = along_x(-5.0);
+        let intervals = bitten.intervals(&ray, 0.001, f64::INFINITY);
+        assert_eq!(1, intervals.len());
+        let exit = &intervals[0].exit;
+        assert!(approx_eq!(f64, 5.0, exit.t));
+        assert!(!exit.front_face);
+        assert!(approx_eq!(f64, -1.0, exit.normal.x()));
+        assert!(Rc::ptr_eq(&blue, exit.material.as_ref().unwrap()));
+
+        // Looking back from inside the bite, the ray enters the red ball through the blue surface,
+        // which now faces out of the remaining solid.
+        let back = Ray::new(&Vector::new(0.5, 0.0, 0.0), &Vector::new(-1.0, 0.0, 0.0));
+        let (hit, hit_record) = bitten.hit(&back, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 0.5, hit_record.t));
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.x()));
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(Vector::dot(&cross, &hit_record.normal) > 0.0);
+    }
+}
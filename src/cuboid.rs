@@ -0,0 +1,114 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::quad::Quad;
+use crate::ray::Ray;
+use crate::scenery::Scenery;
+use crate::vector::Vector;
+
+/// An axis-aligned box between two opposite corners, made of six quads facing outward.
+pub struct Cuboid {
+    sides: Scenery,
+}
+
+impl Cuboid {
+    pub fn new(a: &Vector, b: &Vector, material: Rc<dyn Material>) -> Cuboid {
+        let min = Vector::new(a.x().min(b.x()), a.y().min(b.y()), a.z().min(b.z()));
+        let max = Vector::new(a.x().max(b.x()), a.y().max(b.y()), a.z().max(b.z()));
+        let dx = Vector::new(max.x() - min.x(), 0.0, 0.0);
+        let dy = Vector::new(0.0, max.y() - min.y(), 0.0);
+        let dz = Vector::new(0.0, 0.0, max.z() - min.z());
+
+        let mut sides = Scenery::empty();
+        let faces = vec![
+            // Front and back
+            (
+                Vector::new(min.x(), min.y(), max.z()),
+                dx.clone(),
+                dy.clone(),
+            ),
+            (Vector::new(max.x(), min.y(), min.z()), -&dx, dy.clone()),
+            // Right and left
+            (Vector::new(max.x(), min.y(), max.z()), -&dz, dy.clone()),
+            (
+                Vector::new(min.x(), min.y(), min.z()),
+                dz.clone(),
+                dy.clone(),
+            ),
+            // Top and bottom
+            (Vector::new(min.x(), max.y(), max.z()), dx.clone(), -&dz),
+            (Vector::new(min.x(), min.y(), min.z()), dx, dz),
+        ];
+        for (q, u, v) in faces {
+            sides.add(Box::new(Quad::new(&q, &u, &v, material.clone())));
+        }
+        Cuboid { sides }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        self.sides.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.sides.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: &Vector, direction: &Vector) -> Option<f64> {
+        self.sides.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Vector) -> Option<Vector> {
+        self.sides.random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    #[test]
+    fn sides_face_outward() {
+        let cuboid = Cuboid::new(
+            &Vector::new(1.0, 2.0, 3.0),
+            &Vector::new(-1.0, 0.0, 1.0),
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        );
+        let center = Vector::new(0.0, 1.0, 2.0);
+        let directions = vec![
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(-1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            Vector::new(0.0, -1.0, 0.0),
+            Vector::new(0.0, 0.0, 1.0),
+            Vector::new(0.0, 0.0, -1.0),
+        ];
+        for direction in directions {
+            // From outside, each face is hit at its front.
+            let ray = Ray::new(&(&center + 5.0 * &direction), &-&direction);
+            let (hit, hit_record) = cuboid.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit);
+            assert!(hit_record.front_face);
+            assert!(approx_eq!(
+                f64,
+                1.0,
+                Vector::dot(&hit_record.normal, &direction)
+            ));
+            // From inside, the same face is hit at its back.
+            let ray = Ray::new(&center, &direction);
+            let (hit, hit_record) = cuboid.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit);
+            assert!(!hit_record.front_face);
+        }
+        let bounds = cuboid.bounding_box().unwrap();
+        assert!(approx_eq!(f64, -1.0, bounds.minimum().x(), epsilon = 1e-3));
+        assert!(approx_eq!(f64, 3.0, bounds.maximum().z(), epsilon = 1e-3));
+    }
+}
@@ -0,0 +1,313 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// How a curve is shaded across its width.
+#[derive(Clone, Copy)]
+enum Shading {
+    /// A flat ribbon, turned to face each ray.
+    Ribbon,
+    /// Still a ribbon facing the ray, but with normals that turn across it like a thin tube's.
+    Cylinder,
+}
+
+/// A thin strand along a cubic Bézier curve, whose width changes linearly from one end to the
+/// other. `u` runs along the curve and `v` runs across it, from the side to the left of the curve
+/// as seen along a ray to the side on the right.
+///
+/// Curves are found by splitting them in halves, as seen from the ray, until the pieces are
+/// nearly straight, skipping any whose bounds the ray misses.
+pub struct Curve {
+    points: [Vector; 4],
+    widths: (f64, f64),
+    shading: Shading,
+    material: Rc<dyn Material>,
+    bounds: Aabb,
+    /// How many times the curve is halved before the pieces count as straight.
+    max_depth: u32,
+}
+
+impl Curve {
+    /// A flat ribbon, as suits hair and fur, whose materials model the strand themselves.
+    pub fn ribbon(points: [Vector; 4], widths: (f64, f64), material: Rc<dyn Material>) -> Curve {
+        Self::new(points, widths, Shading::Ribbon, material)
+    }
+
+    /// A ribbon shaded as a round tube, for thicker strands such as grass or wire.
+    pub fn cylinder(points: [Vector; 4], widths: (f64, f64), material: Rc<dyn Material>) -> Curve {
+        Self::new(points, widths, Shading::Cylinder, material)
+    }
+
+    fn new(
+        points: [Vector; 4],
+        widths: (f64, f64),
+        shading: Shading,
+        material: Rc<dyn Material>,
+    ) -> Curve {
+        let half_width = 0.5 * widths.0.max(widths.1);
+        let hull = Aabb::from_points(&points);
+        let margin = Vector::new(half_width, half_width, half_width);
+        let bounds = Aabb::new(&(hull.minimum() - &margin), &(hull.maximum() + &margin));
+
+        // Halving a cubic divides how far it strays from a straight line by four, so pick enough
+        // halvings to bring that well below the width.
+        let bend = (0..2)
+            .map(|i| (&points[i] - 2.0 * &points[i + 1] + &points[i + 2]).length())
+            .fold(0.0, f64::max);
+        let tolerance = 0.05 * widths.0.max(widths.1);
+        let max_depth = if bend > 0.0 && tolerance > 0.0 {
+            ((2.0_f64.sqrt() * 6.0 * bend / (8.0 * tolerance)).log2() / 2.0)
+                .round()
+                .clamp(0.0, 10.0) as u32
+        } else {
+            0
+        };
+        Curve {
+            points,
+            widths,
+            shading,
+            material,
+            bounds,
+            max_depth,
+        }
+    }
+
+    fn width(&self, u: f64) -> f64 {
+        (1.0 - u) * self.widths.0 + u * self.widths.1
+    }
+
+    /// Searches the part of the curve between `us.0` and `us.1`, with control points `segment`
+    /// in the ray's frame, for the closest hit with a depth along the ray within `depths`.
+    /// Returns the depth and where along the curve the hit is.
+    fn intersect(
+        &self,
+        segment: &[Vector; 4],
+        us: (f64, f64),
+        depth: u32,
+        depths: (f64, f64),
+    ) -> Option<(f64, f64)> {
+        let half_width = 0.5 * self.width(us.0).max(self.width(us.1));
+        let bounds = Aabb::from_points(segment);
+        let (min, max) = (bounds.minimum(), bounds.maximum());
+        if min.x() - half_width > 0.0
+            || max.x() + half_width < 0.0
+            || min.y() - half_width > 0.0
+            || max.y() + half_width < 0.0
+            || min.z() - half_width > depths.1
+            || max.z() + half_width < depths.0
+        {
+            return None;
+        }
+
+        if depth > 0 {
+            let (first, second) = split(segment);
+            let middle = 0.5 * (us.0 + us.1);
+            let near = self.intersect(&first, (us.0, middle), depth - 1, depths);
+            let limit = near.map_or(depths.1, |(z, _)| z);
+            let far = self.intersect(&second, (middle, us.1), depth - 1, (depths.0, limit));
+            return far.or(near);
+        }
+
+        // Looking down the ray, throw out hits beyond the ends of this piece, past the lines
+        // through its end points square to the curve there.
+        let beyond_start = (segment[1].y() - segment[0].y()) * -segment[0].y()
+            + segment[0].x() * (segment[0].x() - segment[1].x());
+        let beyond_end = (segment[2].y() - segment[3].y()) * -segment[3].y()
+            + segment[3].x() * (segment[3].x() - segment[2].x());
+        if beyond_start < 0.0 || beyond_end < 0.0 {
+            return None;
+        }
+
+        // Treat the piece as the straight line between its ends to find where the ray is closest.
+        let chord = Vector::new(
+            segment[3].x() - segment[0].x(),
+            segment[3].y() - segment[0].y(),
+            0.0,
+        );
+        let chord_length2 = chord.length_squared();
+        if chord_length2 == 0.0 {
+            return None;
+        }
+        let w = (-(segment[0].x() * chord.x() + segment[0].y() * chord.y()) / chord_length2)
+            .clamp(0.0, 1.0);
+        let u = (1.0 - w) * us.0 + w * us.1;
+        let (point, _) = evaluate(segment, w);
+        let distance2 = point.x() * point.x() + point.y() * point.y();
+        let width = self.width(u);
+        if distance2 > 0.25 * width * width || point.z() <= depths.0 || point.z() >= depths.1 {
+            return None;
+        }
+        Some((point.z(), u))
+    }
+}
+
+/// The point at `u` along a cubic Bézier curve, and the derivative there.
+fn evaluate(points: &[Vector; 4], u: f64) -> (Vector, Vector) {
+    let lerp = |a: &Vector, b: &Vector| (1.0 - u) * a + u * b;
+    let (a, b, c) = (
+        lerp(&points[0], &points[1]),
+        lerp(&points[1], &points[2]),
+        lerp(&points[2], &points[3]),
+    );
+    let (d, e) = (lerp(&a, &b), lerp(&b, &c));
+    let tangent = 3.0 * (&e - &d);
+    (lerp(&d, &e), tangent)
+}
+
+/// Splits a cubic Bézier curve at its middle into two that follow it exactly.
+fn split(points: &[Vector; 4]) -> ([Vector; 4], [Vector; 4]) {
+    let middle = |a: &Vector, b: &Vector| 0.5 * (a + b);
+    let (a, b, c) = (
+        middle(&points[0], &points[1]),
+        middle(&points[1], &points[2]),
+        middle(&points[2], &points[3]),
+    );
+    let (d, e) = (middle(&a, &b), middle(&b, &c));
+    let center = middle(&d, &e);
+    (
+        [points[0].clone(), a, d, center.clone()],
+        [center, e, c, points[3].clone()],
+    )
+}
+
+impl Hittable for Curve {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        if !self.bounds.hit(ray, t_min, t_max).0 {
+            return (false, HitRecord::empty());
+        }
+        // In the ray's frame the ray runs up the z axis from the origin, and depths are distances.
+        let length = ray.direction().length();
+        let direction = ray.direction() / length;
+        let frame = Onb::from_w(&direction);
+        let to_frame = |p: &Vector| frame.to_local(&(p - ray.origin()));
+        let segment = [
+            to_frame(&self.points[0]),
+            to_frame(&self.points[1]),
+            to_frame(&self.points[2]),
+            to_frame(&self.points[3]),
+        ];
+        let (depth, u) = match self.intersect(
+            &segment,
+            (0.0, 1.0),
+            self.max_depth,
+            (t_min * length, t_max * length),
+        ) {
+            Some(found) => found,
+            None => return (false, HitRecord::empty()),
+        };
+
+        let t = depth / length;
+        let (on_curve, dpdu) = evaluate(&self.points, u);
+        let width = self.width(u);
+        let right = Vector::cross(&dpdu, &direction).unit_vector();
+        // The hit and the closest point on the curve lie at the same depth, so they differ only
+        // across the curve.
+        let offset = Vector::dot(&(ray.at(t) - &on_curve), &right);
+        let dpdv = width * &right;
+        let outward_normal = Vector::cross(&dpdu, &dpdv).unit_vector();
+        let v = (0.5 + offset / width).clamp(0.0, 1.0);
+        let mut hit_record = HitRecord {
+            t,
+            u,
+            v,
+            p: ray.at(t),
+            dpdu,
+            dpdv,
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        if let Shading::Cylinder = self.shading {
+            // Across a tube, the normal turns from one side to the other.
+            let angle = (v - 0.5) * PI;
+            hit_record.normal = angle.cos() * &hit_record.normal + angle.sin() * &right;
+        }
+        if !hit_record.is_opaque() {
+            return (false, HitRecord::empty());
+        }
+        (true, hit_record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    fn material() -> Rc<dyn Material> {
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn straight_strand_is_as_wide_as_asked_along_its_length() {
+        // Along the x axis, narrowing from 0.4 wide to 0.2.
+        let points = [0.0, 1.0, 2.0, 3.0].map(|x| Vector::new(x, 0.0, 0.0));
+        let strand = Curve::ribbon(points, (0.4, 0.2), material());
+        let down = |x: f64, y: f64| {
+            let ray = Ray::new(&Vector::new(x, y, 5.0), &Vector::new(0.0, 0.0, -2.0));
+            strand.hit(&ray, 0.001, f64::INFINITY)
+        };
+        let (hit, hit_record) = down(1.5, 0.0);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.5, hit_record.t, epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.5, hit_record.u, epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.5, hit_record.v, epsilon = 1e-12));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.z(), epsilon = 1e-12));
+
+        // Where it is 0.3 wide, a ray 0.1 to one side is a third of the way from the middle
+        // to the edge.
+        let (hit, hit_record) = down(1.5, 0.1);
+        assert!(hit);
+        let right = Vector::cross(&hit_record.dpdu, &Vector::new(0.0, 0.0, -1.0));
+        let expected = if right.y() > 0.0 {
+            5.0 / 6.0
+        } else {
+            1.0 / 6.0
+        };
+        assert!(approx_eq!(f64, expected, hit_record.v, epsilon = 1e-9));
+        assert!(down(0.0, 0.19).0);
+        assert!(!down(3.0, 0.11).0);
+        assert!(!down(3.2, 0.0).0);
+        assert!(!down(1.5, 0.16).0);
+    }
+
+    #[test]
+    fn subdivision_follows_a_bent_curve() {
+        let points = [
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(0.0, 2.0, 0.0),
+            Vector::new(2.0, 2.0, 0.0),
+            Vector::new(2.0, 0.0, 0.0),
+        ];
+        let arch = Curve::cylinder(points.clone(), (0.02, 0.02), material());
+        for &u in &[0.1, 0.3, 0.5, 0.8] {
+            let (on_curve, _) = evaluate(&points, u);
+            let ray = Ray::new(
+                &(&on_curve + Vector::new(0.0, 0.0, 3.0)),
+                &Vector::new(0.0, 0.0, -1.0),
+            );
+            let (hit, hit_record) = arch.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit, "{}", u);
+            assert!(approx_eq!(f64, u, hit_record.u, epsilon = 1e-3));
+            assert!(approx_eq!(f64, 0.5, hit_record.v, epsilon = 0.05));
+            assert!(approx_eq!(f64, 3.0, hit_record.t, epsilon = 1e-9));
+        }
+        // The middle of the arch is empty.
+        let through = Ray::new(&Vector::new(1.0, 0.8, 3.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!arch.hit(&through, 0.001, f64::INFINITY).0);
+    }
+}
@@ -0,0 +1,171 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// How an opacity between zero and one decides whether a surface is there.
+pub enum AlphaMode {
+    /// The surface is there wherever its opacity reaches the threshold, giving hard edges such as
+    /// those of leaves or chain-link fences.
+    Threshold(f64),
+    /// The surface is there with a probability equal to its opacity, so that partially opaque
+    /// surfaces such as gauze let through that fraction of light on average.
+    Stochastic,
+}
+
+/// Cuts holes in another material's surface wherever an opacity texture, read from the mean of its
+/// channels, says there is nothing there. Rays pass through the holes as if the surface were
+/// absent.
+pub struct Cutout {
+    material: Rc<dyn Material>,
+    opacity: Rc<dyn Texture>,
+    mode: AlphaMode,
+}
+
+impl Cutout {
+    pub fn new(material: Rc<dyn Material>, opacity: Rc<dyn Texture>, mode: AlphaMode) -> Cutout {
+        Cutout {
+            material,
+            opacity,
+            mode,
+        }
+    }
+
+    fn opacity_at(&self, hit_record: &HitRecord) -> f64 {
+        let value = self
+            .opacity
+            .value(hit_record.u, hit_record.v, &hit_record.p);
+        (value.x() + value.y() + value.z()) / 3.0
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        self.material.scatter(ray_in, hit_record)
+    }
+
+    /// Light given off by the material, and none from the holes.
+    fn emitted(&self, hit_record: &HitRecord) -> Vector {
+        if self.is_opaque(hit_record) {
+            self.material.emitted(hit_record)
+        } else {
+            Vector::empty()
+        }
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        self.material.eval(hit_record, wo, wi)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        self.material.pdf(hit_record, wo, wi)
+    }
+
+    fn is_opaque(&self, hit_record: &HitRecord) -> bool {
+        let opacity = self.opacity_at(hit_record);
+        let present = match self.mode {
+            AlphaMode::Threshold(threshold) => opacity >= threshold,
+            AlphaMode::Stochastic => rand::thread_rng().gen::<f64>() < opacity,
+        };
+        present && self.material.is_opaque(hit_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diffuse_light::DiffuseLight;
+    use crate::hittable::Hittable;
+    use crate::lambertian::Lambertian;
+    use crate::sphere::Sphere;
+    use crate::textures::solid_color::SolidColor;
+
+    fn white() -> Rc<dyn Material> {
+        Rc::new(Lambertian::new(&Vector::new(1.0, 1.0, 1.0)))
+    }
+
+    // Opaque only on the bottom half of the sphere, where v is below a half.
+    struct BottomHalf;
+
+    impl Texture for BottomHalf {
+        fn value(&self, _u: f64, v: f64, _p: &Vector) -> Vector {
+            let opacity = if v < 0.5 { 1.0 } else { 0.0 };
+            Vector::new(opacity, opacity, opacity)
+        }
+    }
+
+    #[test]
+    fn rays_pass_through_holes_to_the_far_side() {
+        let cutout = Cutout::new(white(), Rc::new(BottomHalf), AlphaMode::Threshold(0.5));
+        let sphere = Sphere::new(&Vector::empty(), 1.0, Rc::new(cutout));
+        // Entering through the open top and leaving through the solid bottom.
+        let ray = Ray::new(&Vector::new(0.0, 5.0, 0.1), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = sphere.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(hit_record.p.y() < 0.0);
+        assert!(!hit_record.front_face);
+        // Grazing only the open top misses entirely.
+        let ray = Ray::new(&Vector::new(-5.0, 0.5, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        assert!(!sphere.hit(&ray, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn shadow_rays_pass_through_holes() {
+        let cutout = Cutout::new(white(), Rc::new(BottomHalf), AlphaMode::Threshold(0.5));
+        let sphere = Sphere::new(&Vector::empty(), 1.0, Rc::new(cutout));
+        let across_top = Ray::new(&Vector::new(-5.0, 0.5, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(1.0, sphere.transmittance(&across_top, 0.001, f64::INFINITY));
+        let across_bottom = Ray::new(&Vector::new(-5.0, -0.5, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        assert_eq!(
+            0.0,
+            sphere.transmittance(&across_bottom, 0.001, f64::INFINITY)
+        );
+    }
+
+    #[test]
+    fn lights_only_shine_where_they_are_not_cut_away() {
+        let light = Rc::new(DiffuseLight::new(&Vector::new(4.0, 4.0, 4.0)));
+        let cutout = Cutout::new(light, Rc::new(BottomHalf), AlphaMode::Threshold(0.5));
+        let mut hit_record = HitRecord::empty();
+        hit_record.front_face = true;
+        hit_record.v = 0.25;
+        assert_eq!(4.0, cutout.emitted(&hit_record).x());
+        hit_record.v = 0.75;
+        assert_eq!(0.0, cutout.emitted(&hit_record).x());
+    }
+
+    #[test]
+    fn evaluates_like_the_material_it_cuts() {
+        let cutout = Cutout::new(white(), Rc::new(BottomHalf), AlphaMode::Threshold(0.5));
+        let mut hit_record = HitRecord::empty();
+        hit_record.normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.geometric_normal = Vector::new(0.0, 0.0, 1.0);
+        let (wo, wi) = (Vector::new(0.0, 0.0, 1.0), Vector::new(0.6, 0.0, 0.8));
+        let expected = white().eval(&hit_record, &wo, &wi).unwrap();
+        assert_eq!(
+            expected.x(),
+            cutout.eval(&hit_record, &wo, &wi).unwrap().x()
+        );
+        assert_eq!(
+            white().pdf(&hit_record, &wo, &wi),
+            cutout.pdf(&hit_record, &wo, &wi)
+        );
+    }
+
+    #[test]
+    fn stochastic_opacity_lets_through_its_complement() {
+        let opacity = Rc::new(SolidColor::uniform(0.3));
+        let cutout = Cutout::new(white(), opacity, AlphaMode::Stochastic);
+        let hit_record = HitRecord::empty();
+        let samples = 10_000;
+        let opaque = (0..samples)
+            .filter(|_| cutout.is_opaque(&hit_record))
+            .count();
+        let fraction = opaque as f64 / samples as f64;
+        assert!((fraction - 0.3).abs() < 0.02, "{}", fraction);
+    }
+}
@@ -0,0 +1,173 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::disk::Disk;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::math;
+use crate::ray::Ray;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// A cylinder standing on `base` and rising up the y axis. It is an open tube unless given caps.
+/// `u` runs around the side and `v` runs from the bottom to the top.
+pub struct Cylinder {
+    base: Vector,
+    radius: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+    caps: Vec<Box<dyn Hittable>>,
+}
+
+impl Cylinder {
+    pub fn new(base: &Vector, radius: f64, height: f64, material: Rc<dyn Material>) -> Cylinder {
+        Cylinder {
+            base: base.clone(),
+            radius,
+            height,
+            material,
+            caps: Vec::new(),
+        }
+    }
+
+    /// Closes both ends with disks of the same material.
+    pub fn with_caps(mut self) -> Cylinder {
+        let top = &self.base + Vector::new(0.0, self.height, 0.0);
+        self.caps = vec![
+            Box::new(Disk::new(&top, self.radius, self.material.clone())),
+            bottom_cap(&self.base, self.radius, self.material.clone()),
+        ];
+        self
+    }
+
+    fn side_hit(&self, ray: &Ray, t: f64) -> Option<HitRecord> {
+        let p = ray.at(t);
+        let local = &p - &self.base;
+        if local.y() < 0.0 || local.y() > self.height {
+            return None;
+        }
+        let (x, z) = (local.x(), local.z());
+        let phi = (-z).atan2(x).rem_euclid(2.0 * PI);
+        let mut hit_record = HitRecord {
+            t,
+            u: phi / (2.0 * PI),
+            v: local.y() / self.height,
+            p,
+            dpdu: 2.0 * PI * Vector::new(z, 0.0, -x),
+            dpdv: Vector::new(0.0, self.height, 0.0),
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &(Vector::new(x, 0.0, z) / self.radius));
+        Some(hit_record)
+    }
+}
+
+/// A disk closing the bottom of a shape standing on `base`, facing down.
+pub fn bottom_cap(base: &Vector, radius: f64, material: Rc<dyn Material>) -> Box<dyn Hittable> {
+    let disk = Rc::new(Disk::new(&Vector::empty(), radius, material));
+    let flip = Transform::rotation(&Vector::new(1.0, 0.0, 0.0), 180.0);
+    Box::new(Transformed::new(
+        disk,
+        flip.then(&Transform::translation(base)),
+    ))
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let origin = ray.origin() - &self.base;
+        let direction = ray.direction();
+        let a = direction.x() * direction.x() + direction.z() * direction.z();
+        let b = 2.0 * (origin.x() * direction.x() + origin.z() * direction.z());
+        let c = origin.x() * origin.x() + origin.z() * origin.z() - self.radius * self.radius;
+
+        let mut closest = t_max;
+        let mut result = (false, HitRecord::empty());
+        for t in math::solve_quadratic(a, b, c) {
+            if t <= t_min || t >= closest {
+                continue;
+            }
+            if let Some(hit_record) = self.side_hit(ray, t) {
+                if hit_record.is_opaque() {
+                    closest = t;
+                    result = (true, hit_record);
+                    break;
+                }
+            }
+        }
+        for cap in &self.caps {
+            let (hit, hit_record) = cap.hit(ray, t_min, closest);
+            if hit {
+                closest = hit_record.t;
+                result = (true, hit_record);
+            }
+        }
+        result
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vector::new(self.radius, 0.0, self.radius);
+        Some(Aabb::new(
+            &(&self.base - &extent),
+            &(&self.base + &extent + Vector::new(0.0, self.height, 0.0)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    fn cylinder() -> Cylinder {
+        Cylinder::new(
+            &Vector::new(0.0, -1.0, 0.0),
+            1.0,
+            2.0,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn open_tube_is_seen_from_inside_through_its_ends() {
+        let tube = cylinder();
+        let ray = Ray::new(&Vector::new(-3.0, 0.5, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        let (hit, hit_record) = tube.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.0, hit_record.t));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, -1.0, hit_record.normal.x()));
+        assert!(approx_eq!(f64, 0.5, hit_record.u));
+        assert!(approx_eq!(f64, 0.75, hit_record.v));
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(cross.x() < 0.0);
+
+        // Looking down the open end, the ray hits the inside of the far wall.
+        let down = Ray::new(&Vector::new(-0.5, 2.0, 0.0), &Vector::new(0.6, -0.8, 0.0));
+        let (hit, hit_record) = tube.hit(&down, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.p.x(), epsilon = 1e-12));
+    }
+
+    #[test]
+    fn caps_close_the_ends() {
+        let closed = cylinder().with_caps();
+        let down = Ray::new(&Vector::new(0.5, 3.0, 0.0), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = closed.hit(&down, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.0, hit_record.t));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.y()));
+        // From inside, the bottom cap faces away.
+        let inside = Ray::new(&Vector::new(0.5, 0.0, 0.0), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = closed.hit(&inside, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, -1.0, hit_record.p.y(), epsilon = 1e-12));
+    }
+}
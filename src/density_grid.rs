@@ -0,0 +1,118 @@
+use crate::vector::Vector;
+
+/// A regular 3D grid of density samples, addressed with coordinates in the unit cube.
+pub struct DensityGrid {
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    data: Vec<f64>,
+}
+
+impl DensityGrid {
+    /// Creates a grid from samples stored with `x` varying fastest, then `y`, then `z`.
+    pub fn new(nx: usize, ny: usize, nz: usize, data: Vec<f64>) -> DensityGrid {
+        assert_eq!(nx * ny * nz, data.len());
+        DensityGrid { nx, ny, nz, data }
+    }
+
+    pub fn from_fn(nx: usize, ny: usize, nz: usize, f: impl Fn(&Vector) -> f64) -> DensityGrid {
+        let mut data = Vec::with_capacity(nx * ny * nz);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let p = Vector::new(
+                        (x as f64 + 0.5) / nx as f64,
+                        (y as f64 + 0.5) / ny as f64,
+                        (z as f64 + 0.5) / nz as f64,
+                    );
+                    data.push(f(&p));
+                }
+            }
+        }
+        Self::new(nx, ny, nz, data)
+    }
+
+    pub fn max_density(&self) -> f64 {
+        self.data.iter().cloned().fold(0.0, f64::max)
+    }
+
+    /// Samples the grid at a point in the unit cube, trilinearly interpolating between the
+    /// voxel centers around it.
+    pub fn lookup(&self, p: &Vector) -> f64 {
+        let (x0, x1, fx) = Self::cell(p.x(), self.nx);
+        let (y0, y1, fy) = Self::cell(p.y(), self.ny);
+        let (z0, z1, fz) = Self::cell(p.z(), self.nz);
+        let lerp = |a: f64, b: f64, t: f64| (1.0 - t) * a + t * b;
+        let d00 = lerp(self.at(x0, y0, z0), self.at(x1, y0, z0), fx);
+        let d10 = lerp(self.at(x0, y1, z0), self.at(x1, y1, z0), fx);
+        let d01 = lerp(self.at(x0, y0, z1), self.at(x1, y0, z1), fx);
+        let d11 = lerp(self.at(x0, y1, z1), self.at(x1, y1, z1), fx);
+        lerp(lerp(d00, d10, fy), lerp(d01, d11, fy), fz)
+    }
+
+    fn at(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.data[(z * self.ny + y) * self.nx + x]
+    }
+
+    fn cell(coordinate: f64, size: usize) -> (usize, usize, f64) {
+        let position = coordinate * size as f64 - 0.5;
+        let max_index = (size - 1) as f64;
+        let position = position.max(0.0).min(max_index);
+        let lower = position.floor();
+        let upper = (lower + 1.0).min(max_index);
+        (lower as usize, upper as usize, position - lower)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn lookup_at_voxel_centers_returns_samples() {
+        let grid = DensityGrid::new(2, 1, 1, vec![1.0, 3.0]);
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            grid.lookup(&Vector::new(0.25, 0.5, 0.5))
+        ));
+        assert!(approx_eq!(
+            f64,
+            3.0,
+            grid.lookup(&Vector::new(0.75, 0.5, 0.5))
+        ));
+    }
+
+    #[test]
+    fn lookup_interpolates_between_voxels() {
+        let grid = DensityGrid::new(2, 2, 2, vec![0., 1., 0., 1., 0., 1., 0., 1.]);
+        assert!(approx_eq!(
+            f64,
+            0.5,
+            grid.lookup(&Vector::new(0.5, 0.3, 0.9))
+        ));
+    }
+
+    #[test]
+    fn lookup_clamps_outside_voxel_centers() {
+        let grid = DensityGrid::new(2, 1, 1, vec![1.0, 3.0]);
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            grid.lookup(&Vector::new(0.0, 0.5, 0.5))
+        ));
+        assert!(approx_eq!(
+            f64,
+            3.0,
+            grid.lookup(&Vector::new(1.0, 0.5, 0.5))
+        ));
+    }
+
+    #[test]
+    fn max_density_finds_largest_sample() {
+        let grid = DensityGrid::new(3, 1, 1, vec![0.5, 2.5, 1.0]);
+        assert!(approx_eq!(f64, 2.5, grid.max_density()));
+    }
+}
@@ -1,16 +1,164 @@
 use crate::hittable::HitRecord;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::thin_film::ThinFilm;
 use crate::vector::Vector;
 use rand::Rng;
+use std::rc::Rc;
+
+/// The wavelength at which dispersive materials are evaluated outside of spectral rendering,
+/// the sodium D line used to quote refractive indices.
+const REFERENCE_WAVELENGTH: f64 = 587.6;
+
+enum RefractionIndex {
+    Constant(f64),
+    /// Cauchy's equation, `n = a + b / λ²`, with `λ` in micrometers.
+    Cauchy {
+        a: f64,
+        b: f64,
+    },
+    /// The Sellmeier equation, `n² = 1 + Σ bᵢλ² / (λ² - cᵢ)`, with `λ` in micrometers.
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl RefractionIndex {
+    fn at(&self, wavelength: f64) -> f64 {
+        let lambda = wavelength / 1000.0;
+        let lambda_squared = lambda * lambda;
+        match self {
+            RefractionIndex::Constant(n) => *n,
+            RefractionIndex::Cauchy { a, b } => a + b / lambda_squared,
+            RefractionIndex::Sellmeier { b, c } => (1.0
+                + b.iter()
+                    .zip(c.iter())
+                    .map(|(b, c)| b * lambda_squared / (lambda_squared - c))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+
+    fn is_dispersive(&self) -> bool {
+        !matches!(self, RefractionIndex::Constant(_))
+    }
+}
+
+/// The medium enclosed by a dielectric surface. Rays keep track of the interiors they are inside
+/// of, so that nested dielectrics such as liquid in a glass know what lies on either side of each
+/// boundary.
+pub struct Interior {
+    refraction_index: RefractionIndex,
+    absorption: Vector,
+    priority: u32,
+}
+
+impl Interior {
+    /// The interior that a ray inside all of `interiors` is actually travelling through: the one
+    /// with the highest priority, or the most recently entered among equals.
+    pub fn current(interiors: &[Rc<Interior>]) -> Option<&Rc<Interior>> {
+        interiors.iter().max_by_key(|interior| interior.priority)
+    }
+
+    /// The fraction of light of each color left after travelling `distance` through the
+    /// interior, following the Beer-Lambert law.
+    pub fn transmittance(&self, distance: f64) -> Vector {
+        Vector::new(
+            (-self.absorption.x() * distance).exp(),
+            (-self.absorption.y() * distance).exp(),
+            (-self.absorption.z() * distance).exp(),
+        )
+    }
+}
 
 pub struct Dielectric {
-    refraction_index: f64,
+    interior: Rc<Interior>,
+    thin_film: Option<ThinFilm>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Dielectric {
-        Dielectric { refraction_index }
+        Self::from_refraction_index(RefractionIndex::Constant(refraction_index))
+    }
+
+    // No scene uses a Cauchy fit yet; the presets below are Sellmeier fits.
+    #[allow(dead_code)]
+    pub fn cauchy(a: f64, b: f64) -> Dielectric {
+        Self::from_refraction_index(RefractionIndex::Cauchy { a, b })
+    }
+
+    pub fn sellmeier(b: [f64; 3], c: [f64; 3]) -> Dielectric {
+        Self::from_refraction_index(RefractionIndex::Sellmeier { b, c })
+    }
+
+    fn from_refraction_index(refraction_index: RefractionIndex) -> Dielectric {
+        Dielectric {
+            interior: Rc::new(Interior {
+                refraction_index,
+                absorption: Vector::empty(),
+                priority: 0,
+            }),
+            thin_film: None,
+        }
+    }
+
+    /// Sets the absorption coefficient of the interior, per unit distance, for each color.
+    pub fn with_absorption(mut self, absorption: &Vector) -> Dielectric {
+        self.interior_mut().absorption = absorption.clone();
+        self
+    }
+
+    /// Sets the absorption of the interior so that light keeps `color` of its intensity after
+    /// travelling `distance` through it.
+    pub fn with_transmittance(self, color: &Vector, distance: f64) -> Dielectric {
+        let absorption = |channel: f64| -channel.max(1e-6).ln() / distance;
+        let absorption = Vector::new(
+            absorption(color.x()),
+            absorption(color.y()),
+            absorption(color.z()),
+        );
+        self.with_absorption(&absorption)
+    }
+
+    /// Sets which interior wins where dielectrics overlap; the higher priority takes precedence.
+    /// Modeling liquid in a glass as slightly overlapping the glass walls, with the glass at a
+    /// higher priority, gives a clean glass-liquid boundary.
+    pub fn with_priority(mut self, priority: u32) -> Dielectric {
+        self.interior_mut().priority = priority;
+        self
+    }
+
+    /// Coats the surface with a thin film, which makes its reflections iridescent. A film on a
+    /// dielectric with an index of one, like air, makes a soap bubble.
+    pub fn with_thin_film(mut self, thin_film: ThinFilm) -> Dielectric {
+        self.thin_film = Some(thin_film);
+        self
+    }
+
+    /// The medium inside the surface, which rays carry while they are in it.
+    pub fn interior(&self) -> &Rc<Interior> {
+        &self.interior
+    }
+
+    fn interior_mut(&mut self) -> &mut Interior {
+        Rc::get_mut(&mut self.interior).expect("interior is shared before it is configured")
+    }
+
+    /// Schott N-BK7, a common optical crown glass.
+    pub fn crown_glass() -> Dielectric {
+        Self::sellmeier(
+            [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        )
+    }
+
+    /// Schott SF11, a dense flint glass with strong dispersion.
+    pub fn flint_glass() -> Dielectric {
+        Self::sellmeier(
+            [1.737_596_95, 0.313_747_346, 1.898_781_01],
+            [0.013_188_707, 0.062_306_814_2, 155.236_29],
+        )
     }
 
     fn refract(uv: &Vector, n: &Vector, etai_over_etat: f64) -> Vector {
@@ -42,23 +190,150 @@ impl Dielectric {
 impl Material for Dielectric {
     fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
         let attenuation = Vector::new(1.0, 1.0, 1.0);
-        let etai_over_etat = if hit_record.front_face {
-            1.0 / self.refraction_index
+        let interior = &self.interior;
+        let entering = hit_record.front_face;
+
+        // Work out which interiors lie on either side of the boundary. If a higher-priority
+        // interior overlaps this one, the boundary is hidden inside it and the ray carries on.
+        let mut beyond = ray_in.clone();
+        if entering {
+            beyond.enter(interior);
         } else {
-            self.refraction_index
+            beyond.exit(interior);
+        }
+        let near = Interior::current(ray_in.interiors());
+        let far = Interior::current(beyond.interiors());
+        let same_side = match (near, far) {
+            (Some(near), Some(far)) => Rc::ptr_eq(near, far),
+            (None, None) => true,
+            _ => false,
         };
+        if same_side {
+            let passed = beyond.spawn(&hit_record.p, ray_in.direction());
+            return (true, attenuation, passed);
+        }
+
+        let wavelength = ray_in
+            .wavelengths()
+            .map_or(REFERENCE_WAVELENGTH, |wavelengths| wavelengths.hero());
+        let index_of = |interior: Option<&Rc<Interior>>| {
+            interior.map_or(1.0, |interior| interior.refraction_index.at(wavelength))
+        };
+        let (eta_near, eta_far) = (index_of(near), index_of(far));
+        let etai_over_etat = eta_near / eta_far;
         let unit_direction = ray_in.direction().unit_vector();
         let cos_theta = Self::ffmin(Vector::dot(&-&unit_direction, &hit_record.normal), 1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-        let reflect_prob = Self::schlick(cos_theta, etai_over_etat);
-        if etai_over_etat * sin_theta > 1.0 || rand::thread_rng().gen::<f64>() < reflect_prob {
+        let mut rng = rand::thread_rng();
+        let (reflect, attenuation) = if etai_over_etat * sin_theta > 1.0 {
+            (true, attenuation)
+        } else if let Some(film) = &self.thin_film {
+            // Reflect in proportion to the mean reflectance, weighting each color by how far its
+            // own reflectance differs.
+            let reflectance = match ray_in.wavelengths() {
+                Some(_) => {
+                    let reflectance =
+                        film.reflectance(cos_theta, eta_near, eta_far, 0.0, wavelength);
+                    Vector::new(reflectance, reflectance, reflectance)
+                }
+                None => film.reflectance_rgb(
+                    cos_theta,
+                    eta_near,
+                    &Vector::new(eta_far, eta_far, eta_far),
+                    &Vector::empty(),
+                ),
+            };
+            let reflect_prob = (reflectance.x() + reflectance.y() + reflectance.z()) / 3.0;
+            if rng.gen::<f64>() < reflect_prob {
+                (true, reflectance / reflect_prob)
+            } else {
+                (false, (attenuation - reflectance) / (1.0 - reflect_prob))
+            }
+        } else {
+            let reflect_prob = Self::schlick(cos_theta, etai_over_etat);
+            (rng.gen::<f64>() < reflect_prob, attenuation)
+        };
+        let mut scattered = if reflect {
             let reflected = Self::reflect(&unit_direction, &hit_record.normal);
-            let scattered = Ray::new(&hit_record.p, &reflected);
-            (true, attenuation, scattered)
+            ray_in.spawn(&hit_record.p, &reflected)
         } else {
             let refracted = Self::refract(&unit_direction, &hit_record.normal, etai_over_etat);
-            let scattered = Ray::new(&hit_record.p, &refracted);
-            (true, attenuation, scattered)
+            beyond.spawn(&hit_record.p, &refracted)
+        };
+        // Each wavelength bends by a different amount, so only the hero can follow this path.
+        let is_dispersive = |interior: Option<&Rc<Interior>>| {
+            interior.is_some_and(|interior| interior.refraction_index.is_dispersive())
+        };
+        // Likewise, a film's reflectance is only found for the hero wavelength.
+        if is_dispersive(near) || is_dispersive(far) || self.thin_film.is_some() {
+            scattered.terminate_secondary_wavelengths();
         }
+        (true, attenuation, scattered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn constant_index_ignores_wavelength() {
+        let index = RefractionIndex::Constant(1.5);
+        assert_eq!(1.5, index.at(400.0));
+        assert_eq!(1.5, index.at(700.0));
+        assert!(!index.is_dispersive());
+    }
+
+    #[test]
+    fn cauchy_index_decreases_with_wavelength() {
+        let index = &Dielectric::cauchy(1.5, 0.004).interior.refraction_index;
+        assert!(approx_eq!(f64, 1.5 + 0.004 / 0.25, index.at(500.0)));
+        assert!(index.at(400.0) > index.at(700.0));
+        assert!(index.is_dispersive());
+    }
+
+    #[test]
+    fn sellmeier_index_matches_published_glass_data() {
+        let bk7 = &Dielectric::crown_glass().interior.refraction_index;
+        assert!((bk7.at(REFERENCE_WAVELENGTH) - 1.5168).abs() < 1e-4);
+        let sf11 = &Dielectric::flint_glass().interior.refraction_index;
+        assert!((sf11.at(REFERENCE_WAVELENGTH) - 1.7847).abs() < 1e-4);
+        assert!(sf11.at(450.0) > sf11.at(650.0));
+    }
+
+    #[test]
+    fn transmittance_matches_color_at_reference_distance() {
+        let glass = Dielectric::new(1.5).with_transmittance(&Vector::new(0.8, 0.5, 0.1), 2.0);
+        let transmittance = glass.interior.transmittance(2.0);
+        assert!(approx_eq!(f64, 0.8, transmittance.x(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.5, transmittance.y(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.1, transmittance.z(), epsilon = 1e-12));
+        let transmittance = glass.interior.transmittance(4.0);
+        assert!(approx_eq!(f64, 0.25, transmittance.y(), epsilon = 1e-12));
+    }
+
+    #[test]
+    fn clear_interiors_do_not_absorb() {
+        let glass = Dielectric::new(1.5);
+        assert_eq!(1.0, glass.interior.transmittance(100.0).x());
+    }
+
+    #[test]
+    fn highest_priority_interior_is_current() {
+        let glass = Dielectric::new(1.5).with_priority(2).interior;
+        let liquid = Dielectric::new(1.33).with_priority(1).interior;
+        let interiors = vec![glass.clone(), liquid.clone()];
+        assert!(Rc::ptr_eq(&glass, Interior::current(&interiors).unwrap()));
+        assert!(Interior::current(&[]).is_none());
+    }
+
+    #[test]
+    fn latest_interior_wins_among_equal_priorities() {
+        let outer = Dielectric::new(1.5).interior;
+        let inner = Dielectric::new(1.33).interior;
+        let interiors = vec![outer, inner.clone()];
+        assert!(Rc::ptr_eq(&inner, Interior::current(&interiors).unwrap()));
     }
 }
@@ -0,0 +1,45 @@
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A surface that gives off light of one color from its front face, evenly in every direction,
+/// and reflects none.
+pub struct DiffuseLight {
+    emit: Vector,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: &Vector) -> DiffuseLight {
+        DiffuseLight { emit: emit.clone() }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, ray_in: &Ray, _hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        (false, Vector::empty(), ray_in.clone())
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Vector {
+        if hit_record.front_face {
+            self.emit.clone()
+        } else {
+            Vector::empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_from_the_front_face_only() {
+        let light = DiffuseLight::new(&Vector::new(4.0, 2.0, 1.0));
+        let mut hit_record = HitRecord::empty();
+        hit_record.front_face = true;
+        assert_eq!(2.0, light.emitted(&hit_record).y());
+        hit_record.front_face = false;
+        assert_eq!(0.0, light.emitted(&hit_record).y());
+    }
+}
@@ -0,0 +1,118 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A flat disk facing up the y axis, or an annulus if it has a hole in the middle. `u` runs around
+/// the disk and `v` runs in from the outer edge to the inner one.
+pub struct Disk {
+    center: Vector,
+    inner_radius: f64,
+    outer_radius: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: &Vector, radius: f64, material: Rc<dyn Material>) -> Disk {
+        Self::annulus(center, 0.0, radius, material)
+    }
+
+    pub fn annulus(
+        center: &Vector,
+        inner_radius: f64,
+        outer_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Disk {
+        Disk {
+            center: center.clone(),
+            inner_radius,
+            outer_radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let direction = ray.direction();
+        if direction.y().abs() < 1e-12 {
+            return (false, HitRecord::empty());
+        }
+        let t = (self.center.y() - ray.origin().y()) / direction.y();
+        if t <= t_min || t >= t_max {
+            return (false, HitRecord::empty());
+        }
+        let p = ray.at(t);
+        let (x, z) = (p.x() - self.center.x(), p.z() - self.center.z());
+        let distance = (x * x + z * z).sqrt();
+        if distance > self.outer_radius || distance < self.inner_radius {
+            return (false, HitRecord::empty());
+        }
+
+        let phi = (-z).atan2(x).rem_euclid(2.0 * PI);
+        let width = self.outer_radius - self.inner_radius;
+        let radial = if distance > 0.0 {
+            Vector::new(x, 0.0, z) / distance
+        } else {
+            Vector::new(1.0, 0.0, 0.0)
+        };
+        let mut hit_record = HitRecord {
+            t,
+            u: phi / (2.0 * PI),
+            v: (self.outer_radius - distance) / width,
+            p,
+            dpdu: 2.0 * PI * Vector::new(z, 0.0, -x),
+            dpdv: -width * radial,
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &Vector::new(0.0, 1.0, 0.0));
+        if !hit_record.is_opaque() {
+            return (false, HitRecord::empty());
+        }
+        (true, hit_record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vector::new(self.outer_radius, 0.0, self.outer_radius);
+        Some(Aabb::new(&(&self.center - &extent), &(&self.center + &extent)).pad(1e-4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    #[test]
+    fn annulus_lets_rays_through_its_hole() {
+        let washer = Disk::annulus(
+            &Vector::new(0.0, 1.0, 0.0),
+            0.5,
+            1.0,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        );
+        let down_from = |x: f64, z: f64| {
+            let ray = Ray::new(&Vector::new(x, 3.0, z), &Vector::new(0.0, -1.0, 0.0));
+            washer.hit(&ray, 0.001, 10.0)
+        };
+        assert!(!down_from(0.2, 0.2).0);
+        assert!(!down_from(1.2, 0.0).0);
+        let (hit, hit_record) = down_from(0.0, -0.75);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.0, hit_record.t));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 0.25, hit_record.u));
+        assert!(approx_eq!(f64, 0.5, hit_record.v));
+        // The derivatives are oriented so that their cross product faces up.
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(cross.y() > 0.0);
+    }
+}
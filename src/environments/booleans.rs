@@ -0,0 +1,150 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::csg::Csg;
+use crate::cuboid::Cuboid;
+use crate::cylinder::Cylinder;
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::metal::Metal;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// Solids built from boolean operations: the classic rounded cube with three holes drilled
+/// through it, a glass lens, a ball with a bite taken out and a capsule welded from three parts.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 4.0, 11.0),
+        lookat: Vector::new(0.0, 0.9, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 11.5,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// A capped cylinder of the given radius through `center`, running along the y axis and then
+/// turned by `rotation`.
+fn rod(
+    center: &Vector,
+    radius: f64,
+    length: f64,
+    rotation: &Transform,
+    material: Rc<dyn Material>,
+) -> Rc<dyn Hittable> {
+    let cylinder = Rc::new(
+        Cylinder::new(
+            &Vector::new(0.0, -length / 2.0, 0.0),
+            radius,
+            length,
+            material,
+        )
+        .with_caps(),
+    );
+    Rc::new(Transformed::new(
+        cylinder,
+        rotation.then(&Transform::translation(center)),
+    ))
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    // A cube rounded off by a sphere, with holes drilled along all three axes.
+    let center = Vector::new(-2.8, 1.0, 0.0);
+    let body: Rc<dyn Hittable> = Rc::new(Csg::intersection(
+        Rc::new(Cuboid::new(
+            &(&center - Vector::new(0.9, 0.9, 0.9)),
+            &(&center + Vector::new(0.9, 0.9, 0.9)),
+            Rc::new(Lambertian::new(&Vector::new(0.8, 0.3, 0.1))),
+        )),
+        Rc::new(Sphere::new(
+            &center,
+            1.2,
+            Rc::new(Lambertian::new(&Vector::new(0.9, 0.7, 0.2))),
+        )),
+    ));
+    let drill = Rc::new(Lambertian::new(&Vector::new(0.2, 0.5, 0.3)));
+    let axes = [
+        Transform::identity(),
+        Transform::rotation(&Vector::new(1.0, 0.0, 0.0), 90.0),
+        Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 90.0),
+    ];
+    let holes = axes
+        .iter()
+        .map(|rotation| rod(&center, 0.5, 3.0, rotation, drill.clone()))
+        .reduce(|a, b| Rc::new(Csg::union(a, b)))
+        .unwrap();
+    world.add(Box::new(Csg::difference(body, holes)));
+
+    // Where two spheres overlap.
+    let glass = Rc::new(Dielectric::new(1.5));
+    world.add(Box::new(Csg::intersection(
+        Rc::new(Sphere::new(
+            &Vector::new(-0.6, 1.0, 0.5),
+            1.2,
+            glass.clone(),
+        )),
+        Rc::new(Sphere::new(&Vector::new(0.6, 1.0, 0.5), 1.2, glass)),
+    )));
+
+    world.add(Box::new(Csg::difference(
+        Rc::new(Sphere::new(
+            &Vector::new(1.6, 0.8, -0.6),
+            0.8,
+            Rc::new(Metal::new(&Vector::new(0.8, 0.8, 0.85), 0.05)),
+        )),
+        Rc::new(Sphere::new(
+            &Vector::new(1.9, 1.3, 0.0),
+            0.6,
+            Rc::new(Lambertian::new(&Vector::new(0.7, 0.1, 0.1))),
+        )),
+    )));
+
+    // Two hemispherical ends welded onto a rod, lying on its side.
+    let red = Rc::new(Lambertian::new(&Vector::new(0.7, 0.1, 0.1)));
+    let lying = Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 90.0)
+        .then(&Transform::rotation(&Vector::new(0.0, 1.0, 0.0), -20.0));
+    let middle = Vector::new(3.2, 0.4, 1.2);
+    let ends = lying.vector(&Vector::new(0.0, 0.8, 0.0));
+    world.add(Box::new(Csg::union(
+        Rc::new(Csg::union(
+            rod(&middle, 0.4, 1.6, &lying, red.clone()),
+            Rc::new(Sphere::new(&(&middle + &ends), 0.4, red.clone())),
+        )),
+        Rc::new(Sphere::new(&(&middle - &ends), 0.4, red)),
+    )));
+    world
+}
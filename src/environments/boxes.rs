@@ -0,0 +1,94 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::cuboid::Cuboid;
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::plane::Plane;
+use crate::quad::Quad;
+use crate::scenery::Scenery;
+use crate::textures::checker::Checker;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::uv_transform::UvTransform;
+use crate::vector::Vector;
+
+/// Flat shapes: boxes of several materials and a checkered panel, standing on a tiled floor.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(1.0, 3.5, 12.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 12.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    let tiles = Checker::new(
+        Rc::new(SolidColor::uniform(0.8)),
+        Rc::new(SolidColor::uniform(0.3)),
+    );
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::textured(Rc::new(tiles))),
+    )));
+
+    world.add(Box::new(Cuboid::new(
+        &Vector::new(-3.2, 0.0, -0.8),
+        &Vector::new(-1.6, 1.6, 0.8),
+        Rc::new(Lambertian::new(&Vector::new(0.8, 0.3, 0.1))),
+    )));
+    world.add(Box::new(Cuboid::new(
+        &Vector::new(-0.6, 0.0, -0.6),
+        &Vector::new(0.6, 2.4, 0.6),
+        Rc::new(Metal::new(&Vector::new(0.8, 0.8, 0.85), 0.05)),
+    )));
+    world.add(Box::new(Cuboid::new(
+        &Vector::new(1.6, 0.0, -0.8),
+        &Vector::new(3.2, 1.6, 0.8),
+        Rc::new(Dielectric::new(1.5)),
+    )));
+
+    // A panel behind the boxes, checkered through its texture coordinates.
+    let checks = UvTransform::new(
+        Rc::new(Checker::new(
+            Rc::new(SolidColor::new(&Vector::new(0.1, 0.3, 0.8))),
+            Rc::new(SolidColor::uniform(0.9)),
+        )),
+        (8.0, 4.0),
+        (0.0, 0.0),
+    );
+    world.add(Box::new(Quad::new(
+        &Vector::new(-4.0, 0.0, -3.0),
+        &Vector::new(8.0, 0.0, 0.0),
+        &Vector::new(0.0, 4.0, 0.0),
+        Rc::new(Lambertian::textured(Rc::new(checks))),
+    )));
+    world
+}
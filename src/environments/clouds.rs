@@ -0,0 +1,100 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::camera::{Camera, CameraConfig};
+use crate::density_grid::DensityGrid;
+use crate::diffuse_light::DiffuseLight;
+use crate::environments::environment::{read_or_exit, Environment, Image};
+use crate::grid_medium::GridMedium;
+use crate::henyey_greenstein::HenyeyGreenstein;
+use crate::io::read_density_grid;
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Renders a density grid loaded from `grid_path`, or a procedural cloud if none is given, under
+/// a sun that casts the cloud's shadow on the ground.
+pub fn get_environment(grid_path: Option<&Path>) -> Environment {
+    let image = make_image();
+    let grid = match grid_path {
+        Some(path) => read_or_exit(read_density_grid(path), path),
+        None => make_cloud(),
+    };
+    let mut lights = Scenery::empty();
+    lights.add(Box::new(make_sun()));
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(grid),
+        lights: Some(lights),
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 8.0),
+        lookat: Vector::new(0.0, 1.5, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 40.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 8.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// A few overlapping blobs with soft edges.
+fn make_cloud() -> DensityGrid {
+    let blobs = [
+        (Vector::new(0.5, 0.45, 0.5), 0.3),
+        (Vector::new(0.3, 0.4, 0.45), 0.2),
+        (Vector::new(0.7, 0.4, 0.55), 0.22),
+        (Vector::new(0.55, 0.6, 0.5), 0.18),
+    ];
+    DensityGrid::from_fn(64, 64, 64, |p| {
+        blobs
+            .iter()
+            .map(|(center, radius)| {
+                let falloff = 1.0 - (p - center).length() / radius;
+                falloff.max(0.0)
+            })
+            .fold(0.0, f64::max)
+    })
+}
+
+/// A small, bright ball high up and off to the side.
+fn make_sun() -> Sphere {
+    Sphere::new(
+        &Vector::new(30.0, 60.0, 20.0),
+        5.0,
+        Rc::new(DiffuseLight::new(&Vector::new(150.0, 140.0, 120.0))),
+    )
+}
+
+fn make_world(grid: DensityGrid) -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(make_sun()));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Box::new(GridMedium::new(
+        Aabb::new(&Vector::new(-2.5, 0.0, -2.5), &Vector::new(2.5, 3.0, 2.5)),
+        grid,
+        8.0,
+        Rc::new(HenyeyGreenstein::new(&Vector::new(0.95, 0.95, 0.95), 0.6)),
+    )));
+    world
+}
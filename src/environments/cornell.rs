@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::conductor::Conductor;
+use crate::cuboid::Cuboid;
+use crate::diffuse_light::DiffuseLight;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::oren_nayar::OrenNayar;
+use crate::principled::{Principled, PrincipledConfig};
+use crate::quad::Quad;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::solid_color::SolidColor;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// A Cornell box lit by the small light in its ceiling, which is sampled directly: a tall block of
+/// rough clay, a gold ball and a clearcoated plastic ball.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    let light: Rc<dyn Material> = Rc::new(DiffuseLight::new(&Vector::new(15.0, 15.0, 15.0)));
+    let mut lights = Scenery::empty();
+    lights.add(Box::new(ceiling_light(light.clone())));
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(light),
+        lights: Some(lights),
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 600,
+        height: 600,
+        supersampling_ratio: 100,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(278.0, 278.0, -800.0),
+        lookat: Vector::new(278.0, 278.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 40.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 800.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// The light in the middle of the ceiling, facing down.
+fn ceiling_light(material: Rc<dyn Material>) -> Quad {
+    Quad::new(
+        &Vector::new(343.0, 554.0, 332.0),
+        &Vector::new(-130.0, 0.0, 0.0),
+        &Vector::new(0.0, 0.0, -105.0),
+        material,
+    )
+}
+
+fn make_world(light: Rc<dyn Material>) -> Scenery {
+    let mut world = Scenery::empty();
+    let red = Rc::new(Lambertian::new(&Vector::new(0.65, 0.05, 0.05)));
+    let white = Rc::new(Lambertian::new(&Vector::new(0.73, 0.73, 0.73)));
+    let green = Rc::new(Lambertian::new(&Vector::new(0.12, 0.45, 0.15)));
+    let walls = [
+        (
+            Vector::new(555.0, 0.0, 0.0),
+            Vector::new(0.0, 555.0, 0.0),
+            Vector::new(0.0, 0.0, 555.0),
+            green,
+        ),
+        (
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(0.0, 555.0, 0.0),
+            Vector::new(0.0, 0.0, 555.0),
+            red,
+        ),
+        (
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(555.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, 555.0),
+            white.clone(),
+        ),
+        (
+            Vector::new(555.0, 555.0, 555.0),
+            Vector::new(-555.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, -555.0),
+            white.clone(),
+        ),
+        (
+            Vector::new(0.0, 0.0, 555.0),
+            Vector::new(555.0, 0.0, 0.0),
+            Vector::new(0.0, 555.0, 0.0),
+            white,
+        ),
+    ];
+    for (q, u, v, material) in walls.iter() {
+        world.add(Box::new(Quad::new(q, u, v, material.clone())));
+    }
+    world.add(Box::new(ceiling_light(light)));
+
+    let block = Cuboid::new(
+        &Vector::empty(),
+        &Vector::new(165.0, 330.0, 165.0),
+        Rc::new(OrenNayar::new(&Vector::new(0.73, 0.6, 0.5), 30.0)),
+    );
+    let placement = Transform::rotation(&Vector::new(0.0, 1.0, 0.0), 15.0)
+        .then(&Transform::translation(&Vector::new(265.0, 0.0, 295.0)));
+    world.add(Box::new(Transformed::new(Rc::new(block), placement)));
+
+    world.add(Box::new(Sphere::new(
+        &Vector::new(180.0, 90.0, 190.0),
+        90.0,
+        Rc::new(Conductor::gold(0.3)),
+    )));
+    let plastic = PrincipledConfig {
+        base_color: Rc::new(SolidColor::new(&Vector::new(0.1, 0.2, 0.6))),
+        roughness: Rc::new(SolidColor::uniform(0.4)),
+        clearcoat: Rc::new(SolidColor::uniform(1.0)),
+        ..Default::default()
+    };
+    world.add(Box::new(Sphere::new(
+        &Vector::new(420.0, 60.0, 140.0),
+        60.0,
+        Rc::new(Principled::new(plastic)),
+    )));
+    world
+}
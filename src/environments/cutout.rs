@@ -0,0 +1,109 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::cutout::{AlphaMode, Cutout};
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::checker::Checker;
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::turbulence::Turbulence;
+use crate::textures::uv_transform::UvTransform;
+use crate::vector::Vector;
+
+/// Balls with holes cut in them: a lattice cut by a threshold, eaten-away paper cut from noise,
+/// and gauze that is half there.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.5, 10.0),
+        lookat: Vector::new(0.0, 0.9, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    let lattice = Checker::new(
+        Rc::new(SolidColor::uniform(1.0)),
+        Rc::new(SolidColor::uniform(0.0)),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.4, 1.0, 0.0),
+        1.0,
+        Rc::new(Cutout::new(
+            Rc::new(Lambertian::new(&Vector::new(0.8, 0.3, 0.1))),
+            Rc::new(UvTransform::new(Rc::new(lattice), (16.0, 8.0), (0.0, 0.0))),
+            AlphaMode::Threshold(0.5),
+        )),
+    )));
+    // A small ball inside shows through the holes.
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.4, 1.0, 0.0),
+        0.4,
+        Rc::new(Lambertian::new(&Vector::new(0.1, 0.3, 0.8))),
+    )));
+
+    let decay = Turbulence::new(
+        11,
+        2.0,
+        5,
+        ColorRamp::between(&Vector::new(0.0, 0.0, 0.0), &Vector::new(1.0, 1.0, 1.0)),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Cutout::new(
+            Rc::new(Lambertian::new(&Vector::new(0.9, 0.85, 0.7))),
+            Rc::new(decay),
+            AlphaMode::Threshold(0.15),
+        )),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.4, 1.0, 0.0),
+        1.0,
+        Rc::new(Cutout::new(
+            Rc::new(Lambertian::new(&Vector::new(0.9, 0.9, 0.9))),
+            Rc::new(SolidColor::uniform(0.5)),
+            AlphaMode::Stochastic,
+        )),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.4, 1.0, 0.0),
+        0.4,
+        Rc::new(Lambertian::new(&Vector::new(0.8, 0.1, 0.1))),
+    )));
+    world
+}
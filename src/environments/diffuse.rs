@@ -0,0 +1,67 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::oren_nayar::OrenNayar;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Clay balls of increasing roughness on rough concrete, seen from a low angle. The leftmost ball
+/// is Lambertian.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 1.2, 10.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(OrenNayar::new(&Vector::new(0.5, 0.5, 0.5), 30.0)),
+    )));
+
+    let clay = Vector::new(0.7, 0.4, 0.25);
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-3.3, 0.9, 0.0),
+        0.9,
+        Rc::new(Lambertian::new(&clay)),
+    )));
+    for (i, &sigma) in [20.0, 40.0, 60.0].iter().enumerate() {
+        world.add(Box::new(Sphere::new(
+            &Vector::new(-1.1 + 2.2 * i as f64, 0.9, 0.0),
+            0.9,
+            Rc::new(OrenNayar::new(&clay, sigma)),
+        )));
+    }
+    world
+}
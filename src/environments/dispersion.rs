@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Dispersive glass balls, best rendered with `--spectral`.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 100,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 1.5, 7.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 35.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 7.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.8, 0.8, 0.8))),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-1.3, 0.8, 0.0),
+        0.8,
+        Rc::new(Dielectric::crown_glass()),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(1.3, 0.8, 0.0),
+        0.8,
+        Rc::new(Dielectric::flint_glass()),
+    )));
+
+    // A row of colored balls behind the glass to show off the dispersion.
+    let colors = [
+        Vector::new(0.8, 0.1, 0.1),
+        Vector::new(0.8, 0.6, 0.1),
+        Vector::new(0.1, 0.7, 0.1),
+        Vector::new(0.1, 0.3, 0.8),
+        Vector::new(0.5, 0.1, 0.7),
+    ];
+    for (i, color) in colors.iter().enumerate() {
+        world.add(Box::new(Sphere::new(
+            &Vector::new(-3.0 + 1.5 * i as f64, 0.4, -3.0),
+            0.4,
+            Rc::new(Lambertian::new(color)),
+        )));
+    }
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 0.5, 2.0),
+        0.5,
+        Rc::new(Metal::new(&Vector::new(0.9, 0.9, 0.9), 0.0)),
+    )));
+    world
+}
@@ -1,10 +1,19 @@
+use std::io::Error;
+use std::path::Path;
+use std::process;
+
 use crate::camera::Camera;
 use crate::scenery::Scenery;
+use crate::vector::Vector;
 
 pub struct Environment {
     pub scenery: Scenery,
+    /// The objects that emit light, sampled directly from every surface that can be evaluated.
+    /// They are copies of emitters in the scenery, and only their shapes and emission are used.
+    pub lights: Option<Scenery>,
     pub camera: Camera,
     pub image: Image,
+    pub fog: Option<Fog>,
 }
 
 pub struct Image {
@@ -12,3 +21,18 @@ pub struct Image {
     pub height: i32,
     pub supersampling_ratio: i32,
 }
+
+/// A homogeneous medium filling all of space.
+pub struct Fog {
+    pub density: f64,
+    pub albedo: Vector,
+}
+
+/// Unwraps the result of reading the scene file at `path`, or reports why it could not be read
+/// and exits.
+pub fn read_or_exit<T>(result: Result<T, Error>, path: &Path) -> T {
+    result.unwrap_or_else(|error| {
+        eprintln!("Could not read {}: {}", path.display(), error);
+        process::exit(1);
+    })
+}
@@ -0,0 +1,137 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::sdf::operators::{Repeat, SmoothUnion, Subtraction, Translate, Twist};
+use crate::sdf::primitives::{Ball, Capsule, Mandelbulb, Ring, RoundedBox};
+use crate::sdf::Sdf;
+use crate::sdf_object::SdfObject;
+use crate::vector::Vector;
+
+/// Shapes traced through distance fields: a rounded box with a ball carved out, blobs melting
+/// together, a twisted column, a field of rings and a Mandelbulb.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 4.0, 12.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 12.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// Bounds reaching `half_size` around `center`, with a little room to spare.
+fn bounds(center: &Vector, half_size: &Vector) -> Aabb {
+    let margin = Vector::new(0.01, 0.01, 0.01);
+    Aabb::new(
+        &(center - half_size - &margin),
+        &(center + half_size + &margin),
+    )
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    let center = Vector::new(-3.6, 0.8, 0.0);
+    let carved = Subtraction::new(
+        Rc::new(RoundedBox::new(&Vector::new(0.8, 0.8, 0.8), 0.15)),
+        Rc::new(Ball::new(1.0)),
+    );
+    world.add(Box::new(SdfObject::new(
+        Rc::new(Translate::new(Rc::new(carved), &center)),
+        bounds(&center, &Vector::new(0.8, 0.8, 0.8)),
+        Rc::new(Lambertian::new(&Vector::new(0.8, 0.3, 0.1))),
+    )));
+
+    let center = Vector::new(-1.3, 0.9, 0.5);
+    let blobs: Rc<dyn Sdf> = Rc::new(SmoothUnion::new(
+        Rc::new(Translate::new(
+            Rc::new(Ball::new(0.6)),
+            &Vector::new(-0.35, -0.3, 0.0),
+        )),
+        Rc::new(Capsule::new(
+            &Vector::new(0.3, -0.3, 0.0),
+            &Vector::new(0.1, 0.6, 0.0),
+            0.3,
+        )),
+        0.5,
+    ));
+    world.add(Box::new(SdfObject::new(
+        Rc::new(Translate::new(blobs, &center)),
+        bounds(&center, &Vector::new(1.0, 0.9, 0.7)),
+        Rc::new(Dielectric::new(1.5)),
+    )));
+
+    // Twisting stretches distances by up to the twist rate times the distance from the axis.
+    let center = Vector::new(0.8, 1.2, 0.0);
+    let column = Twist::new(
+        Rc::new(RoundedBox::new(&Vector::new(0.4, 1.2, 0.4), 0.05)),
+        1.2,
+    );
+    world.add(Box::new(
+        SdfObject::new(
+            Rc::new(Translate::new(Rc::new(column), &center)),
+            bounds(&center, &Vector::new(0.6, 1.2, 0.6)),
+            Rc::new(Metal::new(&Vector::new(0.8, 0.8, 0.85), 0.1)),
+        )
+        .with_step_scale(0.6),
+    ));
+
+    let rings = Repeat::new(
+        Rc::new(Ring::new(0.15, 0.05)),
+        &Vector::new(0.45, 0.0, 0.45),
+    );
+    let lift = Vector::new(0.0, 0.05, 0.0);
+    world.add(Box::new(SdfObject::new(
+        Rc::new(Translate::new(Rc::new(rings), &lift)),
+        bounds(
+            &Vector::new(0.0, 0.05, 2.475),
+            &Vector::new(4.3, 0.05, 0.45),
+        ),
+        Rc::new(Lambertian::new(&Vector::new(0.2, 0.4, 0.8))),
+    )));
+
+    let center = Vector::new(3.4, 1.3, 0.0);
+    world.add(Box::new(
+        SdfObject::new(
+            Rc::new(Translate::new(Rc::new(Mandelbulb::new(8.0, 12)), &center)),
+            bounds(&center, &Vector::new(1.25, 1.25, 1.25)),
+            Rc::new(Lambertian::new(&Vector::new(0.7, 0.6, 0.4))),
+        )
+        .with_epsilon(1e-3),
+    ));
+    world
+}
@@ -0,0 +1,99 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::rough_dielectric::RoughDielectric;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::checker::Checker;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::uv_transform::UvTransform;
+use crate::vector::Vector;
+
+/// Glass of increasing roughness, rough ice, and a ball with frosted stripes.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 100,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.5, 10.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    // Colored balls behind the glass make the blur visible.
+    for i in 0..7 {
+        let hue = i as f64 / 6.0;
+        world.add(Box::new(Sphere::new(
+            &Vector::new(-4.5 + 1.5 * i as f64, 0.5, -3.5),
+            0.5,
+            Rc::new(Lambertian::new(&Vector::new(
+                0.9 - 0.8 * hue,
+                0.2 + 0.6 * (1.0 - (2.0 * hue - 1.0).abs()),
+                0.1 + 0.8 * hue,
+            ))),
+        )));
+    }
+
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-3.3, 0.8, 0.0),
+        0.8,
+        Rc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-1.1, 0.8, 0.0),
+        0.8,
+        Rc::new(RoughDielectric::new(1.5, 0.2)),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(1.1, 0.8, 0.0),
+        0.8,
+        Rc::new(RoughDielectric::new(1.31, 0.45)),
+    )));
+
+    let stripes = Checker::new(
+        Rc::new(SolidColor::new(&Vector::new(0.0, 0.0, 0.0))),
+        Rc::new(SolidColor::new(&Vector::new(0.6, 0.6, 0.6))),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(3.3, 0.8, 0.0),
+        0.8,
+        Rc::new(RoughDielectric::textured(
+            1.5,
+            Rc::new(UvTransform::new(Rc::new(stripes), (0.0, 10.0), (0.0, 0.0))),
+        )),
+    )));
+    world
+}
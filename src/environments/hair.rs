@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use rand::Rng;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::curve::Curve;
+use crate::environments::environment::{Environment, Image};
+use crate::hair::Hair;
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Fur and grass made of curves: two balls covered in strands of brown and blonde hair, and a
+/// tuft of grass blades shaded as thin tubes.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.5, 8.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 8.2,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// Covers a ball of the color of `skin` with `count` strands that stand out from it and droop
+/// under their own weight.
+fn furry_ball(
+    world: &mut Scenery,
+    center: &Vector,
+    radius: f64,
+    count: usize,
+    skin: &Vector,
+    hair: Rc<dyn Material>,
+) {
+    let mut rng = rand::thread_rng();
+    world.add(Box::new(Sphere::new(
+        center,
+        radius,
+        Rc::new(Lambertian::new(skin)),
+    )));
+    for _ in 0..count {
+        let direction = Vector::random_unit();
+        let root = center + radius * &direction;
+        let length = rng.gen_range(0.35, 0.5);
+        let droop = Vector::new(0.0, -0.4 * length, 0.0);
+        let points = [
+            root.clone(),
+            &root + (length / 3.0) * &direction,
+            &root + (2.0 * length / 3.0) * &direction + 0.5 * &droop,
+            &root + length * &direction + &droop,
+        ];
+        world.add(Box::new(Curve::ribbon(
+            points,
+            (0.012, 0.002),
+            hair.clone(),
+        )));
+    }
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    furry_ball(
+        &mut world,
+        &Vector::new(-1.6, 0.7, 0.0),
+        0.6,
+        800,
+        &Vector::new(0.15, 0.08, 0.04),
+        Rc::new(Hair::from_melanin(1.3, 0.0, 0.3, 0.3)),
+    );
+    furry_ball(
+        &mut world,
+        &Vector::new(0.2, 0.7, -0.4),
+        0.6,
+        800,
+        &Vector::new(0.6, 0.45, 0.3),
+        Rc::new(Hair::from_melanin(0.3, 0.1, 0.25, 0.3)),
+    );
+
+    // Blades of grass arching away from the middle of the tuft.
+    let mut rng = rand::thread_rng();
+    let grass = Rc::new(Lambertian::new(&Vector::new(0.2, 0.5, 0.1)));
+    let tuft = Vector::new(1.9, 0.0, 0.3);
+    for _ in 0..200 {
+        let angle = rng.gen_range(0.0, 2.0 * PI);
+        let spread = rng.gen_range(0.0, 0.3);
+        let outward = Vector::new(angle.cos(), 0.0, angle.sin());
+        let root = &tuft + spread * &outward;
+        let height = rng.gen_range(0.6, 1.3);
+        let lean = rng.gen_range(0.2, 0.6) * height;
+        let points = [
+            root.clone(),
+            &root + Vector::new(0.0, 0.5 * height, 0.0),
+            &root + Vector::new(0.0, height, 0.0) + (0.5 * lean) * &outward,
+            &root + Vector::new(0.0, 0.8 * height, 0.0) + lean * &outward,
+        ];
+        world.add(Box::new(Curve::cylinder(
+            points,
+            (0.04, 0.005),
+            grass.clone(),
+        )));
+    }
+    world
+}
@@ -0,0 +1,91 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::cuboid::Cuboid;
+use crate::environments::environment::{Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// A spiral staircase of one shared box, turned and lifted for each step, around a column of
+/// squashed and stretched copies of one ball.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 600,
+        height: 600,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(6.0, 5.0, 12.0),
+        lookat: Vector::new(0.0, 2.2, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 35.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 13.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    let up = Vector::new(0.0, 1.0, 0.0);
+    let step: Rc<dyn Hittable> = Rc::new(Cuboid::new(
+        &Vector::new(0.6, 0.0, -0.3),
+        &Vector::new(2.4, 0.15, 0.3),
+        Rc::new(Lambertian::new(&Vector::new(0.7, 0.45, 0.25))),
+    ));
+    for i in 0..24 {
+        let transform = Transform::rotation(&up, 25.0 * i as f64).then(&Transform::translation(
+            &Vector::new(0.0, 0.2 * i as f64, 0.0),
+        ));
+        world.add(Box::new(Transformed::new(step.clone(), transform)));
+    }
+
+    let ball: Rc<dyn Hittable> = Rc::new(Sphere::new(
+        &Vector::empty(),
+        1.0,
+        Rc::new(Metal::new(&Vector::new(0.8, 0.8, 0.85), 0.1)),
+    ));
+    for i in 0..5 {
+        let squash = 0.5 + 0.1 * i as f64;
+        let scaling = Transform::scaling(&Vector::new(squash, 0.5, squash)).unwrap();
+        let transform = scaling
+            .then(&Transform::rotation(
+                &Vector::new(1.0, 0.0, 0.0),
+                20.0 * i as f64,
+            ))
+            .then(&Transform::translation(&Vector::new(
+                0.0,
+                0.5 + i as f64,
+                0.0,
+            )));
+        world.add(Box::new(Transformed::new(ball.clone(), transform)));
+    }
+    world
+}
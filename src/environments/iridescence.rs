@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::conductor::Conductor;
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::thin_film::ThinFilm;
+use crate::vector::Vector;
+
+/// Soap bubbles of increasing film thickness in front, and metals tempered by oxide layers of
+/// increasing thickness behind. Renders best with `--spectral`.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 100,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.5, 10.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.1, 0.1, 0.12))),
+    )));
+
+    for (i, &thickness) in [250.0, 400.0, 550.0, 700.0].iter().enumerate() {
+        world.add(Box::new(Sphere::new(
+            &Vector::new(-3.3 + 2.2 * i as f64, 1.0, 1.0),
+            0.8,
+            Rc::new(Dielectric::new(1.0).with_thin_film(ThinFilm::new(thickness, 1.33))),
+        )));
+    }
+
+    // Steel, which absorbs enough light for its oxide's reflections to interfere visibly.
+    for (i, &thickness) in [60.0, 120.0, 180.0].iter().enumerate() {
+        let steel = Conductor::new(
+            &Vector::new(2.9, 2.9, 2.5),
+            &Vector::new(3.1, 2.9, 2.7),
+            0.1,
+        );
+        world.add(Box::new(Sphere::new(
+            &Vector::new(-2.2 + 2.2 * i as f64, 0.6, -2.5),
+            0.6,
+            Rc::new(steel.with_thin_film(ThinFilm::new(thickness, 2.4))),
+        )));
+    }
+    world
+}
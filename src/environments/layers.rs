@@ -0,0 +1,109 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::coated::Coated;
+use crate::conductor::Conductor;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::mix_material::MixMaterial;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::turbulence::Turbulence;
+use crate::textures::wood::Wood;
+use crate::vector::Vector;
+
+const SEED: u64 = 7;
+
+/// Layered and blended materials: a varnished wood floor, red car paint, dusty copper and
+/// copper under an amber lacquer.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 10.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    let wood = Wood::new(
+        SEED,
+        0.5,
+        4,
+        1.5,
+        ColorRamp::new(vec![
+            (0.0, Vector::new(0.45, 0.25, 0.1)),
+            (0.7, Vector::new(0.6, 0.4, 0.2)),
+            (1.0, Vector::new(0.35, 0.18, 0.07)),
+        ]),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Coated::new(
+            Rc::new(Lambertian::textured(Rc::new(wood))),
+            1.5,
+        )),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.2, 0.9, 0.0),
+        0.9,
+        Rc::new(Coated::new(
+            Rc::new(Lambertian::new(&Vector::new(0.6, 0.05, 0.05))),
+            1.5,
+        )),
+    )));
+
+    let dust = Turbulence::new(
+        SEED,
+        4.0,
+        5,
+        ColorRamp::between(&Vector::new(0.0, 0.0, 0.0), &Vector::new(1.0, 1.0, 1.0)),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 0.9, 0.0),
+        0.9,
+        Rc::new(MixMaterial::textured(
+            Rc::new(Conductor::copper(0.2)),
+            Rc::new(Lambertian::new(&Vector::new(0.55, 0.5, 0.45))),
+            Rc::new(dust),
+        )),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.2, 0.9, 0.0),
+        0.9,
+        Rc::new(Coated::tinted(
+            Rc::new(Conductor::copper(0.4)),
+            1.5,
+            &Vector::new(0.95, 0.75, 0.4),
+        )),
+    )));
+    world
+}
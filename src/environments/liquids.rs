@@ -0,0 +1,88 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Absorbing glass, and a glass ball filled with colored liquid holding an air bubble.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 100,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.5, 10.0),
+        lookat: Vector::new(0.0, 0.9, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 25.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    // Green bottle glass, losing most of its red and blue over the width of the ball.
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.2, 0.9, 0.0),
+        0.9,
+        Rc::new(Dielectric::new(1.5).with_transmittance(&Vector::new(0.3, 0.8, 0.4), 1.8)),
+    )));
+
+    // A glass shell filled with liquid: the liquid outranks the glass, and the air bubble
+    // outranks the liquid, so each boundary refracts between the right pair of media.
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 0.9, 0.0),
+        0.9,
+        Rc::new(Dielectric::new(1.5).with_priority(1)),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 0.9, 0.0),
+        0.8,
+        Rc::new(
+            Dielectric::new(1.33)
+                .with_transmittance(&Vector::new(0.9, 0.3, 0.2), 1.6)
+                .with_priority(2),
+        ),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.25, 1.2, 0.3),
+        0.25,
+        Rc::new(Dielectric::new(1.0).with_priority(3)),
+    )));
+
+    // The same liquid without its glass, for comparison.
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.2, 0.9, 0.0),
+        0.9,
+        Rc::new(Dielectric::new(1.33).with_transmittance(&Vector::new(0.9, 0.3, 0.2), 1.6)),
+    )));
+    world
+}
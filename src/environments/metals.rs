@@ -0,0 +1,71 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::conductor::Conductor;
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// Measured metals, each shown polished in the front row and rough in the back row.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 3.0, 10.0),
+        lookat: Vector::new(0.0, 0.7, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.4, 0.4, 0.4))),
+    )));
+
+    let metals: [fn(f64) -> Conductor; 4] = [
+        Conductor::gold,
+        Conductor::copper,
+        Conductor::aluminium,
+        Conductor::silver,
+    ];
+    for (i, metal) in metals.iter().enumerate() {
+        let x = -3.3 + 2.2 * i as f64;
+        for &(z, roughness) in &[(1.0, 0.05), (-1.5, 0.4)] {
+            let material: Rc<dyn Material> = Rc::new(metal(roughness));
+            world.add(Box::new(Sphere::new(
+                &Vector::new(x, 0.8, z),
+                0.8,
+                material,
+            )));
+        }
+    }
+    world
+}
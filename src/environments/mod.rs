@@ -1,2 +1,27 @@
+pub mod booleans;
+pub mod boxes;
+pub mod clouds;
+pub mod cornell;
+pub mod cutout;
+pub mod diffuse;
+pub mod dispersion;
 pub mod environment;
+pub mod fields;
+pub mod glass;
+pub mod hair;
+pub mod instances;
+pub mod iridescence;
+pub mod layers;
+pub mod liquids;
+pub mod metals;
+pub mod motion;
+pub mod noise;
+pub mod particles;
+pub mod principled;
+pub mod quadrics;
+pub mod rings;
 pub mod spheres;
+pub mod subsurface;
+pub mod terrain;
+pub mod textures;
+pub mod volumes;
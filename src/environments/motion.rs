@@ -0,0 +1,83 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::cuboid::Cuboid;
+use crate::environments::environment::{Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::moving_sphere::MovingSphere;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// Balls bouncing up off the ground and a spinning box, blurred by an open shutter.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 3.0, 12.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 12.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config).with_shutter(0.0, 1.0)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    let mut rng = rand::thread_rng();
+    for i in 0..7 {
+        let start = Vector::new(-4.5 + 1.5 * i as f64, 0.4, rng.gen_range(-2.0, 1.0));
+        let end = &start + Vector::new(0.0, rng.gen_range(0.2, 1.2), 0.0);
+        let albedo = Vector::random_range(&mut rng, 0.2, 0.9);
+        world.add(Box::new(MovingSphere::new(
+            &start,
+            &end,
+            0.4,
+            Rc::new(Lambertian::new(&albedo)),
+        )));
+    }
+
+    let cube: Rc<dyn Hittable> = Rc::new(Cuboid::new(
+        &Vector::new(-0.6, -0.6, -0.6),
+        &Vector::new(0.6, 0.6, 0.6),
+        Rc::new(Metal::new(&Vector::new(0.8, 0.6, 0.2), 0.2)),
+    ));
+    let up = Vector::new(0.0, 1.0, 0.0);
+    let lifted = Transform::translation(&Vector::new(0.0, 2.6, -3.0));
+    world.add(Box::new(Transformed::moving(
+        cube,
+        lifted.clone(),
+        Transform::rotation(&up, 60.0).then(&lifted),
+    )));
+    world
+}
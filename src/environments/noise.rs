@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use crate::bump_map::BumpMap;
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::marble::Marble;
+use crate::textures::turbulence::Turbulence;
+use crate::textures::wood::Wood;
+use crate::vector::Vector;
+
+const SEED: u64 = 42;
+
+/// Balls with procedural marble, wood and turbulence textures.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 9.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 9.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::textured(Rc::new(Turbulence::new(
+            SEED,
+            1.0,
+            5,
+            ColorRamp::between(&Vector::new(0.3, 0.3, 0.3), &Vector::new(0.6, 0.6, 0.55)),
+        )))),
+    )));
+
+    let marble = Marble::new(
+        SEED,
+        4.0,
+        7,
+        10.0,
+        ColorRamp::new(vec![
+            (0.0, Vector::new(0.2, 0.2, 0.25)),
+            (0.3, Vector::new(0.7, 0.7, 0.7)),
+            (1.0, Vector::new(0.95, 0.95, 0.9)),
+        ]),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::textured(Rc::new(marble))),
+    )));
+
+    let wood = Wood::new(
+        SEED,
+        8.0,
+        4,
+        1.5,
+        ColorRamp::new(vec![
+            (0.0, Vector::new(0.45, 0.25, 0.1)),
+            (0.7, Vector::new(0.6, 0.4, 0.2)),
+            (1.0, Vector::new(0.35, 0.18, 0.07)),
+        ]),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::textured(Rc::new(wood))),
+    )));
+
+    let clouds = Turbulence::new(
+        SEED + 1,
+        3.0,
+        7,
+        ColorRamp::between(&Vector::new(0.1, 0.2, 0.6), &Vector::new(0.9, 0.9, 0.9)),
+    );
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(BumpMap::new(
+            Rc::new(Lambertian::textured(Rc::new(clouds))),
+            Rc::new(Turbulence::new(
+                SEED + 1,
+                3.0,
+                7,
+                ColorRamp::between(&Vector::new(0.0, 0.0, 0.0), &Vector::new(1.0, 1.0, 1.0)),
+            )),
+            0.05,
+        )),
+    )));
+    world
+}
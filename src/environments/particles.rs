@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::rc::Rc;
+
+use rand::Rng;
+
+use crate::aabb::Aabb;
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{read_or_exit, Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::plane::Plane;
+use crate::point_cloud::{Point, PointCloud, PointShape};
+use crate::scenery::Scenery;
+use crate::vector::Vector;
+
+/// How many particles each of the procedural clouds has.
+const PARTICLES: usize = 100_000;
+
+/// Renders the points of the PLY file at `points_path` as disks, framed by the camera, or a
+/// fountain of spheres next to a spiral of disks if no file is given.
+pub fn get_environment(points_path: Option<&Path>) -> Environment {
+    let image = make_image();
+    let (camera, scenery) = match points_path {
+        Some(path) => {
+            let loaded =
+                PointCloud::load(path, 0.01, PointShape::Disk).and_then(|cloud| {
+                    match cloud.bounding_box() {
+                        Some(bounds) => Ok((cloud, bounds)),
+                        None => Err(Error::new(ErrorKind::InvalidData, "the file has no points")),
+                    }
+                });
+            let (cloud, bounds) = read_or_exit(loaded, path);
+            let camera = frame_camera(&image, &bounds);
+            let mut world = Scenery::empty();
+            world.add(Box::new(cloud));
+            (camera, world)
+        }
+        None => (make_camera(&image), make_world()),
+    };
+    Environment {
+        camera,
+        image,
+        scenery,
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 9.0),
+        lookat: Vector::new(0.0, 1.2, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 9.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// Looks at a loaded cloud with the given bounds from in front and a little above, from far enough to see all of it.
+fn frame_camera(image: &Image, bounds: &Aabb) -> Camera {
+    let center = 0.5 * (bounds.minimum() + bounds.maximum());
+    let size = (bounds.maximum() - bounds.minimum()).length();
+    let config = CameraConfig {
+        location: &center + size * Vector::new(0.0, 0.4, 1.6),
+        lookat: center,
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 40.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 1.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// Drops of water thrown up from a nozzle, caught at random moments of their flight and colored
+/// from blue to white by how fast they go.
+fn fountain() -> PointCloud {
+    let mut rng = rand::thread_rng();
+    let nozzle = Vector::new(-1.6, 0.0, 0.0);
+    let gravity = Vector::new(0.0, -9.8, 0.0);
+    let points = (0..PARTICLES)
+        .map(|_| {
+            let angle = rng.gen_range(0.0, 2.0 * PI);
+            let spread = rng.gen_range(0.0, 0.5);
+            let speed = rng.gen_range(5.0, 6.5);
+            let velocity =
+                speed * Vector::new(spread * angle.cos(), 1.0, spread * angle.sin()).unit_vector();
+            // Until the drop falls back to the ground.
+            let flight = 2.0 * velocity.y() / 9.8;
+            let t = rng.gen_range(0.0, flight);
+            let position = &nozzle + t * &velocity + (0.5 * t * t) * &gravity;
+            let fast = ((&velocity + t * &gravity).length() / 6.5).min(1.0);
+            let color = Vector::new(0.2 + 0.7 * fast, 0.4 + 0.5 * fast, 0.9);
+            Point::new(&position, rng.gen_range(0.008, 0.015), &color)
+        })
+        .collect();
+    PointCloud::new(points, PointShape::Sphere)
+}
+
+/// Stars along two spiral arms, tilted towards the camera, red in the middle and blue outside.
+fn spiral() -> PointCloud {
+    let mut rng = rand::thread_rng();
+    let center = Vector::new(1.6, 1.4, 0.0);
+    let (tilt_sin, tilt_cos) = (0.9_f64).sin_cos();
+    let points = (0..PARTICLES)
+        .map(|_| {
+            let distance: f64 = rng.gen_range(0.0_f64, 1.0).sqrt() * 1.3;
+            let arm = if rng.gen::<bool>() { 0.0 } else { PI };
+            let angle = arm + 4.0 * distance + rng.gen_range(-0.35, 0.35);
+            let (x, z) = (distance * angle.cos(), distance * angle.sin());
+            let y = rng.gen_range(-0.04, 0.04);
+            let position =
+                &center + Vector::new(x, y * tilt_cos - z * tilt_sin, y * tilt_sin + z * tilt_cos);
+            let outer = distance / 1.3;
+            let color = Vector::new(1.0 - 0.6 * outer, 0.6, 0.3 + 0.7 * outer);
+            Point::new(&position, 0.01, &color)
+        })
+        .collect();
+    PointCloud::new(points, PointShape::Disk)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Box::new(fountain()));
+    world.add(Box::new(spiral()));
+    world
+}
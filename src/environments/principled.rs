@@ -0,0 +1,134 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::principled::{Principled, PrincipledConfig};
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::checker::Checker;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::textures::uv_transform::UvTransform;
+use crate::vector::Vector;
+
+/// The principled material: a roughness sweep of plastic in the front row, the same sweep of
+/// metal in the middle row, and one ball for each of the remaining sliders at the back.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 500,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 4.0, 11.0),
+        lookat: Vector::new(0.0, 0.3, -1.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 35.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn uniform(value: f64) -> Rc<dyn Texture> {
+    Rc::new(SolidColor::uniform(value))
+}
+
+fn color(r: f64, g: f64, b: f64) -> Rc<dyn Texture> {
+    Rc::new(SolidColor::new(&Vector::new(r, g, b)))
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.4, 0.4, 0.4))),
+    )));
+
+    let mut add = |x: f64, z: f64, config: PrincipledConfig| {
+        world.add(Box::new(Sphere::new(
+            &Vector::new(x, 0.7, z),
+            0.7,
+            Rc::new(Principled::new(config)),
+        )));
+    };
+    for i in 0..5 {
+        let x = -3.6 + 1.8 * i as f64;
+        let roughness = 0.05 + 0.2 * i as f64;
+        add(
+            x,
+            1.5,
+            PrincipledConfig {
+                base_color: color(0.7, 0.1, 0.1),
+                roughness: uniform(roughness),
+                ..Default::default()
+            },
+        );
+        add(
+            x,
+            -0.5,
+            PrincipledConfig {
+                base_color: color(0.9, 0.7, 0.4),
+                metallic: uniform(1.0),
+                roughness: uniform(roughness),
+                ..Default::default()
+            },
+        );
+    }
+
+    let stripes = Checker::new(uniform(0.0), uniform(1.0));
+    let back = vec![
+        PrincipledConfig {
+            base_color: color(0.2, 0.2, 0.6),
+            roughness: uniform(0.9),
+            sheen: uniform(1.0),
+            ..Default::default()
+        },
+        PrincipledConfig {
+            base_color: color(0.1, 0.3, 0.1),
+            roughness: uniform(0.6),
+            clearcoat: uniform(1.0),
+            ..Default::default()
+        },
+        PrincipledConfig {
+            base_color: color(0.9, 0.95, 1.0),
+            roughness: uniform(0.1),
+            transmission: uniform(1.0),
+            ..Default::default()
+        },
+        PrincipledConfig {
+            base_color: color(0.9, 0.9, 0.9),
+            metallic: uniform(1.0),
+            roughness: uniform(0.4),
+            anisotropy: uniform(1.0),
+            ..Default::default()
+        },
+        PrincipledConfig {
+            base_color: color(0.8, 0.4, 0.1),
+            metallic: Rc::new(UvTransform::new(Rc::new(stripes), (0.0, 8.0), (0.0, 0.0))),
+            roughness: uniform(0.3),
+            ..Default::default()
+        },
+    ];
+    for (i, config) in back.into_iter().enumerate() {
+        add(-3.6 + 1.8 * i as f64, -2.5, config);
+    }
+    world
+}
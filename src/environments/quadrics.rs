@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::cone::Cone;
+use crate::cylinder::Cylinder;
+use crate::disk::Disk;
+use crate::environments::environment::{Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::paraboloid::Paraboloid;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::textures::checker::Checker;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::uv_transform::UvTransform;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// Machined parts: a capped bar stock cylinder, a cone, a polished dish, washers and a tube lying
+/// on its side.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 4.0, 11.0),
+        lookat: Vector::new(0.0, 0.8, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 11.5,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    // Stripes around the side show how `u` wraps.
+    let stripes = UvTransform::new(
+        Rc::new(Checker::new(
+            Rc::new(SolidColor::new(&Vector::new(0.8, 0.2, 0.1))),
+            Rc::new(SolidColor::uniform(0.9)),
+        )),
+        (12.0, 1.0),
+        (0.0, 0.0),
+    );
+    world.add(Box::new(
+        Cylinder::new(
+            &Vector::new(-3.0, 0.0, 0.0),
+            0.8,
+            1.8,
+            Rc::new(Lambertian::textured(Rc::new(stripes))),
+        )
+        .with_caps(),
+    ));
+
+    world.add(Box::new(
+        Cone::new(
+            &Vector::new(-0.8, 0.0, 0.5),
+            0.7,
+            1.6,
+            Rc::new(Lambertian::new(&Vector::new(0.2, 0.5, 0.8))),
+        )
+        .with_cap(),
+    ));
+
+    world.add(Box::new(Paraboloid::new(
+        &Vector::new(1.2, 0.0, -0.5),
+        1.2,
+        1.0,
+        Rc::new(Metal::new(&Vector::new(0.9, 0.8, 0.6), 0.02)),
+    )));
+
+    let steel = Rc::new(Metal::new(&Vector::new(0.7, 0.7, 0.75), 0.3));
+    for i in 0..3 {
+        world.add(Box::new(Disk::annulus(
+            &Vector::new(3.0 + 0.3 * i as f64, 0.001 + 0.1 * i as f64, 1.2),
+            0.25,
+            0.6,
+            steel.clone(),
+        )));
+    }
+
+    // An open tube lying on its side.
+    let tube: Rc<dyn Hittable> = Rc::new(Cylinder::new(
+        &Vector::empty(),
+        0.4,
+        2.0,
+        Rc::new(Lambertian::new(&Vector::new(0.3, 0.7, 0.3))),
+    ));
+    let lying = Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 90.0)
+        .then(&Transform::rotation(&Vector::new(0.0, 1.0, 0.0), 30.0))
+        .then(&Transform::translation(&Vector::new(0.5, 0.4, 2.2)));
+    world.add(Box::new(Transformed::new(tube, lying)));
+    world
+}
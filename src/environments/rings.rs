@@ -0,0 +1,87 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Image};
+use crate::hittable::Hittable;
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::torus::Torus;
+use crate::transformed::Transformed;
+use crate::vector::{Transform, Vector};
+
+/// A chain of linked gold rings held up beside a glass ring and a thin red hoop.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 5.0, 9.0),
+        lookat: Vector::new(0.0, 0.5, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 35.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Lambertian::new(&Vector::new(0.4, 0.4, 0.45))),
+    )));
+
+    // Alternate links stand upright, each threaded through its neighbors.
+    let link: Rc<dyn Hittable> = Rc::new(Torus::new(
+        &Vector::empty(),
+        0.6,
+        0.15,
+        Rc::new(Metal::new(&Vector::new(0.9, 0.7, 0.3), 0.05)),
+    ));
+    for i in 0..6 {
+        let x = -2.2 + 0.85 * i as f64;
+        let transform = if i % 2 == 0 {
+            Transform::translation(&Vector::new(x, 0.75, 1.0))
+        } else {
+            Transform::rotation(&Vector::new(1.0, 0.0, 0.0), 90.0)
+                .then(&Transform::translation(&Vector::new(x, 0.75, 1.0)))
+        };
+        world.add(Box::new(Transformed::new(link.clone(), transform)));
+    }
+
+    world.add(Box::new(Torus::new(
+        &Vector::new(-1.5, 0.4, -1.5),
+        1.0,
+        0.4,
+        Rc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(Torus::new(
+        &Vector::new(1.8, 0.08, -1.2),
+        1.1,
+        0.08,
+        Rc::new(Lambertian::new(&Vector::new(0.7, 0.1, 0.1))),
+    )));
+    world
+}
@@ -6,6 +6,7 @@ use crate::dielectric::Dielectric;
 use crate::environments::environment::{Environment, Image};
 use crate::lambertian::Lambertian;
 use crate::metal::Metal;
+use crate::plane::Plane;
 use crate::scenery::Scenery;
 use crate::sphere::Sphere;
 use crate::vector::Vector;
@@ -16,6 +17,8 @@ pub fn get_environment() -> Environment {
         camera: make_camera(&image),
         image,
         scenery: make_world(),
+        lights: None,
+        fog: None,
     }
 }
 
@@ -42,9 +45,9 @@ fn make_camera(image: &Image) -> Camera {
 
 fn make_world() -> Scenery {
     let mut world = Scenery::empty();
-    world.add(Box::new(Sphere::new(
-        &Vector::new(0.0, -1000.0, 0.0),
-        1000.0,
+    world.add(Box::new(Plane::new(
+        &Vector::empty(),
+        &Vector::new(0.0, 1.0, 0.0),
         Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
     )));
 
@@ -0,0 +1,90 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::subsurface::Subsurface;
+use crate::vector::Vector;
+
+/// Translucent wax, skin, jade and marble, next to a Lambertian ball for comparison.
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 10.0),
+        lookat: Vector::new(0.0, 0.7, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn ball(x: f64) -> Box<Sphere> {
+    Box::new(Sphere::new(
+        &Vector::new(x, 0.7, 0.0),
+        0.7,
+        Rc::new(Lambertian::new(&Vector::new(0.8, 0.8, 0.8))),
+    ))
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.4, 0.4, 0.4))),
+    )));
+
+    world.add(ball(-3.6));
+    world.add(Box::new(Subsurface::new(
+        ball(-1.8),
+        1.45,
+        &Vector::new(0.3, 0.2, 0.1),
+        &Vector::new(0.999, 0.98, 0.9),
+    )));
+    // Skin scatters red light much further than blue, and mostly forwards.
+    world.add(Box::new(
+        Subsurface::new(
+            ball(0.0),
+            1.4,
+            &Vector::new(0.37, 0.14, 0.07),
+            &Vector::new(0.995, 0.96, 0.9),
+        )
+        .with_anisotropy(0.8),
+    ));
+    world.add(Box::new(Subsurface::new(
+        ball(1.8),
+        1.6,
+        &Vector::new(0.6, 0.8, 0.6),
+        &Vector::new(0.85, 0.98, 0.9),
+    )));
+    world.add(Box::new(Subsurface::new(
+        ball(3.6),
+        1.5,
+        &Vector::new(0.05, 0.05, 0.05),
+        &Vector::new(0.999, 0.999, 0.999),
+    )));
+    world
+}
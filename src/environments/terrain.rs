@@ -0,0 +1,88 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{read_or_exit, Environment, Image};
+use crate::heightfield::Heightfield;
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::plane::Plane;
+use crate::scenery::Scenery;
+use crate::textures::perlin::Perlin;
+use crate::vector::Vector;
+
+/// How many samples the procedural terrain has along each side.
+const RESOLUTION: usize = 257;
+
+/// Renders terrain from the PNG or PFM height map at `heightmap_path`, or procedural hills
+/// around a lake if none is given.
+pub fn get_environment(heightmap_path: Option<&Path>) -> Environment {
+    let image = make_image();
+    let corner = Vector::new(-10.0, 0.0, -10.0);
+    let size = Vector::new(20.0, 4.0, 20.0);
+    let ground: Rc<dyn Material> = Rc::new(Lambertian::new(&Vector::new(0.45, 0.5, 0.3)));
+    let terrain = match heightmap_path {
+        Some(path) => read_or_exit(Heightfield::load(path, &corner, &size, ground), path),
+        None => Heightfield::new(&corner, &size, RESOLUTION, RESOLUTION, make_hills(), ground),
+    };
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(terrain),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 7.0, 16.0),
+        lookat: Vector::new(0.0, 0.5, -2.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 40.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 18.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// Octaves of noise, each twice as fine and half as tall as the last, over a bowl that dips in
+/// the middle.
+fn make_hills() -> Vec<f64> {
+    let noise = Perlin::new(7);
+    (0..RESOLUTION * RESOLUTION)
+        .map(|i| {
+            let x = (i % RESOLUTION) as f64 / (RESOLUTION - 1) as f64 - 0.5;
+            let z = (i / RESOLUTION) as f64 / (RESOLUTION - 1) as f64 - 0.5;
+            let mut height = 0.05 + 1.6 * (x * x + z * z);
+            let mut scale = 1.0;
+            for _ in 0..6 {
+                let p = Vector::new(6.0 * x, 0.5, 6.0 * z) / scale;
+                height += 0.35 * scale * noise.noise(&p);
+                scale *= 0.5;
+            }
+            height.clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+fn make_world(terrain: Heightfield) -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(terrain));
+    world.add(Box::new(Plane::new(
+        &Vector::new(0.0, 0.9, 0.0),
+        &Vector::new(0.0, 1.0, 0.0),
+        Rc::new(Dielectric::new(1.33)),
+    )));
+    world
+}
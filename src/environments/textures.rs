@@ -0,0 +1,150 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::environments::environment::{read_or_exit, Environment, Image};
+use crate::lambertian::Lambertian;
+use crate::metal::Metal;
+use crate::normal_map::NormalMap;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::textures::checker::Checker;
+use crate::textures::image_texture::{ImageTexture, WrapMode};
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::textures::uv_transform::UvTransform;
+use crate::vector::Vector;
+
+/// Textured balls. The middle ball is wrapped in the PPM image at `image_path` if one is given.
+pub fn get_environment(image_path: Option<&Path>) -> Environment {
+    let image = make_image();
+    let picture = match image_path {
+        Some(path) => read_or_exit(ImageTexture::load(path, WrapMode::Repeat), path),
+        None => make_picture(),
+    };
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(picture),
+        lights: None,
+        fog: None,
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(0.0, 2.0, 9.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 30.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 9.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+/// A small image of colored stripes, which bilinear filtering blurs into a smooth gradient.
+fn make_picture() -> ImageTexture {
+    let stripes = [
+        Vector::new(0.8, 0.1, 0.1),
+        Vector::new(0.8, 0.5, 0.1),
+        Vector::new(0.8, 0.8, 0.1),
+        Vector::new(0.1, 0.7, 0.1),
+        Vector::new(0.1, 0.5, 0.8),
+        Vector::new(0.4, 0.1, 0.8),
+    ];
+    let height = 4;
+    let pixels = (0..height)
+        .flat_map(|row| {
+            stripes
+                .iter()
+                .map(move |color| color * (1.0 - 0.2 * row as f64))
+        })
+        .collect();
+    ImageTexture::new(stripes.len(), height, pixels, WrapMode::Repeat)
+}
+
+/// A tangent-space normal map of a single round stud, which tiles over a surface.
+fn make_stud_normal_map() -> ImageTexture {
+    let size = 32;
+    let mut pixels = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = (x as f64 + 0.5) / size as f64 - 0.5;
+            let dy = 0.5 - (y as f64 + 0.5) / size as f64;
+            let r_squared = dx * dx + dy * dy;
+            let normal = if r_squared < 0.16 {
+                Vector::new(dx, dy, (0.16 - r_squared).sqrt()).unit_vector()
+            } else {
+                Vector::new(0.0, 0.0, 1.0)
+            };
+            pixels.push(0.5 * (normal + Vector::new(1.0, 1.0, 1.0)));
+        }
+    }
+    ImageTexture::new(size, size, pixels, WrapMode::Repeat)
+}
+
+fn make_checker(even: Vector, odd: Vector, scale: (f64, f64)) -> Rc<dyn Texture> {
+    let checker = Checker::new(
+        Rc::new(SolidColor::new(&even)),
+        Rc::new(SolidColor::new(&odd)),
+    );
+    Rc::new(UvTransform::new(Rc::new(checker), scale, (0.0, 0.0)))
+}
+
+fn make_world(picture: ImageTexture) -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(-2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(NormalMap::new(
+            Rc::new(Lambertian::textured(make_checker(
+                Vector::new(0.2, 0.3, 0.1),
+                Vector::new(0.9, 0.9, 0.9),
+                (16.0, 8.0),
+            ))),
+            Rc::new(UvTransform::new(
+                Rc::new(make_stud_normal_map()),
+                (16.0, 8.0),
+                (0.0, 0.0),
+            )),
+            1.0,
+        )),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Lambertian::textured(Rc::new(UvTransform::new(
+            Rc::new(picture),
+            (2.0, 1.0),
+            (0.25, 0.0),
+        )))),
+    )));
+    world.add(Box::new(Sphere::new(
+        &Vector::new(2.2, 1.0, 0.0),
+        1.0,
+        Rc::new(Metal::textured(
+            make_checker(
+                Vector::new(0.9, 0.7, 0.3),
+                Vector::new(0.7, 0.7, 0.7),
+                (12.0, 6.0),
+            ),
+            0.1,
+        )),
+    )));
+    world
+}
@@ -0,0 +1,93 @@
+use std::rc::Rc;
+
+use crate::camera::{Camera, CameraConfig};
+use crate::constant_medium::ConstantMedium;
+use crate::dielectric::Dielectric;
+use crate::environments::environment::{Environment, Fog, Image};
+use crate::lambertian::Lambertian;
+use crate::scenery::Scenery;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+pub fn get_environment() -> Environment {
+    let image = make_image();
+    Environment {
+        camera: make_camera(&image),
+        image,
+        scenery: make_world(),
+        lights: None,
+        fog: Some(Fog {
+            density: 0.01,
+            albedo: Vector::new(0.9, 0.9, 0.9),
+        }),
+    }
+}
+
+fn make_image() -> Image {
+    Image {
+        width: 800,
+        height: 400,
+        supersampling_ratio: 50,
+    }
+}
+
+fn make_camera(image: &Image) -> Camera {
+    let config = CameraConfig {
+        location: Vector::new(13.0, 2.0, 3.0),
+        lookat: Vector::new(0.0, 1.0, 0.0),
+        up: Vector::new(0.0, 1.0, 0.0),
+        field_of_view: 25.0,
+        aspect_ratio: image.width as f64 / image.height as f64,
+        focus_distance: 10.0,
+        aperture: 0.0,
+    };
+    Camera::from_config(config)
+}
+
+fn make_world() -> Scenery {
+    let mut world = Scenery::empty();
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+    )));
+
+    // A ball of white smoke.
+    world.add(Box::new(ConstantMedium::new(
+        Box::new(Sphere::new(
+            &Vector::new(-4.0, 1.0, 0.0),
+            1.0,
+            Rc::new(Lambertian::new(&Vector::new(0.0, 0.0, 0.0))),
+        )),
+        1.5,
+        &Vector::new(0.9, 0.9, 0.9),
+    )));
+
+    // A glass ball filled with murky blue liquid.
+    world.add(Box::new(Sphere::new(
+        &Vector::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(ConstantMedium::new(
+        Box::new(Sphere::new(
+            &Vector::new(0.0, 1.0, 0.0),
+            0.95,
+            Rc::new(Lambertian::new(&Vector::new(0.0, 0.0, 0.0))),
+        )),
+        0.8,
+        &Vector::new(0.2, 0.4, 0.9),
+    )));
+
+    // A ball of dark soot.
+    world.add(Box::new(ConstantMedium::new(
+        Box::new(Sphere::new(
+            &Vector::new(4.0, 1.0, 0.0),
+            1.0,
+            Rc::new(Lambertian::new(&Vector::new(0.0, 0.0, 0.0))),
+        )),
+        4.0,
+        &Vector::new(0.1, 0.1, 0.1),
+    )));
+    world
+}
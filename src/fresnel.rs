@@ -0,0 +1,123 @@
+use crate::vector::Vector;
+
+/// Fresnel reflectance of a conductor with complex index of refraction `eta + ik`, for light
+/// arriving at an angle with cosine `cos_theta_i` to the normal. Averages both polarizations.
+pub fn conductor(cos_theta_i: f64, eta: f64, k: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(-1.0, 1.0);
+    let cos2 = cos_theta_i * cos_theta_i;
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cos_theta_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+/// Fresnel reflectance at the boundary of a dielectric, for light arriving at an angle with
+/// cosine `cos_theta_i` to the normal. `eta` is the ratio of the refractive index on the far side
+/// of the normal to the index on its near side; a negative cosine means light arrives from the
+/// far side. Returns one on total internal reflection.
+pub fn dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let (cos_theta_i, eta) = if cos_theta_i < 0.0 {
+        (-cos_theta_i.max(-1.0), 1.0 / eta)
+    } else {
+        (cos_theta_i.min(1.0), eta)
+    };
+    let sin2_theta_t = (1.0 - cos_theta_i * cos_theta_i) / (eta * eta);
+    if sin2_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+    let r_parallel = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let r_perpendicular = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+    0.5 * (r_parallel * r_parallel + r_perpendicular * r_perpendicular)
+}
+
+/// Schlick's approximation to the Fresnel reflectance, for a surface reflecting `r0` of each
+/// color at normal incidence.
+pub fn schlick(cos_theta_i: f64, r0: &Vector) -> Vector {
+    let weight = (1.0 - cos_theta_i.clamp(0.0, 1.0)).powi(5);
+    r0 + (Vector::new(1.0, 1.0, 1.0) - r0) * weight
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn conductor_at_normal_incidence_matches_closed_form() {
+        let (eta, k) = (0.2, 3.9);
+        let expected = ((eta - 1.0) * (eta - 1.0) + k * k) / ((eta + 1.0) * (eta + 1.0) + k * k);
+        assert!(approx_eq!(
+            f64,
+            expected,
+            conductor(1.0, eta, k),
+            epsilon = 1e-12
+        ));
+    }
+
+    #[test]
+    fn conductor_reflects_everything_at_grazing_angles() {
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            conductor(0.0, 0.2, 3.9),
+            epsilon = 1e-9
+        ));
+    }
+
+    #[test]
+    fn conductor_without_absorption_matches_dielectric_reflectance() {
+        let eta: f64 = 1.5;
+        let r0 = ((eta - 1.0) / (eta + 1.0)).powi(2);
+        assert!(approx_eq!(
+            f64,
+            r0,
+            conductor(1.0, eta, 0.0),
+            epsilon = 1e-12
+        ));
+    }
+
+    #[test]
+    fn dielectric_at_normal_incidence_matches_closed_form() {
+        let r0 = (0.5f64 / 2.5).powi(2);
+        assert!(approx_eq!(f64, r0, dielectric(1.0, 1.5), epsilon = 1e-12));
+        assert!(approx_eq!(f64, r0, dielectric(-1.0, 1.5), epsilon = 1e-12));
+    }
+
+    #[test]
+    fn dielectric_totally_reflects_beyond_critical_angle() {
+        let critical = (1.0f64 / 1.5).asin();
+        assert_eq!(1.0, dielectric(-(critical + 0.01).cos(), 1.5));
+        assert!(dielectric(-(critical - 0.01).cos(), 1.5) < 1.0);
+    }
+
+    #[test]
+    fn dielectric_reflects_everything_at_grazing_angles() {
+        assert!(approx_eq!(f64, 1.0, dielectric(0.0, 1.5), epsilon = 1e-9));
+    }
+
+    #[test]
+    fn dielectric_and_conductor_agree_without_absorption() {
+        for &cos in &[0.2, 0.5, 0.9] {
+            assert!(approx_eq!(
+                f64,
+                dielectric(cos, 1.7),
+                conductor(cos, 1.7, 0.0),
+                epsilon = 1e-9
+            ));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::density_grid::DensityGrid;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A heterogeneous volume whose density comes from a voxel grid stretched over a bounding box.
+pub struct GridMedium {
+    bounds: Aabb,
+    grid: DensityGrid,
+    density_scale: f64,
+    majorant: f64,
+    phase_function: Rc<dyn Material>,
+}
+
+impl GridMedium {
+    pub fn new(
+        bounds: Aabb,
+        grid: DensityGrid,
+        density_scale: f64,
+        phase_function: Rc<dyn Material>,
+    ) -> GridMedium {
+        let majorant = grid.max_density() * density_scale;
+        GridMedium {
+            bounds,
+            grid,
+            density_scale,
+            majorant,
+            phase_function,
+        }
+    }
+
+    pub fn density(&self, p: &Vector) -> f64 {
+        let min = self.bounds.minimum();
+        let max = self.bounds.maximum();
+        let local = Vector::new(
+            (p.x() - min.x()) / (max.x() - min.x()),
+            (p.y() - min.y()) / (max.y() - min.y()),
+            (p.z() - min.z()) / (max.z() - min.z()),
+        );
+        self.grid.lookup(&local) * self.density_scale
+    }
+}
+
+impl Hittable for GridMedium {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let (hit, t_enter, t_exit) = self.bounds.hit(ray, t_min, t_max);
+        if !hit || self.majorant <= 0.0 {
+            return (false, HitRecord::empty());
+        }
+
+        // Delta tracking: sample collisions against the majorant density and accept each one as
+        // a real collision with probability proportional to the local density.
+        let mut rng = rand::thread_rng();
+        let ray_length = ray.direction().length();
+        let mut t = t_enter;
+        loop {
+            t -= (1.0 - rng.gen::<f64>()).ln() / (self.majorant * ray_length);
+            if t >= t_exit {
+                return (false, HitRecord::empty());
+            }
+            let p = ray.at(t);
+            if rng.gen::<f64>() * self.majorant < self.density(&p) {
+                let hit_record = HitRecord {
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    p,
+                    normal: Vector::new(1.0, 0.0, 0.0), // arbitrary
+                    front_face: true,                   // also arbitrary
+                    material: Some(self.phase_function.clone()),
+                    ..HitRecord::empty()
+                };
+                return (true, hit_record);
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds.clone())
+    }
+
+    /// Estimates the fraction of light that passes through the volume along the ray with ratio
+    /// tracking, which gives a smooth estimate for shadow rays where delta tracking would only
+    /// ever let all or none of the light through.
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let (hit, t_enter, t_exit) = self.bounds.hit(ray, t_min, t_max);
+        if !hit || self.majorant <= 0.0 {
+            return 1.0;
+        }
+        let mut rng = rand::thread_rng();
+        let ray_length = ray.direction().length();
+        let mut transmittance = 1.0;
+        let mut t = t_enter;
+        loop {
+            t -= (1.0 - rng.gen::<f64>()).ln() / (self.majorant * ray_length);
+            if t >= t_exit {
+                return transmittance;
+            }
+            transmittance *= 1.0 - self.density(&ray.at(t)) / self.majorant;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isotropic::Isotropic;
+
+    // A unit box whose lower half in x has half the density of its upper half.
+    fn make_medium() -> GridMedium {
+        GridMedium::new(
+            Aabb::new(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.)),
+            DensityGrid::new(2, 1, 1, vec![1.0, 2.0]),
+            1.0,
+            Rc::new(Isotropic::new(&Vector::new(1., 1., 1.))),
+        )
+    }
+
+    fn make_ray() -> Ray {
+        Ray::new(&Vector::new(0.1, -1., 0.5), &Vector::new(0., 2., 0.))
+    }
+
+    #[test]
+    fn ratio_tracking_matches_beer_lambert() {
+        let medium = make_medium();
+        let samples = 20000;
+        let mean = (0..samples)
+            .map(|_| medium.transmittance(&make_ray(), 0.0, f64::INFINITY))
+            .sum::<f64>()
+            / samples as f64;
+        assert!((mean - (-1.0f64).exp()).abs() < 0.02);
+    }
+
+    #[test]
+    fn delta_tracking_matches_beer_lambert() {
+        let medium = make_medium();
+        let samples = 20000;
+        let escaped = (0..samples)
+            .filter(|_| !medium.hit(&make_ray(), 0.0, f64::INFINITY).0)
+            .count();
+        let fraction = escaped as f64 / samples as f64;
+        assert!((fraction - (-1.0f64).exp()).abs() < 0.02);
+    }
+
+    #[test]
+    fn density_maps_world_space_into_grid() {
+        let medium = make_medium();
+        assert_eq!(1.0, medium.density(&Vector::new(0.1, 0.5, 0.5)));
+        assert_eq!(2.0, medium.density(&Vector::new(0.9, 0.5, 0.5)));
+    }
+}
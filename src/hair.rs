@@ -0,0 +1,425 @@
+use std::f64::consts::{LN_2, PI};
+
+use rand::Rng;
+
+use crate::fresnel;
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// How many ways of passing through the fiber are modelled on their own: reflecting off it,
+/// passing straight through, and reflecting once inside. Light that takes longer paths is lumped
+/// into one last lobe.
+const LOBES: usize = 3;
+
+/// The absorption of the two pigments that color hair, per unit of concentration.
+const EUMELANIN: (f64, f64, f64) = (0.419, 0.697, 1.37);
+const PHEOMELANIN: (f64, f64, f64) = (0.187, 0.4, 1.05);
+
+/// A hair fiber, modelled as a rough dielectric cylinder with pigment absorbing light inside it,
+/// after Chiang et al.'s take on the Marschner model. Light leaving the fiber is split into lobes
+/// by how many times it crossed the interior. Each lobe spreads out along the fiber by the
+/// longitudinal roughness `beta_m` and around it by the azimuthal roughness `beta_n`, and the
+/// scales on the surface of the fiber tilt each lobe a different way.
+///
+/// It is meant for curves, and reads where across the fiber light arrives from `v`.
+pub struct Hair {
+    sigma_a: Vector,
+    eta: f64,
+    /// The variance of each lobe along the fiber.
+    variances: [f64; LOBES + 1],
+    /// The scale of the logistic distribution each lobe follows around the fiber.
+    azimuthal_scale: f64,
+    /// The sine and cosine of the scale angle, and of twice and four times it.
+    tilts: [(f64, f64); LOBES],
+}
+
+impl Hair {
+    /// A fiber with absorption coefficients `sigma_a`, relative to its diameter.
+    pub fn new(sigma_a: &Vector, beta_m: f64, beta_n: f64) -> Hair {
+        let beta_m = beta_m.clamp(1e-3, 1.0);
+        let beta_n = beta_n.clamp(1e-3, 1.0);
+        let variance = (0.726 * beta_m + 0.812 * beta_m.powi(2) + 3.7 * beta_m.powi(20)).powi(2);
+        let mut hair = Hair {
+            sigma_a: sigma_a.clone(),
+            eta: 1.55,
+            variances: [variance, 0.25 * variance, 4.0 * variance, 4.0 * variance],
+            azimuthal_scale: (PI / 8.0).sqrt()
+                * (0.265 * beta_n + 1.194 * beta_n.powi(2) + 5.372 * beta_n.powi(22)),
+            tilts: [(0.0, 1.0); LOBES],
+        };
+        hair.set_scale_angle(2.0);
+        hair
+    }
+
+    /// A fiber colored by the concentrations of eumelanin, the brown to black pigment, and
+    /// pheomelanin, the red one. Eumelanin from about 0.3 for blonde to 8 for black covers most
+    /// natural hair.
+    pub fn from_melanin(eumelanin: f64, pheomelanin: f64, beta_m: f64, beta_n: f64) -> Hair {
+        let sigma_a = eumelanin * Vector::new(EUMELANIN.0, EUMELANIN.1, EUMELANIN.2)
+            + pheomelanin * Vector::new(PHEOMELANIN.0, PHEOMELANIN.1, PHEOMELANIN.2);
+        Self::new(&sigma_a, beta_m, beta_n)
+    }
+
+    /// Sets the angle in degrees by which the scales on the fiber tilt, 2 for human hair.
+    fn set_scale_angle(&mut self, degrees: f64) {
+        let sin = degrees.to_radians().sin();
+        let mut tilt = (sin, (1.0 - sin * sin).max(0.0).sqrt());
+        for entry in &mut self.tilts {
+            *entry = tilt;
+            let (sin, cos) = tilt;
+            tilt = (2.0 * cos * sin, cos * cos - sin * sin);
+        }
+    }
+
+    /// The fraction of light leaving by each lobe, for light arriving at `cos_theta_o` to the
+    /// normal plane of the fiber and at `h` across it, with `transmittance` through the interior.
+    fn attenuations(
+        &self,
+        cos_theta_o: f64,
+        h: f64,
+        transmittance: &Vector,
+    ) -> [Vector; LOBES + 1] {
+        let cos_gamma_o = (1.0 - h * h).max(0.0).sqrt();
+        let f = fresnel::dielectric(cos_theta_o * cos_gamma_o, self.eta);
+        let once = (1.0 - f).powi(2) * transmittance;
+        let twice = f * transmittance * &once;
+        let scale = Vector::new(
+            f / (1.0 - transmittance.x() * f),
+            f / (1.0 - transmittance.y() * f),
+            f / (1.0 - transmittance.z() * f),
+        );
+        let rest = &twice * transmittance * &scale;
+        [Vector::new(f, f, f), once, twice, rest]
+    }
+
+    /// The geometry of light crossing the fiber: the angles `gamma_o` and `gamma_t` to the normal
+    /// at which it arrives and refracts, seen along the fiber, and the attenuation of each lobe.
+    fn lobes(&self, sin_theta_o: f64, h: f64) -> (f64, f64, [Vector; LOBES + 1]) {
+        let cos_theta_o = (1.0 - sin_theta_o * sin_theta_o).max(0.0).sqrt();
+        let sin_theta_t = sin_theta_o / self.eta;
+        let cos_theta_t = (1.0 - sin_theta_t * sin_theta_t).max(0.0).sqrt();
+        // Seen along the fiber, light refracts as if through an index that depends on its angle.
+        let eta_p = (self.eta * self.eta - sin_theta_o * sin_theta_o).sqrt() / cos_theta_o;
+        let sin_gamma_t = (h / eta_p).clamp(-1.0, 1.0);
+        let cos_gamma_t = (1.0 - sin_gamma_t * sin_gamma_t).sqrt();
+        let path = 2.0 * cos_gamma_t / cos_theta_t;
+        let transmittance = Vector::new(
+            (-self.sigma_a.x() * path).exp(),
+            (-self.sigma_a.y() * path).exp(),
+            (-self.sigma_a.z() * path).exp(),
+        );
+        (
+            h.clamp(-1.0, 1.0).asin(),
+            sin_gamma_t.asin(),
+            self.attenuations(cos_theta_o, h, &transmittance),
+        )
+    }
+
+    /// The chance of sampling each lobe, in proportion to how much light it carries.
+    fn lobe_pdfs(&self, sin_theta_o: f64, h: f64) -> [f64; LOBES + 1] {
+        let (_, _, attenuations) = self.lobes(sin_theta_o, h);
+        let weights = attenuations.map(|a| (a.x() + a.y() + a.z()) / 3.0);
+        let total: f64 = weights.iter().sum();
+        weights.map(|weight| weight / total)
+    }
+
+    /// The sine and cosine of the outgoing angle to the normal plane, tilted by the scales for
+    /// lobe `p`.
+    fn tilted(&self, p: usize, sin_theta_o: f64, cos_theta_o: f64) -> (f64, f64) {
+        // Reflection tilts one way by twice the scale angle, transmission back the other way by
+        // the angle itself, and reflection inside by four times it.
+        let (sign, (sin, cos)) = match p {
+            0 => (-1.0, self.tilts[1]),
+            1 => (1.0, self.tilts[0]),
+            2 => (1.0, self.tilts[2]),
+            _ => return (sin_theta_o, cos_theta_o),
+        };
+        (
+            sin_theta_o * cos + sign * cos_theta_o * sin,
+            (cos_theta_o * cos - sign * sin_theta_o * sin).abs(),
+        )
+    }
+
+    /// The BSDF for light arriving from `wi` and leaving towards `wo`, in a local frame with `x`
+    /// along the fiber and `z` the normal, where light arrives at `h` across the fiber from -1
+    /// to 1.
+    pub fn eval(&self, wo: &Vector, wi: &Vector, h: f64) -> Vector {
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(wo);
+        let (sin_theta_i, cos_theta_i, phi_i) = angles(wi);
+        let (gamma_o, gamma_t, attenuations) = self.lobes(sin_theta_o, h);
+        let phi = phi_i - phi_o;
+        let mut f = Vector::empty();
+        for (p, attenuation) in attenuations.iter().enumerate().take(LOBES) {
+            let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            let m = longitudinal(
+                cos_theta_i,
+                cos_theta_op,
+                sin_theta_i,
+                sin_theta_op,
+                self.variances[p],
+            );
+            let n = azimuthal(phi, p, self.azimuthal_scale, gamma_o, gamma_t);
+            f = f + (m * n) * attenuation;
+        }
+        let m = longitudinal(
+            cos_theta_i,
+            cos_theta_o,
+            sin_theta_i,
+            sin_theta_o,
+            self.variances[LOBES],
+        );
+        f = f + (m / (2.0 * PI)) * &attenuations[LOBES];
+        if wi.z().abs() > 0.0 {
+            f = f / wi.z().abs();
+        }
+        f
+    }
+
+    /// The density with which `sample` picks `wi`.
+    pub fn pdf(&self, wo: &Vector, wi: &Vector, h: f64) -> f64 {
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(wo);
+        let (sin_theta_i, cos_theta_i, phi_i) = angles(wi);
+        let (gamma_o, gamma_t, _) = self.lobes(sin_theta_o, h);
+        let lobe_pdfs = self.lobe_pdfs(sin_theta_o, h);
+        let phi = phi_i - phi_o;
+        let mut pdf = 0.0;
+        for (p, lobe_pdf) in lobe_pdfs.iter().enumerate().take(LOBES) {
+            let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+            pdf += longitudinal(
+                cos_theta_i,
+                cos_theta_op,
+                sin_theta_i,
+                sin_theta_op,
+                self.variances[p],
+            ) * lobe_pdf
+                * azimuthal(phi, p, self.azimuthal_scale, gamma_o, gamma_t);
+        }
+        pdf + longitudinal(
+            cos_theta_i,
+            cos_theta_o,
+            sin_theta_i,
+            sin_theta_o,
+            self.variances[LOBES],
+        ) * lobe_pdfs[LOBES]
+            / (2.0 * PI)
+    }
+
+    /// Samples an incoming direction by picking a lobe and then angles along and around the
+    /// fiber from it. Returns the direction and its weight, the BSDF times the cosine term over
+    /// the pdf.
+    fn sample(&self, wo: &Vector, h: f64, u: [f64; 4]) -> Option<(Vector, Vector)> {
+        let (sin_theta_o, cos_theta_o, phi_o) = angles(wo);
+        let (gamma_o, gamma_t, _) = self.lobes(sin_theta_o, h);
+        let lobe_pdfs = self.lobe_pdfs(sin_theta_o, h);
+        let mut choice = u[0];
+        let mut p = 0;
+        while p < LOBES && choice >= lobe_pdfs[p] {
+            choice -= lobe_pdfs[p];
+            p += 1;
+        }
+
+        let (sin_theta_op, cos_theta_op) = self.tilted(p, sin_theta_o, cos_theta_o);
+        let variance = self.variances[p];
+        let u1 = u[1].max(1e-5);
+        let cos_theta = 1.0 + variance * (u1 + (1.0 - u1) * (-2.0 / variance).exp()).ln();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let cos_phi = (2.0 * PI * u[2]).cos();
+        let sin_theta_i = -cos_theta * sin_theta_op + sin_theta * cos_phi * cos_theta_op;
+        let cos_theta_i = (1.0 - sin_theta_i * sin_theta_i).max(0.0).sqrt();
+
+        let dphi = if p < LOBES {
+            lobe_phi(p, gamma_o, gamma_t)
+                + sample_trimmed_logistic(u[3], self.azimuthal_scale, -PI, PI)
+        } else {
+            2.0 * PI * u[3]
+        };
+        let phi_i = phi_o + dphi;
+        let wi = Vector::new(
+            sin_theta_i,
+            cos_theta_i * phi_i.cos(),
+            cos_theta_i * phi_i.sin(),
+        );
+        let pdf = self.pdf(wo, &wi, h);
+        if pdf <= 0.0 {
+            return None;
+        }
+        Some((wi.clone(), self.eval(wo, &wi, h) * (wi.z().abs() / pdf)))
+    }
+}
+
+/// The sine and cosine of the angle between `w` and the plane square to the fiber, and the angle
+/// around the fiber.
+fn angles(w: &Vector) -> (f64, f64, f64) {
+    let sin_theta = w.x().clamp(-1.0, 1.0);
+    (
+        sin_theta,
+        (1.0 - sin_theta * sin_theta).max(0.0).sqrt(),
+        w.z().atan2(w.y()),
+    )
+}
+
+/// The modified Bessel function of the first kind of order zero.
+fn bessel_i0(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    for i in 1..10 {
+        term *= x2 / (4.0 * (i * i) as f64);
+        sum += term;
+    }
+    sum
+}
+
+/// The logarithm of `bessel_i0`, which would overflow for large `x`.
+fn log_bessel_i0(x: f64) -> f64 {
+    if x > 12.0 {
+        x + 0.5 * (-(2.0 * PI).ln() + (1.0 / x).ln() + 1.0 / (8.0 * x))
+    } else {
+        bessel_i0(x).ln()
+    }
+}
+
+/// How light of a lobe with variance `v` spreads along the fiber.
+fn longitudinal(
+    cos_theta_i: f64,
+    cos_theta_o: f64,
+    sin_theta_i: f64,
+    sin_theta_o: f64,
+    v: f64,
+) -> f64 {
+    let a = cos_theta_i * cos_theta_o / v;
+    let b = sin_theta_i * sin_theta_o / v;
+    if v <= 0.1 {
+        (log_bessel_i0(a) - b - 1.0 / v + LN_2 + (1.0 / (2.0 * v)).ln()).exp()
+    } else {
+        (-b).exp() * bessel_i0(a) / ((1.0 / v).sinh() * 2.0 * v)
+    }
+}
+
+/// The angle around the fiber at which lobe `p` leaves, for light refracting at `gamma_t`.
+fn lobe_phi(p: usize, gamma_o: f64, gamma_t: f64) -> f64 {
+    let p = p as f64;
+    2.0 * p * gamma_t - 2.0 * gamma_o + p * PI
+}
+
+/// How light of lobe `p` spreads around the fiber, `phi` away from where it came from.
+fn azimuthal(phi: f64, p: usize, s: f64, gamma_o: f64, gamma_t: f64) -> f64 {
+    let mut dphi = phi - lobe_phi(p, gamma_o, gamma_t);
+    while dphi > PI {
+        dphi -= 2.0 * PI;
+    }
+    while dphi < -PI {
+        dphi += 2.0 * PI;
+    }
+    trimmed_logistic(dphi, s, -PI, PI)
+}
+
+fn logistic(x: f64, s: f64) -> f64 {
+    let e = (-x.abs() / s).exp();
+    e / (s * (1.0 + e).powi(2))
+}
+
+fn logistic_cdf(x: f64, s: f64) -> f64 {
+    1.0 / (1.0 + (-x / s).exp())
+}
+
+/// The logistic distribution cut down to between `a` and `b`.
+fn trimmed_logistic(x: f64, s: f64, a: f64, b: f64) -> f64 {
+    logistic(x, s) / (logistic_cdf(b, s) - logistic_cdf(a, s))
+}
+
+fn sample_trimmed_logistic(u: f64, s: f64, a: f64, b: f64) -> f64 {
+    let k = logistic_cdf(b, s) - logistic_cdf(a, s);
+    let x = -s * (1.0 / (u * k + logistic_cdf(a, s)) - 1.0).ln();
+    x.clamp(a, b)
+}
+
+impl Material for Hair {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let frame = hit_record.shading_frame();
+        let wo = frame.to_local(&-ray_in.direction().unit_vector());
+        let h = 2.0 * hit_record.v - 1.0;
+        let mut rng = rand::thread_rng();
+        match self.sample(&wo, h, [rng.gen(), rng.gen(), rng.gen(), rng.gen()]) {
+            Some((wi, weight)) => {
+                let direction = frame.local(wi.x(), wi.y(), wi.z());
+                (true, weight, ray_in.spawn(&hit_record.p, &direction))
+            }
+            None => (false, Vector::empty(), ray_in.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn uniform_sphere(rng: &mut StdRng) -> Vector {
+        let z: f64 = 1.0 - 2.0 * rng.gen::<f64>();
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        Vector::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    // Without absorption a fiber scatters all the light that reaches it.
+    #[test]
+    fn clear_fiber_passes_white_furnace_test() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for &(beta_m, beta_n) in &[(0.2, 0.3), (0.5, 0.5), (0.8, 0.9)] {
+            let hair = Hair::new(&Vector::empty(), beta_m, beta_n);
+            let wo = uniform_sphere(&mut rng);
+            let samples = 200_000;
+            let total: f64 = (0..samples)
+                .map(|_| {
+                    let h = 2.0 * rng.gen::<f64>() - 1.0;
+                    let wi = uniform_sphere(&mut rng);
+                    hair.eval(&wo, &wi, h).x() * wi.z().abs()
+                })
+                .sum();
+            let albedo = 4.0 * PI * total / samples as f64;
+            assert!(
+                (albedo - 1.0).abs() < 0.05,
+                "{} {}: {}",
+                beta_m,
+                beta_n,
+                albedo
+            );
+        }
+    }
+
+    // Sampling follows the BSDF exactly when nothing is absorbed, so every weight is one.
+    #[test]
+    fn clear_fiber_samples_with_unit_weights() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let hair = Hair::new(&Vector::empty(), 0.3, 0.3);
+        for _ in 0..1000 {
+            let wo = uniform_sphere(&mut rng);
+            let h = 2.0 * rng.gen::<f64>() - 1.0;
+            if let Some((_, weight)) = hair.sample(&wo, h, rng.gen()) {
+                assert!((weight.x() - 1.0).abs() < 1e-3, "{}", weight.x());
+            }
+        }
+    }
+
+    #[test]
+    fn pigment_darkens_the_fiber() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let blonde = Hair::from_melanin(0.3, 0.0, 0.3, 0.3);
+        let black = Hair::from_melanin(8.0, 0.0, 0.3, 0.3);
+        let wo = Vector::new(0.2, 0.3, 0.9).unit_vector();
+        let mean = |hair: &Hair, rng: &mut StdRng| {
+            (0..2000)
+                .filter_map(|_| hair.sample(&wo, 2.0 * rng.gen::<f64>() - 1.0, rng.gen()))
+                .map(|(_, weight)| weight.x())
+                .sum::<f64>()
+                / 2000.0
+        };
+        let (light, dark) = (mean(&blonde, &mut rng), mean(&black, &mut rng));
+        assert!(dark < light && light < 1.0, "{} {}", dark, light);
+    }
+}
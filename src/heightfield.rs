@@ -0,0 +1,563 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::io::{read_pfm, read_png};
+use crate::material::Material;
+use crate::math;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// How far outside a cell, along the ray, a hit on its patch may still count, so that hits right
+/// on the edge between two cells are not lost to rounding.
+const EPSILON: f64 = 1e-9;
+
+/// The lowest and highest heights within each cell of a grid of cells. Each level's cells cover
+/// two by two of the cells of the level below, so that rays can skip over large stretches of
+/// terrain that they pass above.
+struct Level {
+    columns: usize,
+    rows: usize,
+    bounds: Vec<(f64, f64)>,
+}
+
+impl Level {
+    fn coarser(&self) -> Level {
+        let columns = self.columns.div_ceil(2);
+        let rows = self.rows.div_ceil(2);
+        let mut bounds = Vec::with_capacity(columns * rows);
+        for z in 0..rows {
+            for x in 0..columns {
+                let mut cell = (f64::INFINITY, f64::NEG_INFINITY);
+                for child_z in 2 * z..(2 * z + 2).min(self.rows) {
+                    for child_x in 2 * x..(2 * x + 2).min(self.columns) {
+                        let (low, high) = self.bounds[child_z * self.columns + child_x];
+                        cell = (cell.0.min(low), cell.1.max(high));
+                    }
+                }
+                bounds.push(cell);
+            }
+        }
+        Level {
+            columns,
+            rows,
+            bounds,
+        }
+    }
+}
+
+/// A ray in the grid's own coordinates, where samples are a unit apart in `x` and `z` and `y` is
+/// the height of a sample, along with the ray it came from and where hits on it may lie.
+struct GridRay<'a> {
+    ray: &'a Ray,
+    origin: Vector,
+    direction: Vector,
+    t_min: f64,
+    t_max: f64,
+}
+
+/// Terrain given by a grid of height samples, `columns` along the x axis and `rows` along the z
+/// axis, spread evenly from `corner` across `size.x` and `size.z`. A sample of height one stands
+/// `size.y` above the corner. Between samples, the surface is the bilinear patch through the four
+/// around it, shaded with normals blended from those at the samples.
+///
+/// The first row of samples lies along the far edge, at the lowest `z`, so that an image seen
+/// from above lies the right way up. `u` runs along x and `v` runs from the near edge to the far
+/// one.
+pub struct Heightfield {
+    corner: Vector,
+    size: Vector,
+    columns: usize,
+    rows: usize,
+    heights: Vec<f64>,
+    normals: Vec<Vector>,
+    /// From single cells up to one cell covering the whole grid.
+    levels: Vec<Level>,
+    material: Rc<dyn Material>,
+}
+
+impl Heightfield {
+    pub fn new(
+        corner: &Vector,
+        size: &Vector,
+        columns: usize,
+        rows: usize,
+        heights: Vec<f64>,
+        material: Rc<dyn Material>,
+    ) -> Heightfield {
+        assert!(
+            columns >= 2 && rows >= 2,
+            "heightfields need two samples each way"
+        );
+        assert_eq!(columns * rows, heights.len());
+        let mut heightfield = Heightfield {
+            corner: corner.clone(),
+            size: size.clone(),
+            columns,
+            rows,
+            heights,
+            normals: Vec::new(),
+            levels: Vec::new(),
+            material,
+        };
+        heightfield.normals = (0..columns * rows)
+            .map(|i| heightfield.sample_normal(i % columns, i / columns))
+            .collect();
+
+        let mut cells = Vec::with_capacity((columns - 1) * (rows - 1));
+        for z in 0..rows - 1 {
+            for x in 0..columns - 1 {
+                let corners = [
+                    heightfield.height(x, z),
+                    heightfield.height(x + 1, z),
+                    heightfield.height(x, z + 1),
+                    heightfield.height(x + 1, z + 1),
+                ];
+                let low = corners.iter().cloned().fold(f64::INFINITY, f64::min);
+                let high = corners.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                cells.push((low, high));
+            }
+        }
+        let mut level = Level {
+            columns: columns - 1,
+            rows: rows - 1,
+            bounds: cells,
+        };
+        while level.columns > 1 || level.rows > 1 {
+            let coarser = level.coarser();
+            heightfield.levels.push(level);
+            level = coarser;
+        }
+        heightfield.levels.push(level);
+        heightfield
+    }
+
+    /// Loads the heights from a grayscale PNG or PFM image, averaging the channels of color ones.
+    /// PNG samples run from zero to one, while PFM samples are taken as they are.
+    pub fn load(
+        path: &Path,
+        corner: &Vector,
+        size: &Vector,
+        material: Rc<dyn Material>,
+    ) -> Result<Heightfield, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let (width, height, pixels) = match extension.as_deref() {
+            Some("png") => read_png(path)?,
+            Some("pfm") => read_pfm(path)?,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "heightfields are loaded from PNG or PFM images",
+                ))
+            }
+        };
+        if width < 2 || height < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "heightfield images need at least two pixels each way",
+            ));
+        }
+        let heights = pixels
+            .iter()
+            .map(|pixel| (pixel.x() + pixel.y() + pixel.z()) / 3.0)
+            .collect();
+        Ok(Self::new(corner, size, width, height, heights, material))
+    }
+
+    fn height(&self, x: usize, z: usize) -> f64 {
+        self.heights[z * self.columns + x]
+    }
+
+    /// The distance between neighbouring samples along x and along z.
+    fn spacing(&self) -> (f64, f64) {
+        (
+            self.size.x() / (self.columns - 1) as f64,
+            self.size.z() / (self.rows - 1) as f64,
+        )
+    }
+
+    /// The normal at a sample, from the slopes to its neighbours on either side.
+    fn sample_normal(&self, x: usize, z: usize) -> Vector {
+        let (dx, dz) = self.spacing();
+        let (x0, x1) = (x.saturating_sub(1), (x + 1).min(self.columns - 1));
+        let (z0, z1) = (z.saturating_sub(1), (z + 1).min(self.rows - 1));
+        let slope_x =
+            (self.height(x1, z) - self.height(x0, z)) * self.size.y() / ((x1 - x0) as f64 * dx);
+        let slope_z =
+            (self.height(x, z1) - self.height(x, z0)) * self.size.y() / ((z1 - z0) as f64 * dz);
+        Vector::new(-slope_x, 1.0, -slope_z).unit_vector()
+    }
+
+    fn to_grid<'a>(&self, ray: &'a Ray, t_min: f64, t_max: f64) -> GridRay<'a> {
+        let scale = Vector::new(
+            (self.columns - 1) as f64 / self.size.x(),
+            1.0 / self.size.y(),
+            (self.rows - 1) as f64 / self.size.z(),
+        );
+        let scaled =
+            |v: &Vector| Vector::new(v.x() * scale.x(), v.y() * scale.y(), v.z() * scale.z());
+        GridRay {
+            ray,
+            origin: scaled(&(ray.origin() - &self.corner)),
+            direction: scaled(ray.direction()),
+            t_min,
+            t_max,
+        }
+    }
+
+    /// Walks the ray across the cells of `level` within `block`, given as the first and one past
+    /// the last column and row, from `t_enter` to `t_exit`. Cells whose heights the ray passes
+    /// above or below are skipped; the rest are searched a level down, or intersected at the
+    /// bottom level.
+    fn traverse(
+        &self,
+        ray: &GridRay,
+        level: usize,
+        block: [usize; 4],
+        t_enter: f64,
+        t_exit: f64,
+    ) -> Option<HitRecord> {
+        let grid = &self.levels[level];
+        let cell_size = (1usize << level) as f64;
+        let (origin, direction) = (&ray.origin, &ray.direction);
+        let start = origin + t_enter * direction;
+        let cell = |coordinate: f64, low: usize, high: usize| {
+            ((coordinate / cell_size).floor() as isize).clamp(low as isize, high as isize - 1)
+        };
+        // The direction of each step along an axis, when the ray next crosses a cell boundary
+        // along it, and how far apart those crossings are.
+        let walk = |index: isize, origin: f64, direction: f64| {
+            if direction > 0.0 {
+                let next = ((index + 1) as f64 * cell_size - origin) / direction;
+                (1, next, cell_size / direction)
+            } else if direction < 0.0 {
+                let next = (index as f64 * cell_size - origin) / direction;
+                (-1, next, -cell_size / direction)
+            } else {
+                (0, f64::INFINITY, f64::INFINITY)
+            }
+        };
+        let mut x = cell(start.x(), block[0], block[2]);
+        let mut z = cell(start.z(), block[1], block[3]);
+        let (step_x, mut next_x, delta_x) = walk(x, origin.x(), direction.x());
+        let (step_z, mut next_z, delta_z) = walk(z, origin.z(), direction.z());
+
+        let mut t = t_enter;
+        loop {
+            let t_next = next_x.min(next_z).min(t_exit);
+            let (low, high) = grid.bounds[z as usize * grid.columns + x as usize];
+            let y0 = origin.y() + t * direction.y();
+            let y1 = origin.y() + t_next * direction.y();
+            if y0.min(y1) <= high + EPSILON && y0.max(y1) >= low - EPSILON {
+                let found = if level == 0 {
+                    self.patch_hit(ray, x as usize, z as usize, t, t_next)
+                } else {
+                    let below = &self.levels[level - 1];
+                    let (x, z) = (2 * x as usize, 2 * z as usize);
+                    let children = [x, z, (x + 2).min(below.columns), (z + 2).min(below.rows)];
+                    self.traverse(ray, level - 1, children, t, t_next)
+                };
+                if found.is_some() {
+                    return found;
+                }
+            }
+            if t_next >= t_exit {
+                return None;
+            }
+            if next_x < next_z {
+                x += step_x;
+                if x < block[0] as isize || x >= block[2] as isize {
+                    return None;
+                }
+                next_x += delta_x;
+            } else {
+                z += step_z;
+                if z < block[1] as isize || z >= block[3] as isize {
+                    return None;
+                }
+                next_z += delta_z;
+            }
+            t = t_next;
+        }
+    }
+
+    /// Intersects the ray with the bilinear patch over the cell at column `x` and row `z`,
+    /// between `t_enter` and `t_exit`. Along the ray, the height of the patch below it is a
+    /// quadratic in `t`, so the hits are where that meets the height of the ray.
+    fn patch_hit(
+        &self,
+        ray: &GridRay,
+        x: usize,
+        z: usize,
+        t_enter: f64,
+        t_exit: f64,
+    ) -> Option<HitRecord> {
+        let h00 = self.height(x, z);
+        let (a, b, c) = self.patch_slopes(x, z);
+        let (ou, ov, oy) = (
+            ray.origin.x() - x as f64,
+            ray.origin.z() - z as f64,
+            ray.origin.y(),
+        );
+        let (du, dv, dy) = (ray.direction.x(), ray.direction.z(), ray.direction.y());
+        let roots = math::solve_quadratic(
+            c * du * dv,
+            a * du + b * dv + c * (ou * dv + ov * du) - dy,
+            h00 + a * ou + b * ov + c * ou * ov - oy,
+        );
+        for t in roots {
+            if t < t_enter - EPSILON || t > t_exit + EPSILON || t <= ray.t_min || t >= ray.t_max {
+                continue;
+            }
+            let u = (ou + t * du).clamp(0.0, 1.0);
+            let v = (ov + t * dv).clamp(0.0, 1.0);
+            let hit_record = self.record_at(ray.ray, t, x, z, u, v);
+            if hit_record.is_opaque() {
+                return Some(hit_record);
+            }
+        }
+        None
+    }
+
+    /// The patch over a cell is `h00 + a u + b v + c u v`, for `u` and `v` across the cell.
+    fn patch_slopes(&self, x: usize, z: usize) -> (f64, f64, f64) {
+        let h00 = self.height(x, z);
+        let h10 = self.height(x + 1, z);
+        let h01 = self.height(x, z + 1);
+        let h11 = self.height(x + 1, z + 1);
+        (h10 - h00, h01 - h00, h00 - h10 - h01 + h11)
+    }
+
+    fn record_at(&self, ray: &Ray, t: f64, x: usize, z: usize, u: f64, v: f64) -> HitRecord {
+        let (a, b, c) = self.patch_slopes(x, z);
+        let (last_column, last_row) = ((self.columns - 1) as f64, (self.rows - 1) as f64);
+        let dpdu = Vector::new(
+            self.size.x(),
+            self.size.y() * (a + c * v) * last_column,
+            0.0,
+        );
+        let dpdv = -Vector::new(0.0, self.size.y() * (b + c * u) * last_row, self.size.z());
+        let outward_normal = Vector::cross(&dpdu, &dpdv).unit_vector();
+
+        let corner_normal = |dx: usize, dz: usize| &self.normals[(z + dz) * self.columns + x + dx];
+        let smooth_normal = ((1.0 - u) * (1.0 - v) * corner_normal(0, 0)
+            + u * (1.0 - v) * corner_normal(1, 0)
+            + (1.0 - u) * v * corner_normal(0, 1)
+            + u * v * corner_normal(1, 1))
+        .unit_vector();
+
+        let mut hit_record = HitRecord {
+            t,
+            u: (x as f64 + u) / last_column,
+            v: 1.0 - (z as f64 + v) / last_row,
+            p: ray.at(t),
+            dpdu,
+            dpdv,
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        hit_record.normal = if hit_record.front_face {
+            smooth_normal
+        } else {
+            -smooth_normal
+        };
+        hit_record
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let grid_ray = self.to_grid(ray, t_min, t_max);
+        let top = self.levels.len() - 1;
+        let (low, high) = self.levels[top].bounds[0];
+        let bounds = Aabb::new(
+            &Vector::new(0.0, low, 0.0),
+            &Vector::new((self.columns - 1) as f64, high, (self.rows - 1) as f64),
+        )
+        .pad(1e-6);
+        let (hit, t_enter, t_exit) = bounds.hit(
+            &Ray::new(&grid_ray.origin, &grid_ray.direction),
+            t_min,
+            t_max,
+        );
+        if !hit {
+            return (false, HitRecord::empty());
+        }
+        match self.traverse(&grid_ray, top, [0, 0, 1, 1], t_enter, t_exit) {
+            Some(hit_record) => (true, hit_record),
+            None => (false, HitRecord::empty()),
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (low, high) = self.levels[self.levels.len() - 1].bounds[0];
+        let minimum = &self.corner + Vector::new(0.0, low * self.size.y(), 0.0);
+        let maximum =
+            &self.corner + Vector::new(self.size.x(), high * self.size.y(), self.size.z());
+        Some(Aabb::new(&minimum, &maximum).pad(1e-4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::io::{Image, Png};
+    use crate::lambertian::Lambertian;
+
+    fn material() -> Rc<dyn Material> {
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5)))
+    }
+
+    /// Rolling hills over a 37 by 23 grid, spread over ten by six units.
+    fn hills() -> Heightfield {
+        let (columns, rows) = (37, 23);
+        let heights = (0..columns * rows)
+            .map(|i| {
+                let (x, z) = ((i % columns) as f64, (i / columns) as f64);
+                0.5 + 0.3 * (0.4 * x).sin() * (0.3 * z).cos() + 0.1 * (1.3 * x + 0.7 * z).sin()
+            })
+            .collect();
+        Heightfield::new(
+            &Vector::new(-5.0, 0.0, -3.0),
+            &Vector::new(10.0, 2.0, 6.0),
+            columns,
+            rows,
+            heights,
+            material(),
+        )
+    }
+
+    /// How far above the surface a point is, found by interpolating the samples directly.
+    fn height_above(field: &Heightfield, p: &Vector) -> f64 {
+        let (dx, dz) = field.spacing();
+        let gx = (p.x() - field.corner.x()) / dx;
+        let gz = (p.z() - field.corner.z()) / dz;
+        let x = (gx.floor() as usize).min(field.columns - 2);
+        let z = (gz.floor() as usize).min(field.rows - 2);
+        let (u, v) = (gx - x as f64, gz - z as f64);
+        let (a, b, c) = field.patch_slopes(x, z);
+        let surface = field.height(x, z) + a * u + b * v + c * u * v;
+        p.y() - field.corner.y() - surface * field.size.y()
+    }
+
+    #[test]
+    fn traversal_finds_the_first_crossing() {
+        let field = hills();
+        let origins = [
+            Vector::new(-7.0, 3.0, 4.0),
+            Vector::new(6.0, 1.6, -4.0),
+            Vector::new(0.3, 5.0, 0.2),
+            Vector::new(-4.9, 1.1, -2.9),
+        ];
+        let targets = [
+            Vector::new(4.0, 0.0, -2.0),
+            Vector::new(-5.0, 0.5, 3.0),
+            Vector::new(0.3, 0.0, 0.2),
+            Vector::new(5.0, 1.0, -2.9),
+        ];
+        for (origin, target) in origins.iter().zip(targets.iter()) {
+            let ray = Ray::new(origin, &(target - origin));
+            let (hit, hit_record) = field.hit(&ray, 0.001, f64::INFINITY);
+            // March along the ray in fine steps to find where it first crosses the surface. The
+            // terrain has no walls, so getting under its edge from the side is no hit.
+            let steps = 100_000;
+            let above = |t: f64| {
+                let p = ray.at(t);
+                let inside = p.x().abs() <= 5.0 && p.z().abs() <= 3.0;
+                inside.then(|| height_above(&field, &p) > 0.0)
+            };
+            let crossing = (1..=steps)
+                .map(|i| i as f64 / steps as f64 * 2.0)
+                .find(|&t| {
+                    let before = above(t - 2.0 / steps as f64);
+                    before.is_some() && above(t).is_some() && before != above(t)
+                });
+            match crossing {
+                Some(t) => {
+                    assert!(hit);
+                    assert!(approx_eq!(f64, t, hit_record.t, epsilon = 1e-4));
+                    assert!(height_above(&field, &hit_record.p).abs() < 1e-9);
+                    assert_eq!(above(t) == Some(false), hit_record.front_face);
+                }
+                None => assert!(!hit),
+            }
+        }
+    }
+
+    #[test]
+    fn flat_ground_faces_up_everywhere() {
+        let field = Heightfield::new(
+            &Vector::new(0.0, 1.0, 0.0),
+            &Vector::new(4.0, 3.0, 4.0),
+            3,
+            3,
+            vec![0.5; 9],
+            material(),
+        );
+        let down = Ray::new(&Vector::new(1.0, 5.0, 3.0), &Vector::new(0.0, -2.0, 0.0));
+        let (hit, hit_record) = field.hit(&down, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 1.25, hit_record.t));
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.y()));
+        assert!(approx_eq!(f64, 0.25, hit_record.u));
+        assert!(approx_eq!(f64, 0.25, hit_record.v));
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(cross.y() > 0.0);
+
+        // From below, the ray sees the underside.
+        let up = Ray::new(&Vector::new(3.0, 0.0, 1.0), &Vector::new(0.0, 1.0, 0.0));
+        let (hit, hit_record) = field.hit(&up, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, -1.0, hit_record.normal.y()));
+        let level = Ray::new(&Vector::new(-1.0, 2.0, 1.0), &Vector::new(1.0, 0.0, 0.0));
+        assert!(!field.hit(&level, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn loads_heights_from_png_images() {
+        let path = Path::new("test1.heights.png");
+        {
+            let mut png = Png::new(path, 2, 2);
+            for &gray in &[0.0, 255.0, 51.0, 102.0] {
+                png.write_pixel(&Vector::new(gray, gray, gray));
+            }
+        }
+        let field = Heightfield::load(
+            path,
+            &Vector::new(0.0, 0.0, 0.0),
+            &Vector::new(1.0, 10.0, 1.0),
+            material(),
+        )
+        .unwrap();
+        fs::remove_file(path).unwrap();
+
+        // The second row, with the lower heights, is the near edge.
+        let bounds = field.bounding_box().unwrap();
+        assert!(approx_eq!(f64, 10.0, bounds.maximum().y(), epsilon = 1e-3));
+        let down =
+            |x: f64, z: f64| Ray::new(&Vector::new(x, 20.0, z), &Vector::new(0.0, -1.0, 0.0));
+        let (_, far_right) = field.hit(&down(1.0, 0.0), 0.001, f64::INFINITY);
+        assert!(approx_eq!(f64, 10.0, far_right.p.y(), epsilon = 1e-9));
+        let (_, near_left) = field.hit(&down(0.0, 1.0), 0.001, f64::INFINITY);
+        assert!(approx_eq!(f64, 2.0, near_left.p.y(), epsilon = 1e-9));
+        assert!(Heightfield::load(
+            Path::new("heights.ppm"),
+            &Vector::empty(),
+            &Vector::empty(),
+            material()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use rand::Rng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// An anisotropic phase function for participating media. Positive `g` favors forward
+/// scattering, negative `g` favors back scattering, and zero is isotropic.
+pub struct HenyeyGreenstein {
+    albedo: Rc<dyn Texture>,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: &Vector, g: f64) -> HenyeyGreenstein {
+        Self::textured(Rc::new(SolidColor::new(albedo)), g)
+    }
+
+    pub fn textured(albedo: Rc<dyn Texture>, g: f64) -> HenyeyGreenstein {
+        HenyeyGreenstein {
+            albedo,
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    /// Samples the cosine of the angle between the incoming propagation direction and the
+    /// scattered direction.
+    fn sample_cos_theta(g: f64, u: f64) -> f64 {
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * u;
+        }
+        let sqr_term = (1.0 - g * g) / (1.0 - g + 2.0 * g * u);
+        ((1.0 + g * g - sqr_term * sqr_term) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+
+    /// The density of scattering by an angle with cosine `cos_theta` to the propagation
+    /// direction, over solid angle.
+    fn phase(&self, cos_theta: f64) -> f64 {
+        let denominator = 1.0 + self.g * self.g - 2.0 * self.g * cos_theta;
+        (1.0 - self.g * self.g) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let mut rng = rand::thread_rng();
+        let cos_theta = Self::sample_cos_theta(self.g, rng.gen());
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let frame = Onb::from_w(ray_in.direction());
+        let direction = frame.local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        let scattered = ray_in.spawn(&hit_record.p, &direction);
+        (
+            true,
+            self.albedo.value(hit_record.u, hit_record.v, &hit_record.p),
+            scattered,
+        )
+    }
+
+    /// The albedo times the phase function `p(wo, wi)`, which integrates to one over the whole
+    /// sphere of directions. Unlike a BSDF there is no cosine term. Light arrives travelling along
+    /// `-wi` and leaves along `wo`, so the scattering angle has cosine `-wo · wi`.
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        Some(albedo * self.phase(-Vector::dot(wo, wi)))
+    }
+
+    fn pdf(&self, _hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        self.phase(-Vector::dot(wo, wi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_cosine(g: f64) -> f64 {
+        let samples = 10000;
+        (0..samples)
+            .map(|i| HenyeyGreenstein::sample_cos_theta(g, (i as f64 + 0.5) / samples as f64))
+            .sum::<f64>()
+            / samples as f64
+    }
+
+    #[test]
+    fn mean_cosine_matches_anisotropy() {
+        for &g in &[-0.7, -0.3, 0.0, 0.3, 0.7] {
+            assert!((mean_cosine(g) - g).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn sampled_cosines_are_in_range() {
+        for &g in &[-0.99, 0.5, 0.99] {
+            for &u in &[0.0, 0.5, 1.0] {
+                let cos_theta = HenyeyGreenstein::sample_cos_theta(g, u);
+                assert!((-1.0..=1.0).contains(&cos_theta));
+            }
+        }
+    }
+
+    #[test]
+    fn phase_function_integrates_to_one_and_favors_forward_scattering() {
+        let material = HenyeyGreenstein::new(&Vector::new(0.5, 0.5, 0.5), 0.6);
+        let samples = 10_000;
+        // Midpoint rule over the cosine, with the azimuth integrated out.
+        let integral = (0..samples)
+            .map(|i| material.phase(-1.0 + 2.0 * (i as f64 + 0.5) / samples as f64))
+            .sum::<f64>()
+            * 2.0
+            * 2.0
+            * PI
+            / samples as f64;
+        assert!((integral - 1.0).abs() < 1e-3, "{}", integral);
+
+        let hit_record = HitRecord::empty();
+        let wo = Vector::new(0.0, 0.0, -1.0);
+        let forward = Vector::new(0.0, 0.0, 1.0);
+        let forward_pdf = material.pdf(&hit_record, &wo, &forward);
+        assert!(forward_pdf > material.pdf(&hit_record, &wo, &wo));
+        let f = material.eval(&hit_record, &wo, &forward).unwrap();
+        assert!((f.x() - 0.5 * forward_pdf).abs() < 1e-12);
+    }
+}
@@ -1,26 +1,115 @@
+use crate::aabb::Aabb;
 use crate::material::Material;
+use crate::onb::Onb;
 use crate::ray::Ray;
 use crate::vector::Vector;
 use std::rc::Rc;
 
+#[derive(Clone)]
 pub struct HitRecord {
     pub p: Vector,
+    /// The normal used for shading, which normal and bump maps may perturb.
     pub normal: Vector,
+    /// The true normal of the surface. Like `normal`, it faces against the incoming ray.
+    pub geometric_normal: Vector,
+    /// The partial derivatives of `p` with respect to `u` and `v`, which span the tangent plane.
+    pub dpdu: Vector,
+    pub dpdv: Vector,
     pub t: f64,
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
     pub material: Option<Rc<dyn Material>>,
 }
 
+/// A stretch of a ray spent inside a closed object, from the hit where it enters to the hit where
+/// it leaves.
+#[derive(Clone)]
+pub struct Interval {
+    pub enter: HitRecord,
+    pub exit: HitRecord,
+}
+
 pub trait Hittable {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord);
+
+    /// A box enclosing the object, or `None` if it is unbounded.
+    fn bounding_box(&self) -> Option<Aabb>;
+
+    /// The density, over solid angle, with which `random` picks `direction` from `origin`, or
+    /// `None` if the object can't be sampled. Used to sample directions towards objects that emit
+    /// light.
+    fn pdf_value(&self, _origin: &Vector, _direction: &Vector) -> Option<f64> {
+        None
+    }
+
+    /// A direction from `origin` towards a random point on the object, or `None` if the object
+    /// can't be sampled.
+    fn random(&self, _origin: &Vector) -> Option<Vector> {
+        None
+    }
+
+    /// The fraction of light that gets through the object along the ray between `t_min` and
+    /// `t_max`, as seen by shadow rays. By default, all of it unless the ray hits the object.
+    /// Shapes only report hits where their material is opaque, so light passes through cutouts.
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        if self.hit(ray, t_min, t_max).0 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Every stretch of the ray between `t_min` and `t_max` spent inside the object, in order.
+    /// Only makes sense for closed objects. Stretches cut short by either end of the range start
+    /// or stop there, with an empty hit record.
+    ///
+    /// By default, this follows the ray from hit to hit, pairing hits on the outside of the
+    /// surface with the hits on the inside that come after them.
+    fn intervals(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<Interval> {
+        let clipped = |t: f64| HitRecord {
+            t,
+            ..HitRecord::empty()
+        };
+        let mut intervals = Vec::new();
+        let mut enter: Option<HitRecord> = None;
+        let mut t = t_min;
+        loop {
+            let (hit, hit_record) = self.hit(ray, t, t_max);
+            if !hit {
+                break;
+            }
+            t = hit_record.t;
+            if hit_record.front_face {
+                enter.get_or_insert(hit_record);
+            } else {
+                intervals.push(Interval {
+                    enter: enter.take().unwrap_or_else(|| clipped(t_min)),
+                    exit: hit_record,
+                });
+            }
+        }
+        if let Some(enter) = enter {
+            intervals.push(Interval {
+                enter,
+                exit: clipped(t_max),
+            });
+        }
+        intervals
+    }
 }
 
 impl HitRecord {
     pub fn empty() -> HitRecord {
         HitRecord {
             t: 0.0,
+            u: 0.0,
+            v: 0.0,
             p: Vector::new(0.0, 0.0, 0.0),
             normal: Vector::new(0.0, 0.0, 0.0),
+            geometric_normal: Vector::new(0.0, 0.0, 0.0),
+            dpdu: Vector::new(0.0, 0.0, 0.0),
+            dpdv: Vector::new(0.0, 0.0, 0.0),
             front_face: false,
             material: None,
         }
@@ -32,6 +121,105 @@ impl HitRecord {
             outward_normal.clone()
         } else {
             -outward_normal
-        }
+        };
+        self.geometric_normal = self.normal.clone();
+    }
+
+    /// Whether the material at the hit lets the hit stand; see `Material::is_opaque`.
+    pub fn is_opaque(&self) -> bool {
+        self.material
+            .as_ref()
+            .is_none_or(|material| material.is_opaque(self))
+    }
+
+    /// Builds an orthonormal tangent frame around the shading normal, with the tangent following
+    /// `dpdu` and the normal as `w`. Surfaces without texture coordinates get an arbitrary tangent.
+    pub fn shading_frame(&self) -> Onb {
+        let n = self.normal.unit_vector();
+        let tangent = &self.dpdu - Vector::dot(&self.dpdu, &n) * &n;
+        let tangent = if tangent.length_squared() > 1e-16 {
+            tangent.unit_vector()
+        } else {
+            Onb::from_w(&n).local(1.0, 0.0, 0.0)
+        };
+        let bitangent = Vector::cross(&n, &tangent);
+        Onb::from_vectors(tangent, bitangent, n)
+    }
+}
+
+/// Computes the partial derivatives of position with respect to texture coordinates for a
+/// triangle, as needed to build tangent frames on meshes and other flat faces.
+// There is no triangle mesh to call this yet; the tests cover it until there is.
+#[allow(dead_code)]
+pub fn triangle_partial_derivatives(
+    positions: [&Vector; 3],
+    uvs: [(f64, f64); 3],
+) -> (Vector, Vector) {
+    let dp02 = positions[0] - positions[2];
+    let dp12 = positions[1] - positions[2];
+    let (du02, dv02) = (uvs[0].0 - uvs[2].0, uvs[0].1 - uvs[2].1);
+    let (du12, dv12) = (uvs[1].0 - uvs[2].0, uvs[1].1 - uvs[2].1);
+    let determinant = du02 * dv12 - dv02 * du12;
+    if determinant.abs() < 1e-12 {
+        // Degenerate texture coordinates; pick any frame in the plane of the triangle.
+        let normal = Vector::cross(&dp02, &dp12);
+        let frame = Onb::from_w(&normal);
+        return (frame.local(1.0, 0.0, 0.0), frame.local(0.0, 1.0, 0.0));
+    }
+    let inv_determinant = 1.0 / determinant;
+    let dpdu = inv_determinant * (dv12 * &dp02 - dv02 * &dp12);
+    let dpdv = inv_determinant * (du02 * &dp12 - du12 * &dp02);
+    (dpdu, dpdv)
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn triangle_derivatives_follow_texture_axes() {
+        let a = Vector::new(0., 0., 0.);
+        let b = Vector::new(2., 0., 0.);
+        let c = Vector::new(0., 0., -3.);
+        let (dpdu, dpdv) =
+            triangle_partial_derivatives([&a, &b, &c], [(0., 0.), (1., 0.), (0., 1.)]);
+        assert!(approx_eq!(f64, 2., dpdu.x()));
+        assert!(approx_eq!(f64, 0., dpdu.z()));
+        assert!(approx_eq!(f64, 0., dpdv.x()));
+        assert!(approx_eq!(f64, -3., dpdv.z()));
+    }
+
+    #[test]
+    fn shading_frame_is_orthonormal() {
+        let mut record = HitRecord::empty();
+        record.normal = Vector::new(0., 1., 0.);
+        record.dpdu = Vector::new(1., 0.5, 0.);
+        let frame = record.shading_frame();
+        let (t, b, n) = (
+            frame.local(1., 0., 0.),
+            frame.local(0., 1., 0.),
+            frame.local(0., 0., 1.),
+        );
+        assert!(approx_eq!(f64, 1., t.x()));
+        assert!(approx_eq!(f64, 0., Vector::dot(&t, &n)));
+        assert!(approx_eq!(f64, 0., Vector::dot(&b, &n)));
+        assert!(approx_eq!(f64, 1., b.length()));
+    }
+
+    #[test]
+    fn shading_frame_without_derivatives_is_still_orthonormal() {
+        let mut record = HitRecord::empty();
+        record.normal = Vector::new(0., 0., 1.);
+        let frame = record.shading_frame();
+        let (t, b, n) = (
+            frame.local(1., 0., 0.),
+            frame.local(0., 1., 0.),
+            frame.local(0., 0., 1.),
+        );
+        assert!(approx_eq!(f64, 0., Vector::dot(&t, &n)));
+        assert!(approx_eq!(f64, 0., Vector::dot(&t, &b)));
+        assert!(approx_eq!(f64, 1., t.length()));
     }
 }
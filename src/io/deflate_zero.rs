@@ -8,7 +8,7 @@ pub fn compress(data: &[u8]) -> Vec<u8> {
     let num_chunks = {
         let filled_chunks = data.len() / CHUNK_SIZE;
         filled_chunks
-            + if data.len() == filled_chunks * CHUNK_SIZE && data.len() != 0 {
+            + if data.len() == filled_chunks * CHUNK_SIZE && !data.is_empty() {
                 0
             } else {
                 1
@@ -23,9 +23,9 @@ pub fn compress(data: &[u8]) -> Vec<u8> {
 
     for (idx, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
         let last_byte = if idx == num_chunks - 1 {
-            0x1 as u8
+            0x1_u8
         } else {
-            0x0 as u8
+            0x0_u8
         };
         compressed.extend(&[last_byte]);
         let block_len: [u8; 4] = [
@@ -36,7 +36,7 @@ pub fn compress(data: &[u8]) -> Vec<u8> {
         ];
         compressed.extend(&block_len);
         compressed.extend(chunk);
-        checksum.update_buffer(&chunk);
+        checksum.update_buffer(chunk);
     }
 
     compressed.extend(&util::to_bytes_big_endian(checksum.hash()));
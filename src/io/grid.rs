@@ -0,0 +1,116 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+
+use crate::density_grid::DensityGrid;
+
+/// Reads a density grid stored as three little-endian `u32` dimensions followed by the
+/// little-endian `f32` samples, with `x` varying fastest.
+pub fn read_density_grid(path: &Path) -> Result<DensityGrid, Error> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    parse_density_grid(&bytes)
+}
+
+fn parse_density_grid(bytes: &[u8]) -> Result<DensityGrid, Error> {
+    if bytes.len() < 12 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "grid header is truncated",
+        ));
+    }
+    let dimension = |i: usize| {
+        u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]) as usize
+    };
+    let (nx, ny, nz) = (dimension(0), dimension(4), dimension(8));
+    let samples = &bytes[12..];
+    let size = nx
+        .checked_mul(ny)
+        .and_then(|size| size.checked_mul(nz))
+        .and_then(|size| size.checked_mul(4));
+    if nx == 0 || ny == 0 || nz == 0 || size != Some(samples.len()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "grid dimensions do not match the sample data",
+        ));
+    }
+    let data: Vec<f64> = samples
+        .chunks(4)
+        .map(|s| f32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f64)
+        .collect();
+    // Tracking relies on densities that can be bounded, and that light can't gain from.
+    if data
+        .iter()
+        .any(|density| !density.is_finite() || *density < 0.0)
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "grid densities must be finite and non-negative",
+        ));
+    }
+    Ok(DensityGrid::new(nx, ny, nz, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+
+    use super::*;
+    use crate::vector::Vector;
+
+    #[test]
+    fn reads_grid_from_file() {
+        let path = Path::new("test1.grid");
+        let mut file = File::create(path).unwrap();
+        for dimension in &[2u32, 1, 1] {
+            file.write_all(&dimension.to_le_bytes()).unwrap();
+        }
+        for sample in &[1.0f32, 3.0] {
+            file.write_all(&sample.to_le_bytes()).unwrap();
+        }
+
+        let grid = read_density_grid(path).unwrap();
+        assert_eq!(1.0, grid.lookup(&Vector::new(0.25, 0.5, 0.5)));
+        assert_eq!(3.0, grid.max_density());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatched_sample_count() {
+        let mut bytes = Vec::new();
+        for dimension in &[2u32, 2, 2] {
+            bytes.extend_from_slice(&dimension.to_le_bytes());
+        }
+        bytes.extend_from_slice(&1.0f32.to_le_bytes());
+        assert!(parse_density_grid(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_dimensions_that_overflow() {
+        let mut bytes = Vec::new();
+        for dimension in &[u32::MAX, u32::MAX, u32::MAX] {
+            bytes.extend_from_slice(&dimension.to_le_bytes());
+        }
+        assert!(parse_density_grid(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_negative_or_non_finite_densities() {
+        for bad in &[-1.0f32, f32::NAN, f32::INFINITY] {
+            let mut bytes = Vec::new();
+            for dimension in &[2u32, 1, 1] {
+                bytes.extend_from_slice(&dimension.to_le_bytes());
+            }
+            bytes.extend_from_slice(&0.5f32.to_le_bytes());
+            bytes.extend_from_slice(&bad.to_le_bytes());
+            assert!(parse_density_grid(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(parse_density_grid(&[0, 1, 2]).is_err());
+    }
+}
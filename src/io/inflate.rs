@@ -0,0 +1,318 @@
+extern crate adler32;
+
+use std::io::{Error, ErrorKind};
+
+/// The base lengths of the length codes 257 to 285, and how many extra bits follow each.
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// The base distances of the distance codes, and how many extra bits follow each.
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// The order in which a dynamic block lists the code lengths of its code length alphabet.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const MAX_BITS: usize = 15;
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// Decompresses a zlib stream, as found in the data chunks of PNG images, checking its checksum.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    if data.len() < 6 {
+        return Err(invalid("zlib stream is truncated"));
+    }
+    let (method, flags) = (data[0], data[1]);
+    if method & 0x0f != 8 || (u16::from(method) << 8 | u16::from(flags)) % 31 != 0 {
+        return Err(invalid("not a deflate zlib stream"));
+    }
+    if flags & 0x20 != 0 {
+        return Err(invalid("zlib preset dictionaries are not supported"));
+    }
+
+    let mut bits = BitReader::new(&data[2..]);
+    let mut output = Vec::new();
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => stored_block(&mut bits, &mut output)?,
+            1 => {
+                let (lengths, distances) = fixed_codes();
+                compressed_block(&mut bits, &mut output, &lengths, &distances)?
+            }
+            2 => {
+                let (lengths, distances) = dynamic_codes(&mut bits)?;
+                compressed_block(&mut bits, &mut output, &lengths, &distances)?
+            }
+            _ => return Err(invalid("invalid deflate block type")),
+        }
+        if last {
+            break;
+        }
+    }
+
+    let end = 2 + bits.byte_position();
+    let checksum = data
+        .get(end..end + 4)
+        .ok_or_else(|| invalid("zlib checksum is missing"))?;
+    let expected = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    if adler32::RollingAdler32::from_buffer(&output).hash() != expected {
+        return Err(invalid("zlib checksum does not match"));
+    }
+    Ok(output)
+}
+
+/// Reads the bits of a deflate stream, which are packed starting from the least significant bit
+/// of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader { data, position: 0 }
+    }
+
+    /// Reads `count` bits, the first of them ending up least significant.
+    fn read(&mut self, count: u32) -> Result<usize, Error> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = self
+                .data
+                .get(self.position / 8)
+                .ok_or_else(|| invalid("deflate stream is truncated"))?;
+            value |= ((byte >> (self.position % 8)) as usize & 1) << i;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+
+    /// Skips to the start of the next byte.
+    fn align(&mut self) {
+        self.position = self.byte_position() * 8;
+    }
+
+    /// The index of the first byte not yet fully read.
+    fn byte_position(&self) -> usize {
+        self.position.div_ceil(8)
+    }
+}
+
+/// A canonical Huffman code, stored as how many codes there are of each length and the symbols
+/// in order of their codes.
+struct Huffman {
+    counts: [usize; MAX_BITS + 1],
+    symbols: Vec<usize>,
+}
+
+impl Huffman {
+    /// Builds the code from the length of each symbol's code, where zero means the symbol is
+    /// unused.
+    fn new(lengths: &[usize]) -> Huffman {
+        let mut counts = [0; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1]];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length]] = symbol;
+                offsets[length] += 1;
+            }
+        }
+        Huffman { counts, symbols }
+    }
+
+    /// Reads one symbol a bit at a time. Codes of each length follow on from those one bit
+    /// shorter, so the symbol is found once the code read so far falls among those of its length.
+    fn decode(&self, bits: &mut BitReader) -> Result<usize, Error> {
+        let (mut code, mut first, mut index) = (0, 0, 0);
+        for length in 1..=MAX_BITS {
+            code |= bits.read(1)?;
+            let count = self.counts[length];
+            if code < first + count {
+                return Ok(self.symbols[index + code - first]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("invalid Huffman code"))
+    }
+}
+
+fn stored_block(bits: &mut BitReader, output: &mut Vec<u8>) -> Result<(), Error> {
+    bits.align();
+    let start = bits.byte_position();
+    let header = bits
+        .data
+        .get(start..start + 4)
+        .ok_or_else(|| invalid("stored block header is truncated"))?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    let complement = u16::from_le_bytes([header[2], header[3]]);
+    if length != !complement {
+        return Err(invalid("stored block length is corrupt"));
+    }
+    let contents = bits
+        .data
+        .get(start + 4..start + 4 + length as usize)
+        .ok_or_else(|| invalid("stored block is truncated"))?;
+    output.extend_from_slice(contents);
+    bits.position = (start + 4 + length as usize) * 8;
+    Ok(())
+}
+
+/// The codes that blocks compressed with the fixed codes use, for literals and lengths and for
+/// distances.
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+/// Reads the codes a dynamic block describes in its header, which are themselves compressed with
+/// a code for their lengths.
+fn dynamic_codes(bits: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    let literal_count = bits.read(5)? + 257;
+    let distance_count = bits.read(5)? + 1;
+    let length_code_count = bits.read(4)? + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(invalid("too many codes in dynamic block"));
+    }
+    let mut length_code_lengths = [0; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..length_code_count] {
+        length_code_lengths[symbol] = bits.read(3)?;
+    }
+    let length_code = Huffman::new(&length_code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (length, repeat) = match length_code.decode(bits)? {
+            symbol @ 0..=15 => (symbol, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| invalid("repeated code length with nothing before it"))?;
+                (previous, 3 + bits.read(2)?)
+            }
+            17 => (0, 3 + bits.read(3)?),
+            _ => (0, 11 + bits.read(7)?),
+        };
+        if lengths.len() + repeat > literal_count + distance_count {
+            return Err(invalid("code lengths overrun the dynamic block header"));
+        }
+        lengths.extend(std::iter::repeat_n(length, repeat));
+    }
+    if lengths[256] == 0 {
+        return Err(invalid("dynamic block has no end of block code"));
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count]),
+        Huffman::new(&lengths[literal_count..]),
+    ))
+}
+
+/// Decodes literals and back references until the end of the block.
+fn compressed_block(
+    bits: &mut BitReader,
+    output: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+) -> Result<(), Error> {
+    loop {
+        let symbol = lengths.decode(bits)?;
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(invalid("invalid length code"));
+                }
+                let length = LENGTH_BASE[index] + bits.read(LENGTH_EXTRA[index])?;
+                let index = distances.decode(bits)?;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(invalid("invalid distance code"));
+                }
+                let distance = DISTANCE_BASE[index] + bits.read(DISTANCE_EXTRA[index])?;
+                if distance > output.len() {
+                    return Err(invalid("distance reaches back before the start"));
+                }
+                // The copy may overlap what it is writing, which repeats the last bytes.
+                let start = output.len() - distance;
+                for i in 0..length {
+                    output.push(output[start + i]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::deflate_zero;
+
+    #[test]
+    fn round_trips_stored_blocks() {
+        let data: Vec<u8> = (0..70000).map(|i| (i * 7 % 251) as u8).collect();
+        assert_eq!(data, decompress(&deflate_zero::compress(&data)).unwrap());
+    }
+
+    #[test]
+    fn decodes_fixed_huffman_blocks() {
+        // zlib's compression of "hello hello hello hello", with a back reference.
+        let compressed = [
+            0x78, 0xda, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01, 0x68, 0x03,
+            0x08, 0xb1,
+        ];
+        assert_eq!(
+            b"hello hello hello hello".to_vec(),
+            decompress(&compressed).unwrap()
+        );
+    }
+
+    #[test]
+    fn decodes_dynamic_huffman_blocks() {
+        // zlib's run-length compression of "aaaaaaab" twelve times, which overlaps its copies.
+        let compressed = [
+            0x78, 0x01, 0x25, 0xc1, 0x31, 0x01, 0x00, 0x00, 0x00, 0x40, 0xb0, 0xac, 0xf4, 0x0f,
+            0xe1, 0xb0, 0x31, 0x99, 0x4c, 0x26, 0x93, 0xc9, 0x64, 0x32, 0x99, 0x4c, 0x66, 0xe7,
+            0x06, 0x24, 0x6d,
+        ];
+        assert_eq!(b"aaaaaaab".repeat(12), decompress(&compressed).unwrap());
+    }
+
+    #[test]
+    fn rejects_corrupt_checksums() {
+        let mut compressed = deflate_zero::compress(b"heights");
+        let last = compressed.len() - 1;
+        compressed[last] ^= 1;
+        assert!(decompress(&compressed).is_err());
+    }
+}
@@ -1,9 +1,17 @@
 mod deflate_zero;
+mod grid;
 mod image;
+mod inflate;
+mod pfm;
+mod ply;
 mod png;
 mod ppm;
 mod util;
 
+pub use grid::read_density_grid;
 pub use image::Image;
+pub use pfm::read_pfm;
+pub use ply::read_ply;
+pub use png::read_png;
 pub use png::PngWriter as Png;
-pub use ppm::Ppm;
+pub use ppm::read_ppm;
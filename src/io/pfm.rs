@@ -0,0 +1,110 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+
+use crate::io::util::next_token;
+use crate::vector::Vector;
+
+/// Reads a color (PF) or grayscale (Pf) PFM image of 32-bit floats, returning its width, height
+/// and pixels from the top row down. Gray samples are copied to all three channels. The samples
+/// are left as they are, without the scale from the header applied.
+pub fn read_pfm(path: &Path) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    parse_pfm(&bytes)
+}
+
+fn parse_pfm(bytes: &[u8]) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+    let mut position = 0;
+    let magic = next_token(bytes, &mut position).ok_or_else(|| invalid("missing magic"))?;
+    let channels = match magic.as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err(invalid("unsupported PFM format")),
+    };
+    let mut dimensions = [0usize; 2];
+    for value in dimensions.iter_mut() {
+        *value = next_token(bytes, &mut position)
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid("malformed PFM header"))?;
+    }
+    let [width, height] = dimensions;
+    // The sign of the scale gives the byte order: negative for little-endian.
+    let scale: f64 = next_token(bytes, &mut position)
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| invalid("malformed PFM header"))?;
+
+    // A single whitespace byte separates the header from the raster.
+    let raster_size = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels * 4))
+        .ok_or_else(|| invalid("PFM image is too large"))?;
+    let raster = bytes
+        .get(position + 1..)
+        .and_then(|raster| raster.get(..raster_size))
+        .ok_or_else(|| invalid("truncated PFM raster"))?;
+    let samples: Vec<f64> = raster
+        .chunks(4)
+        .map(|s| {
+            let s = [s[0], s[1], s[2], s[3]];
+            let sample = if scale < 0.0 {
+                f32::from_le_bytes(s)
+            } else {
+                f32::from_be_bytes(s)
+            };
+            sample as f64
+        })
+        .collect();
+
+    // Rows are stored from the bottom of the image up.
+    let pixels = (0..height)
+        .rev()
+        .flat_map(|row| row * width..(row + 1) * width)
+        .map(|pixel| {
+            let first = pixel * channels;
+            if channels == 1 {
+                Vector::new(samples[first], samples[first], samples[first])
+            } else {
+                Vector::new(samples[first], samples[first + 1], samples[first + 2])
+            }
+        })
+        .collect();
+    Ok((width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_grayscale_rows_from_the_bottom_up() {
+        let mut bytes = b"Pf\n2 2\n-1.0\n".to_vec();
+        for sample in &[1.0f32, 2.0, 3.0, 4.5] {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        let (width, height, pixels) = parse_pfm(&bytes).unwrap();
+        assert_eq!((2, 2), (width, height));
+        assert_eq!(3.0, pixels[0].x());
+        assert_eq!(4.5, pixels[1].z());
+        assert_eq!(1.0, pixels[2].y());
+    }
+
+    #[test]
+    fn reads_big_endian_color() {
+        let mut bytes = b"PF 1 1 1.0\n".to_vec();
+        for sample in &[0.25f32, 0.5, 8.0] {
+            bytes.extend_from_slice(&sample.to_be_bytes());
+        }
+        let (_, _, pixels) = parse_pfm(&bytes).unwrap();
+        assert_eq!(0.25, pixels[0].x());
+        assert_eq!(8.0, pixels[0].z());
+        assert!(parse_pfm(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow() {
+        let header = format!("Pf {} 4 -1.0\n", usize::MAX / 2);
+        assert!(parse_pfm(header.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+
+use crate::io::util::next_token;
+use crate::point_cloud::Point;
+use crate::vector::Vector;
+
+/// The color given to points of a file without colors.
+const DEFAULT_COLOR: f64 = 0.8;
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// How the body of a PLY file is written.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+/// The types of values a PLY file can hold.
+#[derive(Clone, Copy, PartialEq)]
+enum Scalar {
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Scalar, Error> {
+        Ok(match name {
+            "char" | "int8" => Scalar::Int8,
+            "uchar" | "uint8" => Scalar::Uint8,
+            "short" | "int16" => Scalar::Int16,
+            "ushort" | "uint16" => Scalar::Uint16,
+            "int" | "int32" => Scalar::Int32,
+            "uint" | "uint32" => Scalar::Uint32,
+            "float" | "float32" => Scalar::Float32,
+            "double" | "float64" => Scalar::Float64,
+            _ => return Err(invalid("unknown PLY property type")),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::Int8 | Scalar::Uint8 => 1,
+            Scalar::Int16 | Scalar::Uint16 => 2,
+            Scalar::Int32 | Scalar::Uint32 | Scalar::Float32 => 4,
+            Scalar::Float64 => 8,
+        }
+    }
+
+    /// The value that stands for full intensity in a color of this type.
+    fn full_intensity(self) -> f64 {
+        match self {
+            Scalar::Uint8 => 255.0,
+            Scalar::Uint16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+struct Property {
+    name: String,
+    scalar: Scalar,
+    /// For list properties, the type of the count that comes before the items.
+    count: Option<Scalar>,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reads the vertices of an ASCII or binary PLY file as points, taking their positions from the
+/// `x`, `y` and `z` properties, their radii from `radius` and their colors from `red`, `green` and
+/// `blue`. Points without a radius get `default_radius`, and points without a color are light
+/// gray. Other elements, such as faces, are skipped.
+pub fn read_ply(path: &Path, default_radius: f64) -> Result<Vec<Point>, Error> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    parse_ply(&bytes, default_radius)
+}
+
+fn parse_ply(bytes: &[u8], default_radius: f64) -> Result<Vec<Point>, Error> {
+    let (format, elements, body) = parse_header(bytes)?;
+    let mut reader = BodyReader {
+        bytes: body,
+        position: 0,
+        format,
+    };
+    for element in &elements {
+        if element.name != "vertex" {
+            for _ in 0..element.count {
+                for property in &element.properties {
+                    reader.skip(property)?;
+                }
+            }
+            continue;
+        }
+
+        let find = |name: &str| element.properties.iter().position(|p| p.name == name);
+        let position = [find("x"), find("y"), find("z")];
+        let position = match position {
+            [Some(x), Some(y), Some(z)] => [x, y, z],
+            _ => return Err(invalid("PLY vertices have no position")),
+        };
+        let radius = find("radius");
+        let color = match [find("red"), find("green"), find("blue")] {
+            [Some(r), Some(g), Some(b)] => Some([r, g, b]),
+            _ => None,
+        };
+
+        // Every vertex takes at least a byte of the body, whatever the header claims.
+        let mut points = Vec::with_capacity(element.count.min(reader.remaining()));
+        let mut values = vec![0.0; element.properties.len()];
+        for _ in 0..element.count {
+            for (value, property) in values.iter_mut().zip(&element.properties) {
+                if property.count.is_some() {
+                    reader.skip(property)?;
+                } else {
+                    *value = reader.read(property.scalar)?;
+                }
+            }
+            let color = match color {
+                Some(channels) => {
+                    let channel = |i: usize| {
+                        let property = &element.properties[channels[i]];
+                        values[channels[i]] / property.scalar.full_intensity()
+                    };
+                    Vector::new(channel(0), channel(1), channel(2))
+                }
+                None => Vector::new(DEFAULT_COLOR, DEFAULT_COLOR, DEFAULT_COLOR),
+            };
+            points.push(Point::new(
+                &Vector::new(
+                    values[position[0]],
+                    values[position[1]],
+                    values[position[2]],
+                ),
+                radius.map_or(default_radius, |radius| values[radius]),
+                &color,
+            ));
+        }
+        return Ok(points);
+    }
+    Err(invalid("PLY file has no vertices"))
+}
+
+/// Reads the header, returning the format, the elements it declares and the body after it.
+fn parse_header(bytes: &[u8]) -> Result<(Format, Vec<Element>, &[u8]), Error> {
+    const END: &[u8] = b"end_header";
+    if !bytes.starts_with(b"ply") {
+        return Err(invalid("not a PLY file"));
+    }
+    let end = bytes
+        .windows(END.len())
+        .position(|window| window == END)
+        .ok_or_else(|| invalid("PLY header does not end"))?;
+    // The body starts on the line after the end of the header.
+    let body = bytes[end..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map_or(bytes.len(), |newline| end + newline + 1);
+    let header = String::from_utf8_lossy(&bytes[..end]);
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for line in header.lines().skip(1) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", name, _version] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(invalid("unknown PLY format")),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| invalid("invalid PLY element count"))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, scalar, name] => elements
+                .last_mut()
+                .ok_or_else(|| invalid("PLY property outside of an element"))?
+                .properties
+                .push(Property {
+                    name: name.to_string(),
+                    scalar: Scalar::parse(scalar)?,
+                    count: Some(Scalar::parse(count)?),
+                }),
+            ["property", scalar, name] => elements
+                .last_mut()
+                .ok_or_else(|| invalid("PLY property outside of an element"))?
+                .properties
+                .push(Property {
+                    name: name.to_string(),
+                    scalar: Scalar::parse(scalar)?,
+                    count: None,
+                }),
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(invalid("invalid PLY header line")),
+        }
+    }
+    let format = format.ok_or_else(|| invalid("PLY header has no format"))?;
+    Ok((format, elements, &bytes[body..]))
+}
+
+/// Reads the values of the body one at a time, in the order the header declares them.
+struct BodyReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    format: Format,
+}
+
+impl BodyReader<'_> {
+    /// How many bytes of the body are left to read.
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    fn read(&mut self, scalar: Scalar) -> Result<f64, Error> {
+        if self.format == Format::Ascii {
+            return next_token(self.bytes, &mut self.position)
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(|| invalid("invalid or missing PLY value"));
+        }
+        let size = scalar.size();
+        let raw = self
+            .bytes
+            .get(self.position..self.position + size)
+            .ok_or_else(|| invalid("PLY body is truncated"))?;
+        self.position += size;
+        let mut data = [0u8; 8];
+        data[..size].copy_from_slice(raw);
+        if self.format == Format::BigEndian {
+            data[..size].reverse();
+        }
+        Ok(match scalar {
+            Scalar::Int8 => data[0] as i8 as f64,
+            Scalar::Uint8 => data[0] as f64,
+            Scalar::Int16 => i16::from_le_bytes([data[0], data[1]]) as f64,
+            Scalar::Uint16 => u16::from_le_bytes([data[0], data[1]]) as f64,
+            Scalar::Int32 => i32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::Uint32 => u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::Float32 => f32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64,
+            Scalar::Float64 => f64::from_le_bytes(data),
+        })
+    }
+
+    fn skip(&mut self, property: &Property) -> Result<(), Error> {
+        let count = match property.count {
+            Some(count) => self.read(count)? as usize,
+            None => 1,
+        };
+        for _ in 0..count {
+            self.read(property.scalar)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn reads_ascii_points_after_other_elements() {
+        let ply = b"ply\nformat ascii 1.0\ncomment made by hand\n\
+            element face 1\nproperty list uchar int vertex_indices\n\
+            element vertex 2\nproperty float x\nproperty float y\nproperty float z\n\
+            property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n\
+            3 0 1 1\n\
+            1 2 3 255 0 51\n\
+            -1.5 0 0.25 0 255 0\n";
+        let points = parse_ply(ply, 0.1).unwrap();
+        assert_eq!(2, points.len());
+        assert!(approx_eq!(f64, 2.0, points[0].position().y()));
+        assert!(approx_eq!(f64, -1.5, points[1].position().x()));
+        assert!(approx_eq!(f64, 0.1, points[0].radius(), epsilon = 1e-7));
+        assert!(approx_eq!(f64, 0.2, points[0].color().z()));
+        assert!(approx_eq!(f64, 1.0, points[1].color().y()));
+    }
+
+    #[test]
+    fn reads_binary_points_of_either_endianness() {
+        for &(format, big_endian) in &[("binary_little_endian", false), ("binary_big_endian", true)]
+        {
+            let mut ply = format!(
+                "ply\nformat {} 1.0\nelement vertex 1\nproperty double x\n\
+                 property double y\nproperty double z\nproperty float radius\nend_header\n",
+                format
+            )
+            .into_bytes();
+            for &value in &[0.5f64, -2.0, 3.0] {
+                let bytes = if big_endian {
+                    value.to_be_bytes()
+                } else {
+                    value.to_le_bytes()
+                };
+                ply.extend_from_slice(&bytes);
+            }
+            let radius = if big_endian {
+                0.25f32.to_be_bytes()
+            } else {
+                0.25f32.to_le_bytes()
+            };
+            ply.extend_from_slice(&radius);
+
+            let points = parse_ply(&ply, 1.0).unwrap();
+            assert_eq!(1, points.len());
+            assert!(approx_eq!(f64, -2.0, points[0].position().y()));
+            assert!(approx_eq!(f64, 0.25, points[0].radius()));
+            assert!(approx_eq!(
+                f64,
+                DEFAULT_COLOR,
+                points[0].color().x(),
+                epsilon = 0.01
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_bodies() {
+        let ply = b"ply\nformat binary_little_endian 1.0\nelement vertex 1\n\
+            property float x\nproperty float y\nproperty float z\nend_header\n\x00\x00";
+        assert!(parse_ply(ply, 1.0).is_err());
+        let ply = b"ply\nformat ascii 1.0\nelement vertex 18446744073709551615\n\
+            property float x\nproperty float y\nproperty float z\nend_header\n0 0 0\n";
+        assert!(parse_ply(ply, 1.0).is_err());
+    }
+}
@@ -1,32 +1,31 @@
 use std::fs::File;
 use std::fs::OpenOptions;
-use std::io::{Error, Write};
+use std::io::{Error, ErrorKind, Read, Write};
 use std::path::Path;
 
 extern crate crc32fast;
 use crc32fast::Hasher;
 
 use crate::io::deflate_zero;
+use crate::io::inflate;
 use crate::io::util;
 use crate::io::Image;
 use crate::vector::Vector;
 
-#[allow(dead_code)]
+const SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+
 pub struct PngWriter {
     file: File,
     data: Vec<u8>,
     width: i32,
-    height: i32,
 }
 
-#[allow(dead_code)]
 enum ChunkType {
     Header,
     Data,
     End,
 }
 
-#[allow(dead_code)]
 impl PngWriter {
     pub fn new(path: &Path, width: i32, height: i32) -> PngWriter {
         let mut file = OpenOptions::new()
@@ -39,14 +38,12 @@ impl PngWriter {
         PngWriter {
             file,
             data: Vec::with_capacity(((4 * width + 1) * height) as usize),
-            width: width,
-            height: height,
+            width,
         }
     }
 
     fn write_header(stream: &mut impl Write, width: i32, height: i32) -> Result<(), Error> {
-        let signature = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
-        stream.write_all(&signature)?;
+        stream.write_all(&SIGNATURE)?;
         let w = util::to_bytes_big_endian(width as u32);
         let h = util::to_bytes_big_endian(height as u32);
         let header = [
@@ -82,7 +79,11 @@ impl PngWriter {
 impl Image for PngWriter {
     fn write_pixel(&mut self, color: &Vector) {
         // Prepend the 0 format token to each scanline.
-        if self.data.len() % (self.width * 4 + 1) as usize == 0 {
+        if self
+            .data
+            .len()
+            .is_multiple_of((self.width * 4 + 1) as usize)
+        {
             self.data.push(0);
         }
         let row = [color.x() as u8, color.y() as u8, color.z() as u8, 0xff];
@@ -97,3 +98,240 @@ impl Drop for PngWriter {
         Self::write_chunk(&mut self.file, ChunkType::End, &[]).unwrap();
     }
 }
+
+/// Reads a non-interlaced grayscale or truecolor PNG image, with or without alpha, of 8 or 16 bits
+/// per sample. Returns its width, height and pixels scaled to `[0, 1]`, from the top row down, with
+/// gray samples copied to all three channels and alpha dropped.
+pub fn read_png(path: &Path) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    parse_png(&bytes)
+}
+
+fn parse_png(bytes: &[u8]) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+    if bytes.get(..8) != Some(&SIGNATURE[..]) {
+        return Err(invalid("missing PNG signature"));
+    }
+    let mut position = 8;
+    let mut header = None;
+    let mut compressed = Vec::new();
+    loop {
+        let length = bytes
+            .get(position..position + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| invalid("truncated PNG chunk"))?;
+        let chunk = bytes
+            .get(position + 4..position + 12 + length)
+            .ok_or_else(|| invalid("truncated PNG chunk"))?;
+        let (chunk_type, data) = (&chunk[..4], &chunk[4..4 + length]);
+        let crc = &chunk[4 + length..];
+        let mut crc32 = Hasher::new();
+        crc32.update(&chunk[..4 + length]);
+        if crc32.finalize() != u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]) {
+            return Err(invalid("PNG chunk checksum does not match"));
+        }
+        position += 12 + length;
+        match chunk_type {
+            b"IHDR" if data.len() == 13 => header = Some(data.to_vec()),
+            b"IDAT" => compressed.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| invalid("missing PNG header"))?;
+    let dimension = |i: usize| {
+        u32::from_be_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]) as usize
+    };
+    let (width, height) = (dimension(0), dimension(4));
+    let (depth, color_type, interlace) = (header[8], header[9], header[12]);
+    let channels = match color_type {
+        0 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return Err(invalid("unsupported PNG color type")),
+    };
+    if depth != 8 && depth != 16 {
+        return Err(invalid("unsupported PNG bit depth"));
+    }
+    if interlace != 0 {
+        return Err(invalid("interlaced PNG images are not supported"));
+    }
+
+    let pixel_size = channels * depth as usize / 8;
+    let too_large = || invalid("PNG image is too large");
+    let stride = width.checked_mul(pixel_size).ok_or_else(too_large)?;
+    let filtered_size = (stride + 1).checked_mul(height).ok_or_else(too_large)?;
+    let raster_size = stride.checked_mul(height).ok_or_else(too_large)?;
+    let filtered = inflate::decompress(&compressed)?;
+    if filtered.len() != filtered_size {
+        return Err(invalid("PNG image data does not match its size"));
+    }
+    let mut raster = vec![0u8; raster_size];
+    for row in 0..height {
+        let line = &filtered[row * (stride + 1)..(row + 1) * (stride + 1)];
+        let (done, rest) = raster.split_at_mut(row * stride);
+        let previous = if row > 0 {
+            &done[(row - 1) * stride..]
+        } else {
+            &[][..]
+        };
+        unfilter(
+            line[0],
+            &line[1..],
+            previous,
+            &mut rest[..stride],
+            pixel_size,
+        )
+        .ok_or_else(|| invalid("unknown PNG filter type"))?;
+    }
+
+    let sample = |i: usize| match depth {
+        8 => raster[i] as f64 / 255.0,
+        _ => u16::from_be_bytes([raster[2 * i], raster[2 * i + 1]]) as f64 / 65535.0,
+    };
+    let pixels = (0..width * height)
+        .map(|pixel| {
+            let first = pixel * channels;
+            if channels < 3 {
+                let gray = sample(first);
+                Vector::new(gray, gray, gray)
+            } else {
+                Vector::new(sample(first), sample(first + 1), sample(first + 2))
+            }
+        })
+        .collect();
+    Ok((width, height, pixels))
+}
+
+/// Undoes the filter applied to a scanline, given the unfiltered line above it (empty for the
+/// first line). Filters predict each byte from the corresponding bytes of the pixel to the left,
+/// the pixel above and the pixel above and to the left.
+fn unfilter(
+    filter: u8,
+    line: &[u8],
+    previous: &[u8],
+    output: &mut [u8],
+    pixel_size: usize,
+) -> Option<()> {
+    for i in 0..line.len() {
+        let left = if i >= pixel_size {
+            output[i - pixel_size]
+        } else {
+            0
+        };
+        let up = previous.get(i).copied().unwrap_or(0);
+        let up_left = if i >= pixel_size {
+            previous.get(i - pixel_size).copied().unwrap_or(0)
+        } else {
+            0
+        };
+        let prediction = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return None,
+        };
+        output[i] = line[i].wrapping_add(prediction);
+    }
+    Some(())
+}
+
+/// Whichever of the neighbouring bytes is closest to `left + up - up_left`.
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// A PNG file holding the given header fields and already filtered image data.
+    fn encode(width: u32, height: u32, depth: u8, color_type: u8, filtered: &[u8]) -> Vec<u8> {
+        let mut bytes = SIGNATURE.to_vec();
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[depth, color_type, 0, 0, 0]);
+        PngWriter::write_chunk(&mut bytes, ChunkType::Header, &header).unwrap();
+        let compressed = deflate_zero::compress(filtered);
+        PngWriter::write_chunk(&mut bytes, ChunkType::Data, &compressed).unwrap();
+        PngWriter::write_chunk(&mut bytes, ChunkType::End, &[]).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn reads_images_written_by_writer() {
+        let path = Path::new("test1.png");
+        {
+            let mut png = PngWriter::new(path, 2, 1);
+            png.write_pixel(&Vector::new(255.0, 0.0, 51.0));
+            png.write_pixel(&Vector::new(0.0, 255.0, 0.0));
+        }
+
+        let (width, height, pixels) = read_png(path).unwrap();
+        assert_eq!((2, 1), (width, height));
+        assert_eq!(1.0, pixels[0].x());
+        assert_eq!(0.2, pixels[0].z());
+        assert_eq!(1.0, pixels[1].y());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn undoes_every_filter_type() {
+        // Three gray pixels a row. Unfiltered, every row counts up in tens from its first pixel,
+        // which is ten times the row number.
+        let filtered = [
+            0, 0, 10, 20, // None
+            1, 10, 10, 10, // Sub
+            2, 10, 10, 10, // Up
+            3, 20, 10, 10, // Average
+            4, 10, 10, 10, // Paeth
+        ];
+        let (width, height, pixels) = parse_png(&encode(3, 5, 8, 0, &filtered)).unwrap();
+        assert_eq!((3, 5), (width, height));
+        for row in 0..5 {
+            for column in 0..3 {
+                let expected = (10 * (row + column)) as f64 / 255.0;
+                assert_eq!(expected, pixels[row * 3 + column].y());
+            }
+        }
+    }
+
+    #[test]
+    fn reads_sixteen_bit_gray_with_alpha() {
+        let filtered = [0, 0x12, 0x34, 0xff, 0xff, 0xff, 0xff, 0, 0];
+        let (_, _, pixels) = parse_png(&encode(2, 1, 16, 4, &filtered)).unwrap();
+        assert_eq!(0x1234 as f64 / 65535.0, pixels[0].x());
+        assert_eq!(0x1234 as f64 / 65535.0, pixels[0].z());
+        assert_eq!(1.0, pixels[1].y());
+    }
+
+    #[test]
+    fn rejects_corrupt_chunks() {
+        let mut bytes = encode(1, 1, 8, 0, &[0, 7]);
+        bytes[20] ^= 1;
+        assert!(parse_png(&bytes).is_err());
+        assert!(parse_png(&encode(1, 1, 8, 3, &[0, 7])).is_err());
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow() {
+        let bytes = encode(u32::MAX, u32::MAX, 16, 6, &[0, 7]);
+        assert!(parse_png(&bytes).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use std::fs::File;
 use std::fs::OpenOptions;
-use std::io::{Error, Write};
+use std::io::{Error, ErrorKind, Read, Write};
 use std::path::Path;
 
+use crate::io::util::next_token;
 use crate::io::Image;
 use crate::vector::Vector;
 
@@ -11,6 +12,7 @@ pub struct Ppm {
 }
 
 impl Ppm {
+    // Renders are written as PNG; this plain-text writer is only exercised by the tests below.
     #[allow(dead_code)]
     pub fn new(path: &Path, image_width: i32, image_height: i32) -> Self {
         let mut file = OpenOptions::new()
@@ -45,6 +47,69 @@ impl Image for Ppm {
     }
 }
 
+/// Reads a binary (P6) or plain (P3) PPM image, returning its width, height and pixels scaled to
+/// `[0, 1]`, from the top row down.
+pub fn read_ppm(path: &Path) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    parse_ppm(&bytes)
+}
+
+fn parse_ppm(bytes: &[u8]) -> Result<(usize, usize, Vec<Vector>), Error> {
+    let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+    let mut position = 0;
+    let magic = next_token(bytes, &mut position).ok_or_else(|| invalid("missing magic"))?;
+    let binary = match magic.as_str() {
+        "P6" => true,
+        "P3" => false,
+        _ => return Err(invalid("unsupported PPM format")),
+    };
+    let mut header = [0usize; 3];
+    for value in header.iter_mut() {
+        *value = next_token(bytes, &mut position)
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid("malformed PPM header"))?;
+    }
+    let [width, height, max_value] = header;
+    if max_value == 0 || max_value > 255 {
+        return Err(invalid("unsupported PPM sample depth"));
+    }
+    if width == 0 || height == 0 {
+        return Err(invalid("empty PPM image"));
+    }
+
+    let sample_count = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .ok_or_else(|| invalid("PPM image is too large"))?;
+    let samples: Vec<usize> = if binary {
+        // A single whitespace byte separates the header from the raster.
+        let raster = bytes
+            .get(position + 1..)
+            .and_then(|raster| raster.get(..sample_count))
+            .ok_or_else(|| invalid("truncated PPM raster"))?;
+        raster.iter().map(|&b| b as usize).collect()
+    } else {
+        (0..sample_count)
+            .map(|_| next_token(bytes, &mut position).and_then(|token| token.parse().ok()))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("truncated PPM raster"))?
+    };
+
+    let scale = 1.0 / max_value as f64;
+    let pixels = samples
+        .chunks(3)
+        .map(|rgb| {
+            Vector::new(
+                rgb[0] as f64 * scale,
+                rgb[1] as f64 * scale,
+                rgb[2] as f64 * scale,
+            )
+        })
+        .collect();
+    Ok((width, height, pixels))
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -76,4 +141,46 @@ mod tests {
 
         fs::remove_file(path).unwrap();
     }
+
+    #[test]
+    fn reads_plain_ppm_written_by_writer() {
+        let path = Path::new("test3.ppm");
+        {
+            let mut ppm = Ppm::new(path, 2, 1);
+            ppm.write_pixel(&Vector::new(255.0, 0.0, 51.0));
+            ppm.write_pixel(&Vector::new(0.0, 255.0, 0.0));
+        }
+
+        let (width, height, pixels) = read_ppm(path).unwrap();
+        assert_eq!((2, 1), (width, height));
+        assert_eq!(1.0, pixels[0].x());
+        assert_eq!(0.2, pixels[0].z());
+        assert_eq!(1.0, pixels[1].y());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parses_binary_ppm_with_comments() {
+        let mut bytes = b"P6\n# a comment\n1 2\n255\n".to_vec();
+        bytes.extend_from_slice(&[255, 0, 0, 0, 0, 255]);
+        let (width, height, pixels) = parse_ppm(&bytes).unwrap();
+        assert_eq!((1, 2), (width, height));
+        assert_eq!(1.0, pixels[0].x());
+        assert_eq!(1.0, pixels[1].z());
+    }
+
+    #[test]
+    fn rejects_truncated_raster() {
+        let bytes = b"P6 2 2 255\n\x01\x02".to_vec();
+        assert!(parse_ppm(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_and_oversized_images() {
+        assert!(parse_ppm(b"P3 0 2 255\n").is_err());
+        assert!(parse_ppm(b"P3 2 0 255\n").is_err());
+        let huge = format!("P6 {} {} 255\n", usize::MAX / 2, 3);
+        assert!(parse_ppm(huge.as_bytes()).is_err());
+    }
 }
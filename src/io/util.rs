@@ -7,6 +7,30 @@ pub fn to_bytes_big_endian(value: u32) -> [u8; 4] {
     ]
 }
 
+/// Reads the next whitespace-delimited token of a text header, skipping `#` comments.
+pub fn next_token(bytes: &[u8], position: &mut usize) -> Option<String> {
+    loop {
+        while *position < bytes.len() && bytes[*position].is_ascii_whitespace() {
+            *position += 1;
+        }
+        if *position < bytes.len() && bytes[*position] == b'#' {
+            while *position < bytes.len() && bytes[*position] != b'\n' {
+                *position += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    let start = *position;
+    while *position < bytes.len() && !bytes[*position].is_ascii_whitespace() {
+        *position += 1;
+    }
+    if start == *position {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes[start..*position]).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
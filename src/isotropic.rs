@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+pub struct Isotropic {
+    albedo: Rc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: &Vector) -> Isotropic {
+        Self::textured(Rc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Rc<dyn Texture>) -> Isotropic {
+        Isotropic { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        // The isotropic phase function scatters uniformly over the whole sphere.
+        let scattered = ray_in.spawn(&hit_record.p, &Vector::random_unit());
+        let attenuation = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        (true, attenuation, scattered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scatters_uniformly_from_the_hit_with_the_albedo() {
+        let isotropic = Isotropic::new(&Vector::new(0.2, 0.4, 0.6));
+        let hit_record = HitRecord {
+            p: Vector::new(1.0, 2.0, 3.0),
+            ..HitRecord::empty()
+        };
+        let ray = Ray::new(&Vector::empty(), &Vector::new(1.0, 0.0, 0.0));
+        let samples = 20_000;
+        let mut mean = Vector::empty();
+        for _ in 0..samples {
+            let (scattered, attenuation, scattered_ray) = isotropic.scatter(&ray, &hit_record);
+            assert!(scattered);
+            assert_eq!(0.4, attenuation.y());
+            assert_eq!(2.0, scattered_ray.origin().y());
+            mean = mean + scattered_ray.direction().unit_vector();
+        }
+        // Scattering forward and back are equally likely, as are all other directions.
+        assert!((&mean / samples as f64).length() < 0.03);
+    }
+}
@@ -1,26 +1,51 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
 use crate::hittable::HitRecord;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
 use crate::vector::Vector;
 
 pub struct Lambertian {
-    albedo: Vector,
+    albedo: Rc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: &Vector) -> Lambertian {
         Lambertian {
-            albedo: albedo.clone(),
+            albedo: Rc::new(SolidColor::new(albedo)),
         }
     }
+
+    pub fn textured(albedo: Rc<dyn Texture>) -> Lambertian {
+        Lambertian { albedo }
+    }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
         let scatter_direction = &hit_record.normal + Vector::random_unit();
         //let scatter_direction = &hit_record.normal + Vector::random_in_hemisphere(&hit_record.normal);  // hemispherical scattering
-        let scattered = Ray::new(&hit_record.p, &scatter_direction);
-        let attenuation = self.albedo.clone();
-        (true, attenuation, scattered)
+        let scattered = ray_in.spawn(&hit_record.p, &scatter_direction);
+        let attenuation = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        // A perturbed shading normal can send light through the real surface; absorb it instead.
+        let scatter = Vector::dot(scattered.direction(), &hit_record.geometric_normal) > 0.0;
+        (scatter, attenuation, scattered)
+    }
+
+    fn eval(&self, hit_record: &HitRecord, _wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let cosine = Vector::dot(wi, &hit_record.normal.unit_vector());
+        if cosine <= 0.0 || Vector::dot(wi, &hit_record.geometric_normal) <= 0.0 {
+            return Some(Vector::empty());
+        }
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        Some(albedo * (cosine / PI))
+    }
+
+    /// Adding a random unit vector to the normal picks directions with a cosine-weighted density.
+    fn pdf(&self, hit_record: &HitRecord, _wo: &Vector, wi: &Vector) -> f64 {
+        Vector::dot(wi, &hit_record.normal.unit_vector()).max(0.0) / PI
     }
 }
@@ -1,16 +1,57 @@
+mod aabb;
+mod bump_map;
 mod camera;
+mod coated;
 mod color;
+mod conductor;
+mod cone;
+mod constant_medium;
+mod csg;
+mod cuboid;
+mod curve;
+mod cutout;
+mod cylinder;
+mod density_grid;
 mod dielectric;
+mod diffuse_light;
+mod disk;
 mod environments;
+mod fresnel;
+mod grid_medium;
+mod hair;
+mod heightfield;
+mod henyey_greenstein;
 mod hittable;
 mod io;
+mod isotropic;
 mod lambertian;
 mod material;
+mod math;
 mod metal;
+mod microfacet;
+mod mix_material;
+mod moving_sphere;
+mod normal_map;
+mod onb;
+mod oren_nayar;
+mod paraboloid;
+mod plane;
+mod point_cloud;
+mod principled;
+mod quad;
 mod ray;
 mod render;
+mod rough_dielectric;
 mod scenery;
+mod sdf;
+mod sdf_object;
+mod spectrum;
 mod sphere;
+mod subsurface;
+mod textures;
+mod thin_film;
+mod torus;
+mod transformed;
 mod vector;
 
 extern crate clap;
@@ -37,21 +78,117 @@ fn main() {
                 .help("The path of the rendered image.")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("scene")
+                .short("s")
+                .long("scene")
+                .value_name("SCENE")
+                .help("The scene to render.")
+                .possible_values(&[
+                    "spheres",
+                    "volumes",
+                    "clouds",
+                    "dispersion",
+                    "textures",
+                    "noise",
+                    "metals",
+                    "glass",
+                    "liquids",
+                    "principled",
+                    "diffuse",
+                    "layers",
+                    "iridescence",
+                    "subsurface",
+                    "cutout",
+                    "boxes",
+                    "cornell",
+                    "instances",
+                    "motion",
+                    "quadrics",
+                    "rings",
+                    "fields",
+                    "booleans",
+                    "terrain",
+                    "hair",
+                    "particles",
+                ])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("grid")
+                .long("grid")
+                .value_name("FILE")
+                .help("A raw density grid to render in the clouds scene.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("texture")
+                .long("texture")
+                .value_name("FILE")
+                .help("A PPM image to wrap around a ball in the textures scene.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("heightmap")
+                .long("heightmap")
+                .value_name("FILE")
+                .help("A grayscale PNG or PFM height map to render in the terrain scene.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("points")
+                .long("points")
+                .value_name("FILE")
+                .help("An ASCII or binary PLY point cloud to render in the particles scene.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("spectral")
+                .long("spectral")
+                .help("Traces sampled wavelengths instead of RGB colors."),
+        )
         .get_matches();
 
     let file_location = matches.value_of("output").unwrap_or("output.png");
-    let environment = environments::spheres::get_environment();
+    let environment = match matches.value_of("scene").unwrap_or("spheres") {
+        "volumes" => environments::volumes::get_environment(),
+        "dispersion" => environments::dispersion::get_environment(),
+        "noise" => environments::noise::get_environment(),
+        "metals" => environments::metals::get_environment(),
+        "glass" => environments::glass::get_environment(),
+        "liquids" => environments::liquids::get_environment(),
+        "principled" => environments::principled::get_environment(),
+        "diffuse" => environments::diffuse::get_environment(),
+        "layers" => environments::layers::get_environment(),
+        "iridescence" => environments::iridescence::get_environment(),
+        "subsurface" => environments::subsurface::get_environment(),
+        "cutout" => environments::cutout::get_environment(),
+        "boxes" => environments::boxes::get_environment(),
+        "cornell" => environments::cornell::get_environment(),
+        "instances" => environments::instances::get_environment(),
+        "motion" => environments::motion::get_environment(),
+        "quadrics" => environments::quadrics::get_environment(),
+        "rings" => environments::rings::get_environment(),
+        "fields" => environments::fields::get_environment(),
+        "booleans" => environments::booleans::get_environment(),
+        "hair" => environments::hair::get_environment(),
+        "textures" => {
+            environments::textures::get_environment(matches.value_of("texture").map(Path::new))
+        }
+        "clouds" => environments::clouds::get_environment(matches.value_of("grid").map(Path::new)),
+        "terrain" => {
+            environments::terrain::get_environment(matches.value_of("heightmap").map(Path::new))
+        }
+        "particles" => {
+            environments::particles::get_environment(matches.value_of("points").map(Path::new))
+        }
+        _ => environments::spheres::get_environment(),
+    };
     let mut file = Png::new(
         Path::new(file_location),
         environment.image.width,
         environment.image.height,
     );
-    render_scenery(
-        environment.scenery,
-        &environment.camera,
-        (environment.image.width, environment.image.height),
-        &mut file,
-        environment.image.supersampling_ratio,
-    );
+    render_scenery(&environment, &mut file, matches.is_present("spectral"));
     println!("\nDone.");
 }
@@ -4,4 +4,30 @@ use crate::vector::Vector;
 
 pub trait Material {
     fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray);
+
+    /// The light the surface gives off at a hit.
+    fn emitted(&self, _hit_record: &HitRecord) -> Vector {
+        Vector::empty()
+    }
+
+    /// The BSDF times the cosine of the angle between `wi` and the shading normal, for light
+    /// arriving from `wi` and leaving towards `wo`, or the phase function for media. Both
+    /// directions point away from the hit and have unit length. Materials that can't be
+    /// evaluated, such as smooth mirrors and glass, return `None` and are left to `scatter` alone
+    /// when sampling lights.
+    fn eval(&self, _hit_record: &HitRecord, _wo: &Vector, _wi: &Vector) -> Option<Vector> {
+        None
+    }
+
+    /// The density, over solid angle, with which `scatter` picks `wi` for light leaving towards
+    /// `wo`. Zero for materials that can't be evaluated.
+    fn pdf(&self, _hit_record: &HitRecord, _wo: &Vector, _wi: &Vector) -> f64 {
+        0.0
+    }
+
+    /// Whether the surface is really there at a hit. Shapes skip hits where it isn't, so that
+    /// materials can cut holes in the geometry they are on, for every ray that is traced.
+    fn is_opaque(&self, _hit_record: &HitRecord) -> bool {
+        true
+    }
 }
@@ -0,0 +1,201 @@
+/// The real roots of `a t² + b t + c = 0`, in increasing order. Falls back to the linear equation
+/// when `a` vanishes.
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            return Vec::new();
+        }
+        return vec![-c / b];
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+    // Avoids cancellation between -b and the root of the discriminant.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return vec![0.0, 0.0];
+    }
+    let (t0, t1) = (q / a, c / q);
+    if t0 < t1 {
+        vec![t0, t1]
+    } else {
+        vec![t1, t0]
+    }
+}
+
+/// The real roots of `a t³ + b t² + c t + d = 0`, in increasing order, found with Cardano's
+/// formula when there is one real root and Vieta's trigonometric form when there are three.
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        return solve_quadratic(b, c, d);
+    }
+    let (b, c, d) = (b / a, c / a, d / a);
+    // t = x - b / 3 leaves x³ + p x + q.
+    let shift = -b / 3.0;
+    let p = c - b * b / 3.0;
+    let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    let mut roots = if p.abs() < 1e-14 {
+        vec![(-q).cbrt()]
+    } else if discriminant > 0.0 {
+        let root = discriminant.sqrt();
+        vec![(-q / 2.0 + root).cbrt() + (-q / 2.0 - root).cbrt()]
+    } else {
+        let scale = 2.0 * (-p / 3.0).sqrt();
+        let angle = ((3.0 * q / (2.0 * p)) * (-3.0 / p).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos()
+            / 3.0;
+        (0..3)
+            .map(|k| scale * (angle - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos())
+            .collect()
+    };
+    for root in roots.iter_mut() {
+        *root = polish(&[1.0, b, c, d], *root + shift);
+    }
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// The real roots of `a t⁴ + b t³ + c t² + d t + e = 0`, in increasing order. Ferrari's method
+/// splits the quartic into two quadratics using a root of its resolvent cubic, and each root is
+/// then refined with Newton's method against the original polynomial.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        return solve_cubic(b, c, d, e);
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    // t = y - b / 4 leaves y⁴ + p y² + q y + r.
+    let shift = -b / 4.0;
+    let b2 = b * b;
+    let p = c - 3.0 * b2 / 8.0;
+    let q = d - b * c / 2.0 + b2 * b / 8.0;
+    let r = e - b * d / 4.0 + b2 * c / 16.0 - 3.0 * b2 * b2 / 256.0;
+
+    let mut roots = Vec::with_capacity(4);
+    if q.abs() < 1e-12 {
+        // A quadratic in y².
+        for z in solve_quadratic(1.0, p, r) {
+            if z >= 0.0 {
+                roots.push(z.sqrt());
+                roots.push(-z.sqrt());
+            }
+        }
+    } else {
+        // y⁴ + p y² + q y + r = (y² + p/2 + m)² - (s y - q / 2s)² for s = √(2m), where m is a
+        // positive root of the resolvent cubic.
+        let m = solve_cubic(8.0, 8.0 * p, 2.0 * p * p - 8.0 * r, -q * q)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if m <= 0.0 {
+            return Vec::new();
+        }
+        let s = (2.0 * m).sqrt();
+        roots.extend(solve_quadratic(1.0, -s, p / 2.0 + m + q / (2.0 * s)));
+        roots.extend(solve_quadratic(1.0, s, p / 2.0 + m - q / (2.0 * s)));
+    }
+    let coefficients = [1.0, b, c, d, e];
+    let mut roots: Vec<f64> = roots
+        .into_iter()
+        .map(|y| polish(&coefficients, y + shift))
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Refines a root of the polynomial with the given coefficients, highest power first, with a few
+/// steps of Newton's method. Steps that would make the residual worse are not taken.
+fn polish(coefficients: &[f64], mut root: f64) -> f64 {
+    let evaluate = |x: f64| {
+        coefficients
+            .iter()
+            .fold((0.0, 0.0), |(value, derivative), &coefficient| {
+                (value * x + coefficient, derivative * x + value)
+            })
+    };
+    for _ in 0..4 {
+        let (value, derivative) = evaluate(root);
+        if value == 0.0 || derivative.abs() < 1e-300 {
+            break;
+        }
+        let next = root - value / derivative;
+        if evaluate(next).0.abs() >= value.abs() {
+            break;
+        }
+        root = next;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn quadratic_roots_are_sorted_and_accurate() {
+        let roots = solve_quadratic(2.0, -2.0, -12.0);
+        assert_eq!(2, roots.len());
+        assert!(approx_eq!(f64, -2.0, roots[0]));
+        assert!(approx_eq!(f64, 3.0, roots[1]));
+        // A tiny root next to a huge one survives without cancellation.
+        let roots = solve_quadratic(1.0, -1e8, 1.0);
+        assert!(approx_eq!(f64, 1e-8, roots[0], epsilon = 1e-20));
+        assert!(solve_quadratic(1.0, 0.0, 1.0).is_empty());
+        assert_eq!(vec![2.0], solve_quadratic(0.0, 2.0, -4.0));
+    }
+
+    fn assert_roots(expected: &[f64], actual: &[f64], epsilon: f64) {
+        assert_eq!(expected.len(), actual.len(), "{:?}", actual);
+        for (expected, actual) in expected.iter().zip(actual) {
+            assert!(
+                approx_eq!(f64, *expected, *actual, epsilon = epsilon),
+                "{:?}",
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn cubic_roots_cover_one_and_three_real_roots() {
+        // (x - 1)(x - 2)(x + 3)
+        assert_roots(&[-3.0, 1.0, 2.0], &solve_cubic(1.0, 0.0, -7.0, 6.0), 1e-12);
+        // (x - 2)(x² + 1)
+        assert_roots(&[2.0], &solve_cubic(2.0, -4.0, 2.0, -4.0), 1e-12);
+        // (x + 1)³
+        assert_roots(&[-1.0], &solve_cubic(1.0, 3.0, 3.0, 1.0), 1e-5);
+    }
+
+    #[test]
+    fn quartic_roots_are_found_and_polished() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        let roots = solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0);
+        assert_roots(&[1.0, 2.0, 3.0, 4.0], &roots, 1e-12);
+        // (x² + 1)(x - 0.5)(x + 7), with only two real roots.
+        let roots = solve_quartic(1.0, 6.5, -2.5, 6.5, -3.5);
+        assert_roots(&[-7.0, 0.5], &roots, 1e-12);
+        // A biquadratic, (x² - 4)(x² - 9).
+        let roots = solve_quartic(2.0, 0.0, -26.0, 0.0, 72.0);
+        assert_roots(&[-3.0, -2.0, 2.0, 3.0], &roots, 1e-12);
+        // No real roots at all.
+        assert!(solve_quartic(1.0, 0.0, 2.0, 0.0, 1.5).is_empty());
+    }
+
+    #[test]
+    fn quartic_roots_of_very_different_sizes_stay_accurate() {
+        // (x - 1e-3)(x - 1)(x - 10)(x - 1000)
+        let roots = [1e-3, 1.0, 10.0, 1000.0];
+        let (r0, r1, r2, r3) = (roots[0], roots[1], roots[2], roots[3]);
+        let b = -(r0 + r1 + r2 + r3);
+        let c = r0 * r1 + r0 * r2 + r0 * r3 + r1 * r2 + r1 * r3 + r2 * r3;
+        let d = -(r0 * r1 * r2 + r0 * r1 * r3 + r0 * r2 * r3 + r1 * r2 * r3);
+        let e = r0 * r1 * r2 * r3;
+        let found = solve_quartic(1.0, b, c, d, e);
+        assert_eq!(4, found.len(), "{:?}", found);
+        for (expected, actual) in roots.iter().zip(&found) {
+            assert!((expected - actual).abs() < 1e-9 * expected, "{:?}", found);
+        }
+    }
+}
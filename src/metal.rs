@@ -1,17 +1,28 @@
+use std::rc::Rc;
+
 use crate::hittable::HitRecord;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
 use crate::vector::Vector;
 
 pub struct Metal {
-    albedo: Vector,
+    albedo: Rc<dyn Texture>,
     fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: &Vector, fuzz: f64) -> Metal {
         Metal {
-            albedo: albedo.clone(),
+            albedo: Rc::new(SolidColor::new(albedo)),
+            fuzz: if fuzz < 1.0 { fuzz } else { 1.0 },
+        }
+    }
+
+    pub fn textured(albedo: Rc<dyn Texture>, fuzz: f64) -> Metal {
+        Metal {
+            albedo,
             fuzz: if fuzz < 1.0 { fuzz } else { 1.0 },
         }
     }
@@ -24,12 +35,13 @@ impl Metal {
 impl Material for Metal {
     fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
         let reflected = Self::reflect(&ray_in.direction().unit_vector(), &hit_record.normal);
-        let scattered = Ray::new(
+        let scattered = ray_in.spawn(
             &hit_record.p,
             &(reflected + self.fuzz * Vector::random_in_unit_sphere()),
         );
-        let attenuation = self.albedo.clone();
-        let scatter = Vector::dot(scattered.direction(), &hit_record.normal) > 0.0;
+        let attenuation = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        let scatter = Vector::dot(scattered.direction(), &hit_record.normal) > 0.0
+            && Vector::dot(scattered.direction(), &hit_record.geometric_normal) > 0.0;
         (scatter, attenuation, scattered)
     }
 }
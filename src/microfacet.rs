@@ -0,0 +1,240 @@
+use crate::vector::Vector;
+
+/// The GGX (Trowbridge-Reitz) microfacet distribution with Smith height-correlated
+/// shadowing-masking. Directions are expressed in a local frame with the normal along +z. The
+/// distribution may be stretched differently along the tangent (x) and bitangent (y).
+pub struct Ggx {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl Ggx {
+    pub fn new(alpha: f64) -> Ggx {
+        Self::anisotropic(alpha, alpha)
+    }
+
+    pub fn anisotropic(alpha_x: f64, alpha_y: f64) -> Ggx {
+        Ggx {
+            alpha_x: alpha_x.max(1e-4),
+            alpha_y: alpha_y.max(1e-4),
+        }
+    }
+
+    /// Maps a perceptually linear roughness in `[0, 1]` to the distribution's width.
+    pub fn from_roughness(roughness: f64) -> Ggx {
+        Self::new(roughness * roughness)
+    }
+
+    /// The density of microfacets oriented along `h`.
+    pub fn d(&self, h: &Vector) -> f64 {
+        if h.z() <= 0.0 {
+            return 0.0;
+        }
+        let x = h.x() / self.alpha_x;
+        let y = h.y() / self.alpha_y;
+        let denominator = x * x + y * y + h.z() * h.z();
+        1.0 / (std::f64::consts::PI * self.alpha_x * self.alpha_y * denominator * denominator)
+    }
+
+    fn lambda(&self, w: &Vector) -> f64 {
+        let cos2 = w.z() * w.z();
+        if cos2 == 0.0 {
+            return f64::INFINITY;
+        }
+        let x = self.alpha_x * w.x();
+        let y = self.alpha_y * w.y();
+        0.5 * (-1.0 + (1.0 + (x * x + y * y) / cos2).sqrt())
+    }
+
+    /// The fraction of microfacets visible from `w`.
+    pub fn g1(&self, w: &Vector) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// The fraction of microfacets visible from both `wo` and `wi`.
+    pub fn g2(&self, wo: &Vector, wi: &Vector) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// The density of microfacet normals as seen from `wo`.
+    pub fn visible_d(&self, wo: &Vector, h: &Vector) -> f64 {
+        self.g1(wo) * Vector::dot(wo, h).max(0.0) * self.d(h) / wo.z().abs()
+    }
+
+    /// Samples a microfacet normal visible from `wo`, following Heitz's method of sampling the
+    /// projected area of a stretched hemisphere.
+    pub fn sample_visible_normal(&self, wo: &Vector, u1: f64, u2: f64) -> Vector {
+        let vh = Vector::new(self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()).unit_vector();
+        let length_squared = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if length_squared > 0.0 {
+            Vector::new(-vh.y(), vh.x(), 0.0) / length_squared.sqrt()
+        } else {
+            Vector::new(1.0, 0.0, 0.0)
+        };
+        let t2 = Vector::cross(&vh, &t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * std::f64::consts::PI * u2;
+        let p1 = r * phi.cos();
+        let p2 = r * phi.sin();
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * p2;
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        Vector::new(
+            self.alpha_x * nh.x(),
+            self.alpha_y * nh.y(),
+            nh.z().max(1e-6),
+        )
+        .unit_vector()
+    }
+}
+
+/// Reflects `wo` about the microfacet normal `h`.
+pub fn reflect(wo: &Vector, h: &Vector) -> Vector {
+    2.0 * Vector::dot(wo, h) * h - wo
+}
+
+/// Refracts `wo` through the microfacet normal `h`, where `eta` is the ratio of the refractive
+/// index below the microfacet to the index above it. Returns `None` on total internal reflection.
+pub fn refract(wo: &Vector, h: &Vector, eta: f64) -> Option<Vector> {
+    let cos_theta_i = Vector::dot(wo, h);
+    let sin2_theta_t = (1.0 - cos_theta_i * cos_theta_i).max(0.0) / (eta * eta);
+    if sin2_theta_t >= 1.0 {
+        return None;
+    }
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+    Some(-wo / eta + (cos_theta_i / eta - cos_theta_t) * h)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn direction(theta: f64) -> Vector {
+        Vector::new(theta.sin(), 0.0, theta.cos())
+    }
+
+    fn uniform_sphere(rng: &mut StdRng) -> Vector {
+        let z = rng.gen_range(-1.0, 1.0);
+        let phi = rng.gen_range(0.0, 2.0 * std::f64::consts::PI);
+        let r = (1.0f64 - z * z).sqrt();
+        Vector::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    #[test]
+    fn distribution_is_normalized_over_projected_area() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for &alpha in &[0.2, 0.5, 1.0] {
+            let ggx = Ggx::new(alpha);
+            let samples = 50_000;
+            let integral = (0..samples)
+                .map(|_| {
+                    let h = uniform_sphere(&mut rng);
+                    ggx.d(&h) * h.z().max(0.0)
+                })
+                .sum::<f64>()
+                * 4.0
+                * std::f64::consts::PI
+                / samples as f64;
+            assert!(
+                (integral - 1.0).abs() < 0.05,
+                "alpha {}: {}",
+                alpha,
+                integral
+            );
+        }
+    }
+
+    // The weak white furnace test: the visible normal distribution integrates to one.
+    #[test]
+    fn visible_normals_pass_weak_white_furnace_test() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for &alpha in &[0.3, 0.6, 1.0] {
+            let ggx = Ggx::new(alpha);
+            for &theta in &[0.0, 0.8, 1.3] {
+                let wo = direction(theta);
+                let samples = 50_000;
+                let integral = (0..samples)
+                    .map(|_| ggx.visible_d(&wo, &uniform_sphere(&mut rng)))
+                    .sum::<f64>()
+                    * 4.0
+                    * std::f64::consts::PI
+                    / samples as f64;
+                assert!(
+                    (integral - 1.0).abs() < 0.05,
+                    "alpha {}: {}",
+                    alpha,
+                    integral
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn anisotropic_visible_normals_pass_weak_white_furnace_test() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let ggx = Ggx::anisotropic(0.2, 0.8);
+        for wo in &[direction(0.7), Vector::new(0.0, 0.6, 0.8)] {
+            let samples = 50_000;
+            let integral = (0..samples)
+                .map(|_| ggx.visible_d(wo, &uniform_sphere(&mut rng)))
+                .sum::<f64>()
+                * 4.0
+                * std::f64::consts::PI
+                / samples as f64;
+            assert!((integral - 1.0).abs() < 0.05, "{}", integral);
+        }
+    }
+
+    #[test]
+    fn refraction_obeys_snells_law() {
+        let wo = direction(0.6);
+        let h = Vector::new(0.0, 0.0, 1.0);
+        let wi = refract(&wo, &h, 1.5).unwrap();
+        assert!((wi.length() - 1.0).abs() < 1e-9);
+        assert!(wi.z() < 0.0);
+        let sin_t = (wi.x() * wi.x() + wi.y() * wi.y()).sqrt();
+        assert!((0.6f64.sin() - 1.5 * sin_t).abs() < 1e-9);
+        assert!(refract(&direction(1.2), &h, 1.0 / 1.5).is_none());
+    }
+
+    #[test]
+    fn sampled_normals_are_visible() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let ggx = Ggx::new(0.7);
+        let wo = direction(1.2);
+        for _ in 0..1000 {
+            let h = ggx.sample_visible_normal(&wo, rng.gen(), rng.gen());
+            assert!(h.z() > 0.0);
+            assert!(Vector::dot(&wo, &h) >= -1e-9);
+            assert!((h.length() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    // Compare the mean of the sampled visible normals' z with a direct integral.
+    #[test]
+    fn visible_normal_sampling_matches_its_density() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let ggx = Ggx::new(0.5);
+        let wo = direction(0.9);
+        let samples = 50_000;
+        let sampled = (0..samples)
+            .map(|_| ggx.sample_visible_normal(&wo, rng.gen(), rng.gen()).x())
+            .sum::<f64>()
+            / samples as f64;
+        let integrated = (0..samples)
+            .map(|_| {
+                let h = uniform_sphere(&mut rng);
+                h.x() * ggx.visible_d(&wo, &h)
+            })
+            .sum::<f64>()
+            * 4.0
+            * std::f64::consts::PI
+            / samples as f64;
+        assert!((sampled - integrated).abs() < 0.01);
+    }
+}
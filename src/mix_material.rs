@@ -0,0 +1,189 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Blends two materials by picking one at random for each scattering event. The weight is the
+/// probability of picking the second material, read from the mean of a texture's channels.
+pub struct MixMaterial {
+    first: Rc<dyn Material>,
+    second: Rc<dyn Material>,
+    weight: Rc<dyn Texture>,
+}
+
+impl MixMaterial {
+    // The scenes so far all blend by texture, so only the tests use a constant weight.
+    #[allow(dead_code)]
+    pub fn new(first: Rc<dyn Material>, second: Rc<dyn Material>, weight: f64) -> MixMaterial {
+        Self::textured(first, second, Rc::new(SolidColor::uniform(weight)))
+    }
+
+    pub fn textured(
+        first: Rc<dyn Material>,
+        second: Rc<dyn Material>,
+        weight: Rc<dyn Texture>,
+    ) -> MixMaterial {
+        MixMaterial {
+            first,
+            second,
+            weight,
+        }
+    }
+
+    fn weight_at(&self, hit_record: &HitRecord) -> f64 {
+        let value = self.weight.value(hit_record.u, hit_record.v, &hit_record.p);
+        (value.x() + value.y() + value.z()) / 3.0
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        if rand::thread_rng().gen::<f64>() < self.weight_at(hit_record) {
+            self.second.scatter(ray_in, hit_record)
+        } else {
+            self.first.scatter(ray_in, hit_record)
+        }
+    }
+
+    /// The light given off by both materials, weighted as `scatter` picks them, wherever the
+    /// surface is there.
+    fn emitted(&self, hit_record: &HitRecord) -> Vector {
+        if !self.is_opaque(hit_record) {
+            return Vector::empty();
+        }
+        let weight = self.weight_at(hit_record);
+        (1.0 - weight) * self.first.emitted(hit_record) + weight * self.second.emitted(hit_record)
+    }
+
+    /// The mean of the two materials weighted as `scatter` picks them. Either may be a mirror or
+    /// glass that can't be evaluated; it then adds nothing, and is left to `scatter`.
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let weight = self.weight_at(hit_record);
+        match (
+            self.first.eval(hit_record, wo, wi),
+            self.second.eval(hit_record, wo, wi),
+        ) {
+            (None, None) => None,
+            (first, second) => Some(
+                (1.0 - weight) * first.unwrap_or_else(Vector::empty)
+                    + weight * second.unwrap_or_else(Vector::empty),
+            ),
+        }
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        let weight = self.weight_at(hit_record);
+        (1.0 - weight) * self.first.pdf(hit_record, wo, wi)
+            + weight * self.second.pdf(hit_record, wo, wi)
+    }
+
+    /// The surface is only there where both materials are.
+    fn is_opaque(&self, hit_record: &HitRecord) -> bool {
+        self.first.is_opaque(hit_record) && self.second.is_opaque(hit_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cutout::{AlphaMode, Cutout};
+    use crate::dielectric::Dielectric;
+    use crate::diffuse_light::DiffuseLight;
+    use crate::lambertian::Lambertian;
+    use crate::textures::checker::Checker;
+
+    fn record(u: f64, v: f64) -> HitRecord {
+        let mut hit_record = HitRecord::empty();
+        hit_record.normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.geometric_normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.u = u;
+        hit_record.v = v;
+        hit_record
+    }
+
+    fn mean_red(material: &dyn Material, hit_record: &HitRecord) -> f64 {
+        let ray = Ray::new(&Vector::new(0.0, 0.0, 1.0), &Vector::new(0.0, 0.0, -1.0));
+        let samples = 10_000;
+        (0..samples)
+            .map(|_| material.scatter(&ray, hit_record).1.x())
+            .sum::<f64>()
+            / samples as f64
+    }
+
+    fn red_and_black() -> (Rc<dyn Material>, Rc<dyn Material>) {
+        (
+            Rc::new(Lambertian::new(&Vector::new(1.0, 0.0, 0.0))),
+            Rc::new(Lambertian::new(&Vector::new(0.0, 0.0, 0.0))),
+        )
+    }
+
+    #[test]
+    fn picks_materials_in_proportion_to_weight() {
+        let (red, black) = red_and_black();
+        let mix = MixMaterial::new(red, black, 0.25);
+        let mean = mean_red(&mix, &record(0.0, 0.0));
+        assert!((mean - 0.75).abs() < 0.02, "{}", mean);
+    }
+
+    #[test]
+    fn textured_weight_varies_over_the_surface() {
+        let (red, black) = red_and_black();
+        let weight = Checker::new(
+            Rc::new(SolidColor::uniform(0.0)),
+            Rc::new(SolidColor::uniform(1.0)),
+        );
+        let mix = MixMaterial::textured(red, black, Rc::new(weight));
+        assert_eq!(1.0, mean_red(&mix, &record(0.5, 0.5)));
+        assert_eq!(0.0, mean_red(&mix, &record(1.5, 0.5)));
+    }
+
+    #[test]
+    fn evaluates_as_the_weighted_mean_of_its_materials() {
+        let (red, black) = red_and_black();
+        let mix = MixMaterial::new(red.clone(), black, 0.25);
+        let hit_record = record(0.0, 0.0);
+        let (wo, wi) = (Vector::new(0.0, 0.0, 1.0), Vector::new(0.6, 0.0, 0.8));
+        let expected = 0.75 * red.eval(&hit_record, &wo, &wi).unwrap().x();
+        let actual = mix.eval(&hit_record, &wo, &wi).unwrap();
+        assert!((expected - actual.x()).abs() < 1e-12);
+        assert_eq!(0.0, actual.y());
+        // Both materials scatter with the same density, so the mix does too.
+        assert!((red.pdf(&hit_record, &wo, &wi) - mix.pdf(&hit_record, &wo, &wi)).abs() < 1e-12);
+
+        let glass: Rc<dyn Material> = Rc::new(Dielectric::new(1.5));
+        let mix = MixMaterial::new(red, glass, 0.25);
+        assert!((expected - mix.eval(&hit_record, &wo, &wi).unwrap().x()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn emits_the_weighted_light_of_its_materials() {
+        let (red, _) = red_and_black();
+        let light = Rc::new(DiffuseLight::new(&Vector::new(4.0, 4.0, 4.0)));
+        let mix = MixMaterial::new(red, light, 0.25);
+        let mut hit_record = record(0.0, 0.0);
+        hit_record.front_face = true;
+        assert_eq!(1.0, mix.emitted(&hit_record).x());
+    }
+
+    #[test]
+    fn holes_in_either_material_cut_through_the_mix() {
+        let (red, black) = red_and_black();
+        let holes = Checker::new(
+            Rc::new(SolidColor::uniform(1.0)),
+            Rc::new(SolidColor::uniform(0.0)),
+        );
+        let cut: Rc<dyn Material> = Rc::new(Cutout::new(
+            black,
+            Rc::new(holes),
+            AlphaMode::Threshold(0.5),
+        ));
+        let mix = MixMaterial::new(red, cut, 0.1);
+        assert!(mix.is_opaque(&record(0.5, 0.5)));
+        assert!(!mix.is_opaque(&record(1.5, 0.5)));
+    }
+}
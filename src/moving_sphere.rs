@@ -0,0 +1,85 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// A sphere whose center moves in a straight line from `start` at time zero to `end` at time one,
+/// staying put outside of that interval.
+pub struct MovingSphere {
+    start: Vector,
+    end: Vector,
+    radius: f64,
+    material: Rc<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        start: &Vector,
+        end: &Vector,
+        radius: f64,
+        material: Rc<dyn Material>,
+    ) -> MovingSphere {
+        MovingSphere {
+            start: start.clone(),
+            end: end.clone(),
+            radius,
+            material,
+        }
+    }
+
+    /// The sphere as it is at `time`.
+    fn at(&self, time: f64) -> Sphere {
+        let time = time.clamp(0.0, 1.0);
+        let center = &self.start + time * (&self.end - &self.start);
+        Sphere::new(&center, self.radius, self.material.clone())
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        self.at(ray.time()).hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = self.at(0.0).bounding_box()?;
+        let end = self.at(1.0).bounding_box()?;
+        Some(start.surrounding(&end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    #[test]
+    fn hits_the_sphere_where_it_is_at_the_ray_time() {
+        let sphere = MovingSphere::new(
+            &Vector::new(0.0, 0.0, -5.0),
+            &Vector::new(0.0, 2.0, -5.0),
+            1.0,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        );
+        let ray = Ray::new(&Vector::new(0.0, 2.0, 0.0), &Vector::new(0.0, 0.0, -1.0));
+        let hit_at = |time: f64| sphere.hit(&ray.clone().with_time(time), 0.001, f64::INFINITY);
+        assert!(!hit_at(0.0).0);
+        let (hit, hit_record) = hit_at(1.0);
+        assert!(hit);
+        assert!(approx_eq!(f64, 4.0, hit_record.t));
+        // Three quarters of the way through, the center is half a unit below the ray.
+        let (hit, hit_record) = hit_at(0.75);
+        assert!(hit);
+        assert!(approx_eq!(f64, 5.0 - 0.75_f64.sqrt(), hit_record.t));
+
+        let bounds = sphere.bounding_box().unwrap();
+        assert_eq!(-1.0, bounds.minimum().y());
+        assert_eq!(3.0, bounds.maximum().y());
+    }
+}
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Perturbs the shading normal of another material with a tangent-space normal map, where red,
+/// green and blue map to the tangent, bitangent and normal directions.
+pub struct NormalMap {
+    material: Rc<dyn Material>,
+    normal_map: Rc<dyn Texture>,
+    strength: f64,
+}
+
+impl NormalMap {
+    pub fn new(
+        material: Rc<dyn Material>,
+        normal_map: Rc<dyn Texture>,
+        strength: f64,
+    ) -> NormalMap {
+        NormalMap {
+            material,
+            normal_map,
+            strength,
+        }
+    }
+
+    fn perturb(&self, hit_record: &HitRecord) -> HitRecord {
+        let encoded = self
+            .normal_map
+            .value(hit_record.u, hit_record.v, &hit_record.p);
+        let perturbed = hit_record.shading_frame().local(
+            self.strength * (2.0 * encoded.x() - 1.0),
+            self.strength * (2.0 * encoded.y() - 1.0),
+            2.0 * encoded.z() - 1.0,
+        );
+        let mut perturbed_record = hit_record.clone();
+        if perturbed.length_squared() > 0.0 {
+            perturbed_record.normal = perturbed.unit_vector();
+        }
+        perturbed_record
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        self.material.scatter(ray_in, &self.perturb(hit_record))
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        self.material.eval(&self.perturb(hit_record), wo, wi)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        self.material.pdf(&self.perturb(hit_record), wo, wi)
+    }
+
+    fn is_opaque(&self, hit_record: &HitRecord) -> bool {
+        self.material.is_opaque(hit_record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::textures::solid_color::SolidColor;
+
+    fn make_record() -> HitRecord {
+        let mut record = HitRecord::empty();
+        record.normal = Vector::new(0., 1., 0.);
+        record.geometric_normal = Vector::new(0., 1., 0.);
+        record.dpdu = Vector::new(1., 0., 0.);
+        record.dpdv = Vector::new(0., 0., -1.);
+        record
+    }
+
+    fn make_map(color: Vector) -> NormalMap {
+        NormalMap::new(
+            Rc::new(Lambertian::new(&Vector::new(1., 1., 1.))),
+            Rc::new(SolidColor::new(&color)),
+            1.0,
+        )
+    }
+
+    #[test]
+    fn flat_normal_map_leaves_normal_unchanged() {
+        let perturbed = make_map(Vector::new(0.5, 0.5, 1.0)).perturb(&make_record());
+        assert!(approx_eq!(f64, 1., perturbed.normal.y()));
+        assert!(approx_eq!(f64, 1., perturbed.geometric_normal.y()));
+    }
+
+    #[test]
+    fn normal_map_tilts_toward_tangent() {
+        let perturbed = make_map(Vector::new(1.0, 0.5, 1.0)).perturb(&make_record());
+        let expected = Vector::new(1., 1., 0.).unit_vector();
+        assert!(approx_eq!(f64, expected.x(), perturbed.normal.x()));
+        assert!(approx_eq!(f64, expected.y(), perturbed.normal.y()));
+        assert!(approx_eq!(f64, 1., perturbed.geometric_normal.y()));
+    }
+
+    #[test]
+    fn evaluates_the_material_with_the_perturbed_normal() {
+        let map = make_map(Vector::new(1.0, 0.5, 1.0));
+        let inner = Lambertian::new(&Vector::new(1., 1., 1.));
+        let (record, perturbed) = (make_record(), map.perturb(&make_record()));
+        let wo = Vector::new(0., 1., 0.);
+        let wi = Vector::new(1., 1., 0.).unit_vector();
+        let expected = inner.eval(&perturbed, &wo, &wi).unwrap();
+        assert!(approx_eq!(
+            f64,
+            expected.x(),
+            map.eval(&record, &wo, &wi).unwrap().x()
+        ));
+        assert!(approx_eq!(f64, 1. / std::f64::consts::PI, expected.x()));
+        assert!(approx_eq!(
+            f64,
+            inner.pdf(&perturbed, &wo, &wi),
+            map.pdf(&record, &wo, &wi)
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::vector::Vector;
+
+/// An orthonormal basis, used to express directions relative to a surface normal or a ray.
+pub struct Onb {
+    u: Vector,
+    v: Vector,
+    w: Vector,
+}
+
+impl Onb {
+    pub fn from_w(n: &Vector) -> Onb {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vector::new(0.0, 1.0, 0.0)
+        } else {
+            Vector::new(1.0, 0.0, 0.0)
+        };
+        let v = Vector::cross(&w, &a).unit_vector();
+        let u = Vector::cross(&w, &v);
+        Onb { u, v, w }
+    }
+
+    /// Creates a basis from three orthonormal vectors.
+    pub fn from_vectors(u: Vector, v: Vector, w: Vector) -> Onb {
+        Onb { u, v, w }
+    }
+
+    /// Expresses a world-space vector in this basis.
+    pub fn to_local(&self, a: &Vector) -> Vector {
+        Vector::new(
+            Vector::dot(a, &self.u),
+            Vector::dot(a, &self.v),
+            Vector::dot(a, &self.w),
+        )
+    }
+
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vector {
+        (a * &self.u) + (b * &self.v) + (c * &self.w)
+    }
+}
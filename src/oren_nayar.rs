@@ -0,0 +1,191 @@
+use rand::Rng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// A rough diffuse surface made of V-shaped Lambertian facets, following the qualitative
+/// Oren-Nayar model. `sigma` is the standard deviation of the facet angles, in degrees; at zero
+/// it is exactly Lambertian.
+pub struct OrenNayar {
+    albedo: Rc<dyn Texture>,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    pub fn new(albedo: &Vector, sigma: f64) -> OrenNayar {
+        Self::textured(Rc::new(SolidColor::new(albedo)), sigma)
+    }
+
+    pub fn textured(albedo: Rc<dyn Texture>, sigma: f64) -> OrenNayar {
+        let sigma2 = sigma.to_radians().powi(2);
+        OrenNayar {
+            albedo,
+            a: 1.0 - sigma2 / (2.0 * (sigma2 + 0.33)),
+            b: 0.45 * sigma2 / (sigma2 + 0.09),
+        }
+    }
+
+    /// The reflectance relative to a Lambertian surface, for directions in the local shading
+    /// frame.
+    fn factor(&self, wo: &Vector, wi: &Vector) -> f64 {
+        let sin_o = (1.0 - wo.z() * wo.z()).max(0.0).sqrt();
+        let sin_i = (1.0 - wi.z() * wi.z()).max(0.0).sqrt();
+        // The cosine of the azimuthal angle between the two directions.
+        let cos_phi = if sin_o > 1e-4 && sin_i > 1e-4 {
+            ((wo.x() * wi.x() + wo.y() * wi.y()) / (sin_o * sin_i)).max(0.0)
+        } else {
+            0.0
+        };
+        // sin(alpha) tan(beta), where alpha is the larger polar angle and beta the smaller.
+        let (sin_alpha, tan_beta) = if wi.z().abs() > wo.z().abs() {
+            (sin_o, sin_i / wi.z().abs())
+        } else {
+            (sin_i, sin_o / wo.z().abs())
+        };
+        self.a + self.b * cos_phi * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let frame = hit_record.shading_frame();
+        let wo = frame.to_local(&-ray_in.direction().unit_vector());
+        let mut rng = rand::thread_rng();
+        let (u1, u2): (f64, f64) = (rng.gen(), rng.gen());
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let wi = Vector::new(r * phi.cos(), r * phi.sin(), (1.0 - u1).max(0.0).sqrt());
+
+        let direction = frame.local(wi.x(), wi.y(), wi.z());
+        let scattered = ray_in.spawn(&hit_record.p, &direction);
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return (false, Vector::empty(), scattered);
+        }
+        // The cosine term cancels against the pdf, leaving the albedo scaled by the roughness.
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        let attenuation = albedo * self.factor(&wo, &wi);
+        // A perturbed shading normal can send light through the real surface; absorb it instead.
+        let scatter = Vector::dot(&direction, &hit_record.geometric_normal) > 0.0;
+        (scatter, attenuation, scattered)
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let frame = hit_record.shading_frame();
+        let (local_wo, local_wi) = (frame.to_local(wo), frame.to_local(wi));
+        if local_wo.z() <= 0.0
+            || local_wi.z() <= 0.0
+            || Vector::dot(wi, &hit_record.geometric_normal) <= 0.0
+        {
+            return Some(Vector::empty());
+        }
+        let albedo = self.albedo.value(hit_record.u, hit_record.v, &hit_record.p);
+        Some(albedo * (self.factor(&local_wo, &local_wi) * local_wi.z() / PI))
+    }
+
+    /// `scatter` picks directions from a cosine-weighted hemisphere.
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        let frame = hit_record.shading_frame();
+        let (local_wo, local_wi) = (frame.to_local(wo), frame.to_local(wi));
+        if local_wo.z() <= 0.0 || local_wi.z() <= 0.0 {
+            return 0.0;
+        }
+        local_wi.z() / PI
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn uniform_hemisphere(rng: &mut StdRng) -> Vector {
+        let z: f64 = rng.gen();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let r = (1.0 - z * z).sqrt();
+        Vector::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    // A hit whose shading frame is the world frame, so that directions need no conversion.
+    fn record() -> HitRecord {
+        let mut hit_record = HitRecord::empty();
+        hit_record.normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.geometric_normal = Vector::new(0.0, 0.0, 1.0);
+        hit_record.dpdu = Vector::new(1.0, 0.0, 0.0);
+        hit_record
+    }
+
+    #[test]
+    fn reduces_to_lambertian_without_roughness() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let albedo = Vector::new(0.8, 0.5, 0.2);
+        let material = OrenNayar::new(&albedo, 0.0);
+        let hit_record = record();
+        for _ in 0..100 {
+            let (wo, wi) = (uniform_hemisphere(&mut rng), uniform_hemisphere(&mut rng));
+            let f = material.eval(&hit_record, &wo, &wi).unwrap();
+            let cosine = wi.z();
+            assert!(approx_eq!(
+                f64,
+                albedo.x() / PI * cosine,
+                f.x(),
+                epsilon = 1e-12
+            ));
+            assert!(approx_eq!(
+                f64,
+                albedo.z() / PI * cosine,
+                f.z(),
+                epsilon = 1e-12
+            ));
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let material = OrenNayar::new(&Vector::new(1.0, 1.0, 1.0), 30.0);
+        let wo = Vector::new(0.6, 0.0, 0.8);
+        let samples = 100_000;
+        let integral = (0..samples)
+            .map(|_| material.pdf(&record(), &wo, &uniform_hemisphere(&mut rng)))
+            .sum::<f64>()
+            * 2.0
+            * PI
+            / samples as f64;
+        assert!((integral - 1.0).abs() < 0.01, "{}", integral);
+    }
+
+    // Rough surfaces lose light between their facets, and scatter more of it back towards
+    // grazing viewers than a Lambertian surface would.
+    #[test]
+    fn roughness_darkens_and_flattens_the_surface() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let material = OrenNayar::new(&Vector::new(1.0, 1.0, 1.0), 40.0);
+        let hit_record = record();
+        let albedo = |wo: &Vector, rng: &mut StdRng| {
+            let samples = 100_000;
+            (0..samples)
+                .map(|_| {
+                    let wi = uniform_hemisphere(rng);
+                    material.eval(&hit_record, wo, &wi).unwrap().x()
+                })
+                .sum::<f64>()
+                * 2.0
+                * PI
+                / samples as f64
+        };
+        let overhead = albedo(&Vector::new(0.0, 0.0, 1.0), &mut rng);
+        assert!(overhead < 0.95 && overhead > 0.6, "{}", overhead);
+        let grazing = albedo(&Vector::new(0.995, 0.0, 0.0998), &mut rng);
+        assert!(grazing > overhead, "{} {}", grazing, overhead);
+    }
+}
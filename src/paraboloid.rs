@@ -0,0 +1,136 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::math;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A bowl whose lowest point is at `vertex`, opening up the y axis until it is `radius` wide at
+/// `height` above the vertex. `u` runs around the bowl and `v` runs from the vertex to the rim.
+pub struct Paraboloid {
+    vertex: Vector,
+    radius: f64,
+    height: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Paraboloid {
+    pub fn new(
+        vertex: &Vector,
+        radius: f64,
+        height: f64,
+        material: Rc<dyn Material>,
+    ) -> Paraboloid {
+        Paraboloid {
+            vertex: vertex.clone(),
+            radius,
+            height,
+            material,
+        }
+    }
+
+    fn surface_hit(&self, ray: &Ray, t: f64) -> Option<HitRecord> {
+        let p = ray.at(t);
+        let local = &p - &self.vertex;
+        if local.y() < 0.0 || local.y() > self.height {
+            return None;
+        }
+        let (x, z) = (local.x(), local.z());
+        let phi = (-z).atan2(x).rem_euclid(2.0 * PI);
+        let v = local.y() / self.height;
+        // The gradient of k (x² + z²) - y, which points out of the bowl.
+        let k = self.height / (self.radius * self.radius);
+        let outward_normal = Vector::new(2.0 * k * x, -1.0, 2.0 * k * z).unit_vector();
+        let dpdv = if v > 1e-12 {
+            Vector::new(x / (2.0 * v), self.height, z / (2.0 * v))
+        } else {
+            // At the vertex, rings shrink to a point and `v` only moves along the axis.
+            Vector::new(0.0, self.height, 0.0)
+        };
+        let mut hit_record = HitRecord {
+            t,
+            u: phi / (2.0 * PI),
+            v,
+            p,
+            dpdu: 2.0 * PI * Vector::new(z, 0.0, -x),
+            dpdv,
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        Some(hit_record)
+    }
+}
+
+impl Hittable for Paraboloid {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // k (x² + z²) = y, with k chosen so that the rim is `radius` wide.
+        let origin = ray.origin() - &self.vertex;
+        let direction = ray.direction();
+        let k = self.height / (self.radius * self.radius);
+        let a = k * (direction.x() * direction.x() + direction.z() * direction.z());
+        let b = 2.0 * k * (origin.x() * direction.x() + origin.z() * direction.z()) - direction.y();
+        let c = k * (origin.x() * origin.x() + origin.z() * origin.z()) - origin.y();
+        for t in math::solve_quadratic(a, b, c) {
+            if t <= t_min || t >= t_max {
+                continue;
+            }
+            if let Some(hit_record) = self.surface_hit(ray, t) {
+                if hit_record.is_opaque() {
+                    return (true, hit_record);
+                }
+            }
+        }
+        (false, HitRecord::empty())
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vector::new(self.radius, 0.0, self.radius);
+        Some(Aabb::new(
+            &(&self.vertex - &extent),
+            &(&self.vertex + &extent + Vector::new(0.0, self.height, 0.0)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    #[test]
+    fn rays_land_in_the_bowl() {
+        let bowl = Paraboloid::new(
+            &Vector::empty(),
+            2.0,
+            1.0,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        );
+        // Straight down into the bowl, landing where y = x² / 4.
+        let down = Ray::new(&Vector::new(1.0, 5.0, 0.0), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = bowl.hit(&down, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 4.75, hit_record.t, epsilon = 1e-12));
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, 0.25, hit_record.v, epsilon = 1e-12));
+        // The normal faces up into the bowl, against the ray, tilted towards the axis.
+        assert!(hit_record.normal.y() > 0.0 && hit_record.normal.x() < 0.0);
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(Vector::dot(&cross, &hit_record.normal) < 0.0);
+
+        // From below, the outside of the bowl is hit.
+        let up = Ray::new(&Vector::new(1.0, -1.0, 0.0), &Vector::new(0.0, 1.0, 0.0));
+        let (hit, hit_record) = bowl.hit(&up, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(hit_record.front_face);
+        // Beyond the rim, there is nothing.
+        let beside = Ray::new(&Vector::new(2.5, 5.0, 0.0), &Vector::new(0.0, -1.0, 0.0));
+        assert!(!bowl.hit(&beside, 0.001, f64::INFINITY).0);
+    }
+}
@@ -0,0 +1,95 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// An infinite plane through `point`, facing `normal`. Texture coordinates are distances along
+/// two directions in the plane, so that textures tile across it.
+pub struct Plane {
+    point: Vector,
+    normal: Vector,
+    frame: Onb,
+    material: Rc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: &Vector, normal: &Vector, material: Rc<dyn Material>) -> Plane {
+        Plane {
+            point: point.clone(),
+            normal: normal.unit_vector(),
+            frame: Onb::from_w(normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let denominator = Vector::dot(&self.normal, ray.direction());
+        if denominator.abs() < 1e-12 {
+            return (false, HitRecord::empty());
+        }
+        let t = Vector::dot(&self.normal, &(&self.point - ray.origin())) / denominator;
+        if t <= t_min || t >= t_max {
+            return (false, HitRecord::empty());
+        }
+
+        let p = ray.at(t);
+        let local = self.frame.to_local(&(&p - &self.point));
+        let mut hit_record = HitRecord {
+            t,
+            u: local.x(),
+            v: local.y(),
+            p,
+            dpdu: self.frame.local(1.0, 0.0, 0.0),
+            dpdv: self.frame.local(0.0, 1.0, 0.0),
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &self.normal);
+        if !hit_record.is_opaque() {
+            return (false, HitRecord::empty());
+        }
+        (true, hit_record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    #[test]
+    fn hits_anywhere_on_the_plane_from_either_side() {
+        let plane = Plane::new(
+            &Vector::new(0.0, -1.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        );
+        let far_away = Ray::new(&Vector::new(1e4, 1.0, -3e4), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = plane.hit(&far_away, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.0, hit_record.t));
+        assert!(hit_record.front_face);
+
+        let below = Ray::new(&Vector::new(0.0, -3.0, 0.0), &Vector::new(0.0, 1.0, 1.0));
+        let (hit, hit_record) = plane.hit(&below, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, -1.0, hit_record.normal.y()));
+        // Texture coordinates measure distance in the plane.
+        let uv = (hit_record.u * hit_record.u + hit_record.v * hit_record.v).sqrt();
+        assert!(approx_eq!(f64, 2.0, uv, epsilon = 1e-12));
+    }
+}
@@ -0,0 +1,353 @@
+use std::io::Error;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::io::read_ply;
+use crate::lambertian::Lambertian;
+use crate::onb::Onb;
+use crate::ray::Ray;
+use crate::sphere::Sphere;
+use crate::vector::Vector;
+
+/// How many points a leaf of the hierarchy holds at most.
+const LEAF_SIZE: usize = 4;
+
+/// A point of a cloud, stored in single precision with an 8 bit color to keep clouds of millions
+/// of points small.
+#[derive(Clone)]
+pub struct Point {
+    position: [f32; 3],
+    radius: f32,
+    color: [u8; 3],
+}
+
+impl Point {
+    /// A point with a color from 0 to 1 in each channel.
+    pub fn new(position: &Vector, radius: f64, color: &Vector) -> Point {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Point {
+            position: [
+                position.x() as f32,
+                position.y() as f32,
+                position.z() as f32,
+            ],
+            radius: radius as f32,
+            color: [channel(color.x()), channel(color.y()), channel(color.z())],
+        }
+    }
+
+    pub fn position(&self) -> Vector {
+        Vector::new(
+            self.position[0] as f64,
+            self.position[1] as f64,
+            self.position[2] as f64,
+        )
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius as f64
+    }
+
+    pub fn color(&self) -> Vector {
+        Vector::new(
+            self.color[0] as f64 / 255.0,
+            self.color[1] as f64 / 255.0,
+            self.color[2] as f64 / 255.0,
+        )
+    }
+
+    fn bounds(&self) -> Aabb {
+        let radius = self.radius().abs();
+        let extent = Vector::new(radius, radius, radius);
+        let position = self.position();
+        Aabb::new(&(&position - &extent), &(&position + &extent))
+    }
+}
+
+/// What each point of a cloud looks like.
+#[derive(Clone, Copy)]
+pub enum PointShape {
+    Sphere,
+    /// A flat disk that always faces the ray, which is cheaper than a sphere and looks the same
+    /// from the camera, but is lit as if flat.
+    Disk,
+}
+
+/// A node of the bounding volume hierarchy, stored flat with the first child of an interior node
+/// right after it.
+struct Node {
+    bounds: Aabb,
+    /// For a leaf, where its points start; otherwise, where the second child is.
+    offset: usize,
+    /// How many points a leaf holds, zero for interior nodes.
+    count: usize,
+    /// The axis interior nodes split their points along.
+    axis: usize,
+}
+
+/// Many small particles, such as the output of a simulation, each with its own position, radius
+/// and color. Points are rendered as spheres or as disks facing the ray, and found through a
+/// bounding volume hierarchy of their own, so that a cloud counts as a single object in the
+/// scenery however many points it has. Each point is a Lambertian surface of its own color.
+pub struct PointCloud {
+    points: Vec<Point>,
+    nodes: Vec<Node>,
+    shape: PointShape,
+}
+
+impl PointCloud {
+    pub fn new(points: Vec<Point>, shape: PointShape) -> PointCloud {
+        let mut cloud = PointCloud {
+            points,
+            nodes: Vec::new(),
+            shape,
+        };
+        if !cloud.points.is_empty() {
+            let count = cloud.points.len();
+            cloud.build(0, count);
+        }
+        cloud
+    }
+
+    /// Loads the points of an ASCII or binary PLY file, giving points without a radius of their
+    /// own `default_radius`.
+    pub fn load(path: &Path, default_radius: f64, shape: PointShape) -> Result<PointCloud, Error> {
+        Ok(Self::new(read_ply(path, default_radius)?, shape))
+    }
+
+    /// Builds the node for the points from `start` to `end`, splitting them in half along the
+    /// axis their centers spread furthest along until few enough are left for a leaf.
+    fn build(&mut self, start: usize, end: usize) {
+        let points = &mut self.points[start..end];
+        let bounds = points
+            .iter()
+            .map(Point::bounds)
+            .reduce(|a, b| a.surrounding(&b))
+            .unwrap();
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            offset: start,
+            count: end - start,
+            axis: 0,
+        });
+        if end - start <= LEAF_SIZE {
+            return;
+        }
+
+        let centers = Aabb::from_points(&points.iter().map(Point::position).collect::<Vec<_>>());
+        let extent = centers.maximum() - centers.minimum();
+        let axis = if extent.x() >= extent.y() && extent.x() >= extent.z() {
+            0
+        } else if extent.y() >= extent.z() {
+            1
+        } else {
+            2
+        };
+        let middle = points.len() / 2;
+        points.select_nth_unstable_by(middle, |a, b| a.position[axis].total_cmp(&b.position[axis]));
+
+        self.build(start, start + middle);
+        let second = self.nodes.len();
+        self.build(start + middle, end);
+        let node = &mut self.nodes[index];
+        node.offset = second;
+        node.count = 0;
+        node.axis = axis;
+    }
+
+    /// The distance along the ray to where it meets point `index`, if within `(t_min, t_max)`.
+    fn intersect(&self, index: usize, ray: &Ray, t_min: f64, t_max: f64) -> Option<f64> {
+        let point = &self.points[index];
+        let center_offset = ray.origin() - point.position();
+        let radius = point.radius();
+        let a = ray.direction().length_squared();
+        match self.shape {
+            PointShape::Sphere => {
+                let half_b = Vector::dot(&center_offset, ray.direction());
+                let c = center_offset.length_squared() - radius * radius;
+                let discriminant = half_b * half_b - a * c;
+                if discriminant <= 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                [(-half_b - root) / a, (-half_b + root) / a]
+                    .iter()
+                    .copied()
+                    .find(|&t| t > t_min && t < t_max)
+            }
+            PointShape::Disk => {
+                // The disk lies square to the ray, where the ray passes closest to its center.
+                let t = -Vector::dot(&center_offset, ray.direction()) / a;
+                let miss = &center_offset + t * ray.direction();
+                if t <= t_min || t >= t_max || miss.length_squared() > radius * radius {
+                    return None;
+                }
+                Some(t)
+            }
+        }
+    }
+
+    fn hit_record(&self, index: usize, ray: &Ray, t: f64) -> HitRecord {
+        let point = &self.points[index];
+        let center = point.position();
+        let radius = point.radius();
+        let p = ray.at(t);
+        let offset = &p - &center;
+        let (outward_normal, u, v, dpdu, dpdv) = match self.shape {
+            PointShape::Sphere => {
+                let outward_normal = &offset / radius;
+                let (u, v) = Sphere::get_uv(&outward_normal);
+                let (dpdu, dpdv) = Sphere::get_partial_derivatives(&offset);
+                (outward_normal, u, v, dpdu, dpdv)
+            }
+            PointShape::Disk => {
+                let facing = -ray.direction().unit_vector();
+                let frame = Onb::from_w(&facing);
+                let local = frame.to_local(&offset);
+                let (dpdu, dpdv) = (
+                    frame.local(2.0 * radius, 0.0, 0.0),
+                    frame.local(0.0, 2.0 * radius, 0.0),
+                );
+                let u = 0.5 + local.x() / (2.0 * radius);
+                let v = 0.5 + local.y() / (2.0 * radius);
+                // Keep the tangents on the side of the normal that faces the ray.
+                let dpdv = if Vector::dot(&Vector::cross(&dpdu, &dpdv), &facing) < 0.0 {
+                    -dpdv
+                } else {
+                    dpdv
+                };
+                (facing, u, v, dpdu, dpdv)
+            }
+        };
+        let mut hit_record = HitRecord {
+            t,
+            u,
+            v,
+            p,
+            dpdu,
+            dpdv,
+            material: Some(Rc::new(Lambertian::new(&point.color()))),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        hit_record
+    }
+}
+
+impl Hittable for PointCloud {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let mut closest: Option<HitRecord> = None;
+        let mut t_max = t_max;
+        let direction = ray.direction();
+        let negative = [
+            direction.x() < 0.0,
+            direction.y() < 0.0,
+            direction.z() < 0.0,
+        ];
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = match self.nodes.get(index) {
+                Some(node) => node,
+                None => continue,
+            };
+            if !node.bounds.hit(ray, t_min, t_max).0 {
+                continue;
+            }
+            if node.count > 0 {
+                for point in node.offset..node.offset + node.count {
+                    if let Some(t) = self.intersect(point, ray, t_min, t_max) {
+                        let hit_record = self.hit_record(point, ray, t);
+                        if hit_record.is_opaque() {
+                            t_max = t;
+                            closest = Some(hit_record);
+                        }
+                    }
+                }
+            } else if negative[node.axis] {
+                // Visit the child nearer the ray first, so that its hits cut the other short.
+                stack.push(index + 1);
+                stack.push(node.offset);
+            } else {
+                stack.push(node.offset);
+                stack.push(index + 1);
+            }
+        }
+        match closest {
+            Some(hit_record) => (true, hit_record),
+            None => (false, HitRecord::empty()),
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| node.bounds.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    fn grid(shape: PointShape) -> PointCloud {
+        let mut points = Vec::new();
+        for i in 0..20 {
+            for j in 0..20 {
+                let position = Vector::new(i as f64, j as f64, 0.0);
+                let color = Vector::new(i as f64 / 19.0, j as f64 / 19.0, 0.5);
+                points.push(Point::new(&position, 0.25, &color));
+            }
+        }
+        PointCloud::new(points, shape)
+    }
+
+    #[test]
+    fn finds_each_point_through_the_hierarchy() {
+        let cloud = grid(PointShape::Sphere);
+        for &(x, y) in &[(0.0, 0.0), (7.0, 12.0), (19.0, 3.0)] {
+            let ray = Ray::new(&Vector::new(x, y, 5.0), &Vector::new(0.0, 0.0, -1.0));
+            let (hit, hit_record) = cloud.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit);
+            assert!(approx_eq!(f64, 4.75, hit_record.t, epsilon = 1e-6));
+            assert!(approx_eq!(f64, 1.0, hit_record.normal.z(), epsilon = 1e-6));
+        }
+        let between = Ray::new(&Vector::new(3.5, 3.5, 5.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!cloud.hit(&between, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn grazing_rays_hit_the_nearest_point() {
+        // Along the bottom row, the ray meets the first sphere in its way.
+        let cloud = grid(PointShape::Sphere);
+        for &(x, direction) in &[(-5.0, 1.0), (24.0, -1.0)] {
+            let ray = Ray::new(&Vector::new(x, 0.0, 0.0), &Vector::new(direction, 0.0, 0.0));
+            let (hit, hit_record) = cloud.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit);
+            assert!(approx_eq!(f64, 4.75, hit_record.t, epsilon = 1e-6));
+        }
+    }
+
+    #[test]
+    fn disks_face_the_ray() {
+        let cloud = grid(PointShape::Disk);
+        let ray = Ray::new(&Vector::new(8.0, 5.0, 3.0), &Vector::new(-1.0, 0.0, -3.0));
+        let (hit, hit_record) = cloud.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        let facing = -ray.direction().unit_vector();
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            Vector::dot(&hit_record.normal, &facing),
+            epsilon = 1e-9
+        ));
+        assert!(hit_record.front_face);
+        let p = &hit_record.p;
+        assert!((p - Vector::new(7.0, 5.0, 0.0)).length() <= 0.25 + 1e-9);
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv);
+        assert!(Vector::dot(&cross, &facing) > 0.0);
+    }
+}
@@ -0,0 +1,461 @@
+use rand::Rng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::fresnel;
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::microfacet::{self, Ggx};
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// The width of the clearcoat's microfacet distribution; the coat is always glossy.
+const CLEARCOAT_ALPHA: f64 = 0.05;
+
+/// How much of the base color's hue tints the sheen.
+const SHEEN_TINT: f64 = 0.5;
+
+/// The sliders of the principled material. Every parameter is a texture so that it can vary over
+/// the surface; scalar parameters are read from the mean of the texture's channels and lie in
+/// `[0, 1]`.
+pub struct PrincipledConfig {
+    pub base_color: Rc<dyn Texture>,
+    /// Blends from a dielectric to a metal whose reflectance is the base color.
+    pub metallic: Rc<dyn Texture>,
+    pub roughness: Rc<dyn Texture>,
+    /// The dielectric reflectance at normal incidence, scaled so that 0.5 means 4%, which also
+    /// sets the index of refraction of transmitted light.
+    pub specular: Rc<dyn Texture>,
+    /// Tints dielectric reflections towards the hue of the base color.
+    pub specular_tint: Rc<dyn Texture>,
+    /// A soft retroreflection at grazing angles, for cloth.
+    pub sheen: Rc<dyn Texture>,
+    /// The strength of a glossy, colorless second specular layer.
+    pub clearcoat: Rc<dyn Texture>,
+    /// Blends from an opaque dielectric to a clear one that refracts light tinted by the base
+    /// color.
+    pub transmission: Rc<dyn Texture>,
+    /// Stretches highlights along the surface's bitangent.
+    pub anisotropy: Rc<dyn Texture>,
+}
+
+impl Default for PrincipledConfig {
+    fn default() -> PrincipledConfig {
+        PrincipledConfig {
+            base_color: Rc::new(SolidColor::uniform(0.8)),
+            metallic: Rc::new(SolidColor::uniform(0.0)),
+            roughness: Rc::new(SolidColor::uniform(0.5)),
+            specular: Rc::new(SolidColor::uniform(0.5)),
+            specular_tint: Rc::new(SolidColor::uniform(0.0)),
+            sheen: Rc::new(SolidColor::uniform(0.0)),
+            clearcoat: Rc::new(SolidColor::uniform(0.0)),
+            transmission: Rc::new(SolidColor::uniform(0.0)),
+            anisotropy: Rc::new(SolidColor::uniform(0.0)),
+        }
+    }
+}
+
+/// The Disney principled BSDF, combining a diffuse base with sheen, an anisotropic GGX specular
+/// lobe, rough transmission and a clearcoat behind one set of artist-friendly parameters.
+pub struct Principled {
+    config: PrincipledConfig,
+}
+
+impl Principled {
+    pub fn new(config: PrincipledConfig) -> Principled {
+        Principled { config }
+    }
+
+    fn parameters(&self, hit_record: &HitRecord) -> Parameters {
+        let (u, v, p) = (hit_record.u, hit_record.v, &hit_record.p);
+        let scalar = |texture: &Rc<dyn Texture>| {
+            let value = texture.value(u, v, p);
+            ((value.x() + value.y() + value.z()) / 3.0).clamp(0.0, 1.0)
+        };
+        Parameters {
+            base_color: self.config.base_color.value(u, v, p),
+            metallic: scalar(&self.config.metallic),
+            roughness: scalar(&self.config.roughness),
+            specular: scalar(&self.config.specular),
+            specular_tint: scalar(&self.config.specular_tint),
+            sheen: scalar(&self.config.sheen),
+            clearcoat: scalar(&self.config.clearcoat),
+            transmission: scalar(&self.config.transmission),
+            anisotropy: scalar(&self.config.anisotropy),
+        }
+    }
+}
+
+/// The principled parameters at one point on a surface.
+struct Parameters {
+    base_color: Vector,
+    metallic: f64,
+    roughness: f64,
+    specular: f64,
+    specular_tint: f64,
+    sheen: f64,
+    clearcoat: f64,
+    transmission: f64,
+    anisotropy: f64,
+}
+
+fn luminance(color: &Vector) -> f64 {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
+
+fn lerp(a: &Vector, b: &Vector, t: f64) -> Vector {
+    (1.0 - t) * a + t * b
+}
+
+/// The principled BSDF with its parameters resolved into lobe weights, in the local shading frame.
+struct Bsdf {
+    base_color: Vector,
+    roughness: f64,
+    diffuse_weight: f64,
+    sheen_color: Vector,
+    specular_color: Vector,
+    transmission_weight: f64,
+    clearcoat: f64,
+    /// The ratio of the refractive index below the surface to the index above it.
+    eta: f64,
+    distribution: Ggx,
+    clearcoat_distribution: Ggx,
+}
+
+impl Bsdf {
+    /// Resolves the parameters for light arriving from outside the surface if `front_face`, and
+    /// from inside otherwise. Inside, only the dielectric interface is left.
+    fn new(parameters: &Parameters, front_face: bool) -> Bsdf {
+        let white = Vector::new(1.0, 1.0, 1.0);
+        let base_luminance = luminance(&parameters.base_color);
+        let tint = if base_luminance > 0.0 {
+            &parameters.base_color / base_luminance
+        } else {
+            white.clone()
+        };
+        let outside = if front_face { 1.0 } else { 0.0 };
+        let dielectric = 1.0 - parameters.metallic;
+
+        let r0 = 0.08 * parameters.specular;
+        let refraction_index = ((1.0 + r0.sqrt()) / (1.0 - r0.sqrt())).max(1.0 + 1e-4);
+        let dielectric_color = r0 * lerp(&white, &tint, parameters.specular_tint);
+
+        let aspect = (1.0 - 0.9 * parameters.anisotropy).sqrt();
+        let alpha = parameters.roughness * parameters.roughness;
+        Bsdf {
+            base_color: parameters.base_color.clone(),
+            roughness: parameters.roughness,
+            diffuse_weight: outside * dielectric * (1.0 - parameters.transmission),
+            sheen_color: parameters.sheen * lerp(&white, &tint, SHEEN_TINT),
+            specular_color: lerp(
+                &dielectric_color,
+                &parameters.base_color,
+                parameters.metallic,
+            ),
+            transmission_weight: dielectric * parameters.transmission,
+            clearcoat: outside * 0.25 * parameters.clearcoat,
+            eta: if front_face {
+                refraction_index
+            } else {
+                1.0 / refraction_index
+            },
+            distribution: Ggx::anisotropic(alpha / aspect, alpha * aspect),
+            clearcoat_distribution: Ggx::new(CLEARCOAT_ALPHA),
+        }
+    }
+
+    /// The reflectance of the specular lobe, blending the opaque base's Schlick approximation
+    /// with the exact Fresnel term of the transmissive part.
+    fn specular_fresnel(&self, cos_theta: f64) -> Vector {
+        let opaque = fresnel::schlick(cos_theta, &self.specular_color);
+        let clear = fresnel::dielectric(cos_theta, self.eta);
+        lerp(
+            &opaque,
+            &Vector::new(clear, clear, clear),
+            self.transmission_weight,
+        )
+    }
+
+    /// The half vector for light refracted between `wo` and `wi`, facing up, or `None` if the
+    /// two cannot be joined by refraction.
+    fn refraction_half_vector(&self, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let h = (wo + self.eta * wi).unit_vector();
+        let h = if h.z() < 0.0 { -h } else { h };
+        if Vector::dot(wo, &h) <= 0.0 || Vector::dot(wi, &h) >= 0.0 {
+            return None;
+        }
+        Some(h)
+    }
+
+    /// The probabilities of sampling the diffuse, specular, transmission and clearcoat lobes.
+    fn lobe_probabilities(&self, wo: &Vector) -> [f64; 4] {
+        let clear = fresnel::dielectric(wo.z(), self.eta);
+        let specular = (1.0 - self.transmission_weight)
+            * luminance(&fresnel::schlick(wo.z(), &self.specular_color))
+            + self.transmission_weight * clear;
+        let weights = [
+            self.diffuse_weight * (luminance(&self.base_color) + luminance(&self.sheen_color)),
+            specular,
+            self.transmission_weight * luminance(&self.base_color) * (1.0 - clear),
+            self.clearcoat * fresnel::dielectric(wo.z(), 1.5),
+        ];
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return [0.0; 4];
+        }
+        [
+            weights[0] / total,
+            weights[1] / total,
+            weights[2] / total,
+            weights[3] / total,
+        ]
+    }
+
+    /// The BSDF for light arriving from `wi` and leaving towards `wo`.
+    fn eval(&self, wo: &Vector, wi: &Vector) -> Vector {
+        if wo.z() <= 0.0 || wi.z() == 0.0 {
+            return Vector::empty();
+        }
+        if wi.z() < 0.0 {
+            let h = match self.refraction_half_vector(wo, wi) {
+                Some(h) if self.transmission_weight > 0.0 => h,
+                _ => return Vector::empty(),
+            };
+            let (cos_o, cos_i) = (Vector::dot(wo, &h), Vector::dot(wi, &h));
+            let transmitted = 1.0 - fresnel::dielectric(cos_o, self.eta);
+            let denominator = cos_i + cos_o / self.eta;
+            let magnitude = self.distribution.d(&h) * self.distribution.g2(wo, wi) * cos_i * cos_o
+                / (wi.z() * wo.z() * denominator * denominator);
+            return self.transmission_weight * transmitted * magnitude.abs() * &self.base_color;
+        }
+
+        let h = (wo + wi).unit_vector();
+        let cos_d = Vector::dot(wi, &h);
+        let mut f = Vector::empty();
+        if self.diffuse_weight > 0.0 {
+            // Burley's diffuse with its retroreflection at grazing angles, plus sheen.
+            let fl = (1.0 - wi.z()).powi(5);
+            let fv = (1.0 - wo.z()).powi(5);
+            let rr = 2.0 * self.roughness * cos_d * cos_d;
+            let lambert = (1.0 - 0.5 * fl) * (1.0 - 0.5 * fv);
+            let retro = rr * (fl + fv + fl * fv * (rr - 1.0));
+            let diffuse = (lambert + retro) / PI * &self.base_color;
+            let sheen = (1.0 - cos_d).powi(5) * &self.sheen_color;
+            f = f + self.diffuse_weight * (diffuse + sheen);
+        }
+        let specular =
+            self.distribution.d(&h) * self.distribution.g2(wo, wi) / (4.0 * wo.z() * wi.z());
+        f = f + self.specular_fresnel(Vector::dot(wo, &h)) * specular;
+        if self.clearcoat > 0.0 {
+            let coat = self.clearcoat_distribution.d(&h) * self.clearcoat_distribution.g2(wo, wi)
+                / (4.0 * wo.z() * wi.z());
+            f = f + Vector::new(1.0, 1.0, 1.0)
+                * (self.clearcoat * fresnel::dielectric(Vector::dot(wo, &h), 1.5) * coat);
+        }
+        f
+    }
+
+    /// The density with which `sample` picks `wi`, over all lobes.
+    fn pdf(&self, wo: &Vector, wi: &Vector) -> f64 {
+        if wo.z() <= 0.0 || wi.z() == 0.0 {
+            return 0.0;
+        }
+        let [diffuse, specular, transmission, clearcoat] = self.lobe_probabilities(wo);
+        if wi.z() < 0.0 {
+            return match self.refraction_half_vector(wo, wi) {
+                Some(h) => {
+                    let cos_i = Vector::dot(wi, &h);
+                    let denominator = cos_i + Vector::dot(wo, &h) / self.eta;
+                    transmission * self.distribution.visible_d(wo, &h) * cos_i.abs()
+                        / (denominator * denominator)
+                }
+                None => 0.0,
+            };
+        }
+        let h = (wo + wi).unit_vector();
+        let reflection = |distribution: &Ggx| {
+            distribution.visible_d(wo, &h) / (4.0 * Vector::dot(wo, &h).max(1e-9))
+        };
+        diffuse * wi.z() / PI
+            + specular * reflection(&self.distribution)
+            + clearcoat * reflection(&self.clearcoat_distribution)
+    }
+
+    /// Samples an incoming direction by picking a lobe, then importance sampling it. Returns the
+    /// direction and its weight, the BSDF times the cosine term over the combined pdf.
+    fn sample(&self, wo: &Vector, u: (f64, f64, f64)) -> Option<(Vector, Vector)> {
+        if wo.z() <= 0.0 {
+            return None;
+        }
+        let [diffuse, specular, transmission, _] = self.lobe_probabilities(wo);
+        let wi = if u.0 < diffuse {
+            let r = u.1.sqrt();
+            let phi = 2.0 * PI * u.2;
+            Vector::new(r * phi.cos(), r * phi.sin(), (1.0 - u.1).max(0.0).sqrt())
+        } else if u.0 < diffuse + specular {
+            let h = self.distribution.sample_visible_normal(wo, u.1, u.2);
+            microfacet::reflect(wo, &h)
+        } else if u.0 < diffuse + specular + transmission {
+            let h = self.distribution.sample_visible_normal(wo, u.1, u.2);
+            microfacet::refract(wo, &h, self.eta)?
+        } else {
+            let h = self
+                .clearcoat_distribution
+                .sample_visible_normal(wo, u.1, u.2);
+            microfacet::reflect(wo, &h)
+        };
+        let pdf = self.pdf(wo, &wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+        Some((wi.clone(), self.eval(wo, &wi) * (wi.z().abs() / pdf)))
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let bsdf = Bsdf::new(&self.parameters(hit_record), hit_record.front_face);
+        let frame = hit_record.shading_frame();
+        let wo = frame.to_local(&-ray_in.direction().unit_vector());
+        let mut rng = rand::thread_rng();
+        match bsdf.sample(&wo, (rng.gen(), rng.gen(), rng.gen())) {
+            Some((wi, weight)) => {
+                let direction = frame.local(wi.x(), wi.y(), wi.z());
+                // Reflections must stay on the incoming side of the real surface, and
+                // transmissions must cross it.
+                let side = Vector::dot(&direction, &hit_record.geometric_normal);
+                let scatter = (side > 0.0) == (wi.z() > 0.0);
+                (scatter, weight, ray_in.spawn(&hit_record.p, &direction))
+            }
+            None => (false, Vector::empty(), ray_in.clone()),
+        }
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> Option<Vector> {
+        let bsdf = Bsdf::new(&self.parameters(hit_record), hit_record.front_face);
+        let frame = hit_record.shading_frame();
+        let (wo, local_wi) = (frame.to_local(wo), frame.to_local(wi));
+        // As in `scatter`, the real surface must agree on which side `wi` is on.
+        let side = Vector::dot(wi, &hit_record.geometric_normal);
+        if (side > 0.0) != (local_wi.z() > 0.0) {
+            return Some(Vector::empty());
+        }
+        Some(bsdf.eval(&wo, &local_wi) * local_wi.z().abs())
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wo: &Vector, wi: &Vector) -> f64 {
+        let bsdf = Bsdf::new(&self.parameters(hit_record), hit_record.front_face);
+        let frame = hit_record.shading_frame();
+        bsdf.pdf(&frame.to_local(wo), &frame.to_local(wi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn direction(theta: f64) -> Vector {
+        Vector::new(theta.sin(), 0.0, theta.cos())
+    }
+
+    fn uniform_sphere(rng: &mut StdRng) -> Vector {
+        let z = rng.gen_range(-1.0, 1.0);
+        let phi = rng.gen_range(0.0, 2.0 * PI);
+        let r = (1.0f64 - z * z).sqrt();
+        Vector::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    fn parameters() -> Parameters {
+        Parameters {
+            base_color: Vector::new(0.8, 0.5, 0.3),
+            metallic: 0.3,
+            roughness: 0.5,
+            specular: 0.5,
+            specular_tint: 0.5,
+            sheen: 0.5,
+            clearcoat: 0.7,
+            transmission: 0.5,
+            anisotropy: 0.6,
+        }
+    }
+
+    fn mean_weight(bsdf: &Bsdf, wo: &Vector, rng: &mut StdRng) -> f64 {
+        let samples = 50_000;
+        (0..samples)
+            .map(
+                |_| match bsdf.sample(wo, (rng.gen(), rng.gen(), rng.gen())) {
+                    Some((_, weight)) => weight.x(),
+                    None => 0.0,
+                },
+            )
+            .sum::<f64>()
+            / samples as f64
+    }
+
+    // Importance sampling and direct integration of the BSDF should agree on the albedo.
+    #[test]
+    fn sampling_weights_match_bsdf_integral() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for &front_face in &[true, false] {
+            let bsdf = Bsdf::new(&parameters(), front_face);
+            let wo = Vector::new(0.3, 0.4, 0.866).unit_vector();
+            let sampled = mean_weight(&bsdf, &wo, &mut rng);
+            let samples = 200_000;
+            let integrated = (0..samples)
+                .map(|_| {
+                    let wi = uniform_sphere(&mut rng);
+                    bsdf.eval(&wo, &wi).x() * wi.z().abs()
+                })
+                .sum::<f64>()
+                * 4.0
+                * PI
+                / samples as f64;
+            assert!(
+                (sampled - integrated).abs() < 0.03,
+                "{} {}",
+                sampled,
+                integrated
+            );
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_at_most_one() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let bsdf = Bsdf::new(&parameters(), true);
+        let wo = direction(0.6);
+        let samples = 200_000;
+        let integral = (0..samples)
+            .map(|_| bsdf.pdf(&wo, &uniform_sphere(&mut rng)))
+            .sum::<f64>()
+            * 4.0
+            * PI
+            / samples as f64;
+        // Some sampled directions are lost below the horizon or to total internal reflection.
+        assert!(integral <= 1.02 && integral > 0.9, "{}", integral);
+    }
+
+    #[test]
+    fn smooth_white_metal_reflects_everything() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let bsdf = Bsdf::new(
+            &Parameters {
+                base_color: Vector::new(1.0, 1.0, 1.0),
+                metallic: 1.0,
+                roughness: 0.0,
+                clearcoat: 0.0,
+                ..parameters()
+            },
+            true,
+        );
+        for &theta in &[0.0, 0.7, 1.3] {
+            let albedo = mean_weight(&bsdf, &direction(theta), &mut rng);
+            assert!((albedo - 1.0).abs() < 0.01, "theta {}: {}", theta, albedo);
+        }
+    }
+}
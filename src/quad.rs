@@ -0,0 +1,165 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A parallelogram with one corner at `q` and sides along `u` and `v`. Texture coordinates run
+/// from zero to one along each side, and the front face is on the side that `u × v` points to.
+pub struct Quad {
+    q: Vector,
+    u: Vector,
+    v: Vector,
+    material: Rc<dyn Material>,
+    normal: Vector,
+    /// The plane's offset along `normal`, so that points on it satisfy `normal · p = d`.
+    d: f64,
+    /// `n / (n · n)` for the unnormalized normal `n = u × v`, which turns the offset of a point
+    /// in the plane into its coordinates along the sides.
+    w: Vector,
+    area: f64,
+}
+
+impl Quad {
+    pub fn new(q: &Vector, u: &Vector, v: &Vector, material: Rc<dyn Material>) -> Quad {
+        let n = Vector::cross(u, v);
+        let normal = n.unit_vector();
+        Quad {
+            q: q.clone(),
+            u: u.clone(),
+            v: v.clone(),
+            material,
+            d: Vector::dot(&normal, q),
+            w: &n / n.length_squared(),
+            area: n.length(),
+            normal,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let denominator = Vector::dot(&self.normal, ray.direction());
+        if denominator.abs() < 1e-12 {
+            return (false, HitRecord::empty());
+        }
+        let t = (self.d - Vector::dot(&self.normal, ray.origin())) / denominator;
+        if t <= t_min || t >= t_max {
+            return (false, HitRecord::empty());
+        }
+
+        let p = ray.at(t);
+        let offset = &p - &self.q;
+        let alpha = Vector::dot(&self.w, &Vector::cross(&offset, &self.v));
+        let beta = Vector::dot(&self.w, &Vector::cross(&self.u, &offset));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return (false, HitRecord::empty());
+        }
+
+        let mut hit_record = HitRecord {
+            t,
+            u: alpha,
+            v: beta,
+            p,
+            dpdu: self.u.clone(),
+            dpdv: self.v.clone(),
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &self.normal);
+        if !hit_record.is_opaque() {
+            return (false, HitRecord::empty());
+        }
+        (true, hit_record)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let corners = [
+            self.q.clone(),
+            &self.q + &self.u,
+            &self.q + &self.v,
+            &self.q + &self.u + &self.v,
+        ];
+        Some(Aabb::from_points(&corners).pad(1e-4))
+    }
+
+    /// Points are sampled uniformly over the area, which seen from `origin` has a density of
+    /// `distance² / (cos θ · area)` per solid angle.
+    fn pdf_value(&self, origin: &Vector, direction: &Vector) -> Option<f64> {
+        let (hit, hit_record) = self.hit(&Ray::new(origin, direction), 0.001, f64::INFINITY);
+        if !hit {
+            return Some(0.0);
+        }
+        let distance_squared = hit_record.t * hit_record.t * direction.length_squared();
+        let cosine = (Vector::dot(direction, &self.normal) / direction.length()).abs();
+        Some(distance_squared / (cosine * self.area))
+    }
+
+    fn random(&self, origin: &Vector) -> Option<Vector> {
+        let mut rng = rand::thread_rng();
+        let point = &self.q + rng.gen::<f64>() * &self.u + rng.gen::<f64>() * &self.v;
+        Some(point - origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    fn unit_square() -> Quad {
+        Quad::new(
+            &Vector::new(-0.5, -0.5, 0.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn hits_carry_coordinates_along_the_sides() {
+        let quad = unit_square();
+        let ray = Ray::new(&Vector::new(0.25, -0.3, 2.0), &Vector::new(0.0, 0.0, -1.0));
+        let (hit, hit_record) = quad.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 2.0, hit_record.t));
+        assert!(approx_eq!(f64, 0.75, hit_record.u));
+        assert!(approx_eq!(f64, 0.2, hit_record.v));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.z()));
+    }
+
+    #[test]
+    fn rays_past_the_edges_or_parallel_miss() {
+        let quad = unit_square();
+        let beside = Ray::new(&Vector::new(0.6, 0.0, 2.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!quad.hit(&beside, 0.001, f64::INFINITY).0);
+        let parallel = Ray::new(&Vector::new(0.0, 0.0, 1.0), &Vector::new(1.0, 0.0, 0.0));
+        assert!(!quad.hit(&parallel, 0.001, f64::INFINITY).0);
+    }
+
+    // Averaging 1/pdf over directions uniformly spread around the origin estimates the solid
+    // angle the quad subtends, which for a unit square seen from its center axis at distance one
+    // half is 2π/3.
+    #[test]
+    fn pdf_integrates_to_the_subtended_solid_angle() {
+        let quad = unit_square();
+        let origin = Vector::new(0.0, 0.0, 0.5);
+        let samples = 20_000;
+        let total: f64 = (0..samples)
+            .map(|_| {
+                let direction = quad.random(&origin).unwrap();
+                1.0 / quad.pdf_value(&origin, &direction).unwrap()
+            })
+            .sum();
+        let solid_angle = total / samples as f64;
+        let expected = 2.0 * std::f64::consts::PI / 3.0;
+        assert!((solid_angle - expected).abs() < 0.05, "{}", solid_angle);
+    }
+}
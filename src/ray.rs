@@ -1,9 +1,17 @@
+use std::rc::Rc;
+
+use crate::dielectric::Interior;
+use crate::spectrum::SampledWavelengths;
 use crate::vector::Vector;
 
 #[derive(Clone)]
 pub struct Ray {
     origin: Vector,
     direction: Vector,
+    /// The moment the ray was sent, within the camera's shutter interval.
+    time: f64,
+    wavelengths: Option<SampledWavelengths>,
+    interiors: Vec<Rc<Interior>>,
 }
 
 impl Ray {
@@ -11,9 +19,34 @@ impl Ray {
         Ray {
             origin: origin.clone(),
             direction: direction.clone(),
+            time: 0.0,
+            wavelengths: None,
+            interiors: Vec::new(),
+        }
+    }
+
+    /// Creates a ray continuing this ray's path, carrying along its time, its sampled wavelengths
+    /// and the dielectric interiors it is inside of.
+    pub fn spawn(&self, origin: &Vector, direction: &Vector) -> Ray {
+        Ray {
+            origin: origin.clone(),
+            direction: direction.clone(),
+            time: self.time,
+            wavelengths: self.wavelengths.clone(),
+            interiors: self.interiors.clone(),
         }
     }
 
+    pub fn with_time(mut self, time: f64) -> Ray {
+        self.time = time;
+        self
+    }
+
+    pub fn with_wavelengths(mut self, wavelengths: SampledWavelengths) -> Ray {
+        self.wavelengths = Some(wavelengths);
+        self
+    }
+
     pub fn origin(&self) -> &Vector {
         &self.origin
     }
@@ -22,6 +55,48 @@ impl Ray {
         &self.direction
     }
 
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The wavelengths this ray carries, if it is part of a spectral render.
+    pub fn wavelengths(&self) -> Option<&SampledWavelengths> {
+        self.wavelengths.as_ref()
+    }
+
+    pub fn terminate_secondary_wavelengths(&mut self) {
+        if let Some(wavelengths) = self.wavelengths.as_mut() {
+            wavelengths.terminate_secondary();
+        }
+    }
+
+    pub fn interiors(&self) -> &[Rc<Interior>] {
+        &self.interiors
+    }
+
+    pub fn enter(&mut self, interior: &Rc<Interior>) {
+        self.interiors.push(interior.clone());
+    }
+
+    pub fn exit(&mut self, interior: &Rc<Interior>) {
+        if let Some(index) = self
+            .interiors
+            .iter()
+            .rposition(|inside| Rc::ptr_eq(inside, interior))
+        {
+            self.interiors.remove(index);
+        }
+    }
+
+    /// The fraction of light of each color that survives travelling along the ray up to `t`,
+    /// given the dielectric interior the ray is travelling through.
+    pub fn transmittance(&self, t: f64) -> Vector {
+        match Interior::current(&self.interiors) {
+            Some(interior) => interior.transmittance(t * self.direction.length()),
+            None => Vector::new(1.0, 1.0, 1.0),
+        }
+    }
+
     pub fn at(&self, t: f64) -> Vector {
         self.origin() + (t * self.direction())
     }
@@ -1,23 +1,17 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use rand::Rng;
 
-use crate::camera::Camera;
 use crate::color::to_color;
-use crate::hittable::Hittable;
+use crate::environments::environment::{Environment, Fog};
+use crate::hittable::{HitRecord, Hittable};
 use crate::io::Image;
 use crate::ray::Ray;
-use crate::scenery::Scenery;
+use crate::spectrum::{SampledSpectrum, SampledWavelengths};
 use crate::vector::Vector;
 
-pub fn render_scenery(
-    scene: Scenery,
-    camera: &Camera,
-    image_size: (i32, i32),
-    file: &mut impl Image,
-    supersampling_ratio: i32,
-) {
+pub fn render_scenery(environment: &Environment, file: &mut impl Image, spectral: bool) {
     let max_depth = 50;
-    let (image_width, image_height) = image_size;
+    let (image_width, image_height) = (environment.image.width, environment.image.height);
     let mut rng = rand::thread_rng();
     println!("Rendering scene...");
     let progress_bar = ProgressBar::new(image_height as u64 * image_width as u64);
@@ -25,14 +19,21 @@ pub fn render_scenery(
         ProgressStyle::default_bar()
             .template("[{elapsed_precise}] {wide_bar} {pos}/{len}px, eta {eta} "),
     );
+    let supersampling_ratio = environment.image.supersampling_ratio;
     for j in (0..image_height).rev() {
         for i in 0..image_width {
             let mut color = Vector::new(0.0, 0.0, 0.0);
             for _ in 0..supersampling_ratio {
                 let u = (i as f64 + rng.gen::<f64>()) / image_width as f64;
                 let v = (j as f64 + rng.gen::<f64>()) / image_height as f64;
-                let ray = camera.get_ray(u, v);
-                color = color + ray_color(ray, &scene, &mut rng, max_depth);
+                let ray = environment.camera.get_ray(u, v);
+                let sample = if spectral {
+                    let ray = ray.with_wavelengths(SampledWavelengths::sample_uniform(rng.gen()));
+                    spectral_ray_color(ray, environment, &mut rng, max_depth)
+                } else {
+                    ray_color(ray, environment, &mut rng, max_depth, None)
+                };
+                color = color + sample;
             }
             //color.gamma_correct(supersampling_ratio);
             let color = to_color(color, supersampling_ratio);
@@ -43,23 +44,294 @@ pub fn render_scenery(
     progress_bar.finish();
 }
 
-fn ray_color(ray: Ray, world: &impl Hittable, rng: &mut impl rand::Rng, depth: i32) -> Vector {
+/// Follows the path of a ray and returns the light it brings back. `scatter_pdf` is the density
+/// with which the ray's direction was picked by the material it left, if the lights were sampled
+/// there too, so that light found both ways is weighted between them.
+fn ray_color(
+    ray: Ray,
+    environment: &Environment,
+    rng: &mut impl rand::Rng,
+    depth: i32,
+    scatter_pdf: Option<f64>,
+) -> Vector {
     if depth <= 0 {
         return Vector::new(0.0, 0.0, 0.0);
     }
 
-    let (hit, record) = world.hit(&ray, 0.001, std::f64::INFINITY);
-    if hit {
+    let (hit, record) = environment.scenery.hit(&ray, 0.001, f64::INFINITY);
+    if let Some(fog) = environment.fog.as_ref() {
+        let t_hit = if hit { record.t } else { f64::INFINITY };
+        if let Some(scattered) = scatter_in_fog(&ray, t_hit, fog, rng) {
+            return &fog.albedo * ray_color(scattered, environment, rng, depth - 1, None);
+        }
+    }
+    if !hit {
+        return background(&ray);
+    }
+
+    let material = record.material.as_ref().unwrap();
+    let mut color = material.emitted(&record);
+    if let (Some(scatter_pdf), Some(lights)) = (scatter_pdf, environment.lights.as_ref()) {
+        let light_pdf = lights
+            .pdf_value(ray.origin(), ray.direction())
+            .unwrap_or(0.0);
+        color = power_heuristic(scatter_pdf, light_pdf) * color;
+    }
+    let direct = sample_lights(&ray, &record, environment);
+    if let Some(direct) = &direct {
+        color = color + direct;
+    }
+    let (scatter, attenuation, scattered) = material.scatter(&ray, &record);
+    if scatter {
+        let wo = -ray.direction().unit_vector();
+        let scatter_pdf =
+            direct.map(|_| material.pdf(&record, &wo, &scattered.direction().unit_vector()));
+        color =
+            color + attenuation * ray_color(scattered, environment, rng, depth - 1, scatter_pdf);
+    }
+    ray.transmittance(record.t) * color
+}
+
+/// Picks a direction towards the lights from a hit, and returns the light arriving from there
+/// that the material sends back along the ray, weighted against finding the same light by
+/// scattering. `None` if there are no lights to sample or the material can't be evaluated.
+fn sample_lights(ray: &Ray, record: &HitRecord, environment: &Environment) -> Option<Vector> {
+    let lights = environment.lights.as_ref()?;
+    let material = record.material.as_ref()?;
+    let wo = -ray.direction().unit_vector();
+    let wi = lights.random(&record.p)?.unit_vector();
+    let f = material.eval(record, &wo, &wi)?;
+    let light_pdf = lights.pdf_value(&record.p, &wi).unwrap_or(0.0);
+    if light_pdf <= 0.0 || f.length_squared() == 0.0 {
+        return Some(Vector::empty());
+    }
+
+    // The shadow ray stops just short of the light, so that the light's own surface in the
+    // scenery doesn't block it.
+    let shadow = ray.spawn(&record.p, &wi);
+    let (hit, light) = lights.hit(&shadow, 0.001, f64::INFINITY);
+    if !hit {
+        return Some(Vector::empty());
+    }
+    let mut visibility = environment
+        .scenery
+        .transmittance(&shadow, 0.001, light.t - 0.001);
+    if let Some(fog) = environment.fog.as_ref() {
+        visibility *= (-fog.density * light.t).exp();
+    }
+    if visibility == 0.0 {
+        return Some(Vector::empty());
+    }
+    let transmittance = visibility * shadow.transmittance(light.t);
+    let emitted = light.material.as_ref()?.emitted(&light);
+    let weight = power_heuristic(light_pdf, material.pdf(record, &wo, &wi)) / light_pdf;
+    Some(weight * (f * emitted * transmittance))
+}
+
+/// The weight given to a sample drawn with density `pdf` over one drawn with `other_pdf` for the
+/// same direction, following Veach's power heuristic.
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b == 0.0 {
+        return 0.0;
+    }
+    a / (a + b)
+}
+
+/// Traces a path carrying several wavelengths, and returns its contribution as a linear RGB color.
+fn spectral_ray_color(
+    ray: Ray,
+    environment: &Environment,
+    rng: &mut impl rand::Rng,
+    depth: i32,
+) -> Vector {
+    let mut ray = ray;
+    let mut throughput = SampledSpectrum::constant(1.0);
+    let mut color = Vector::new(0.0, 0.0, 0.0);
+    for _ in 0..depth {
+        let wavelengths = ray.wavelengths().unwrap().clone();
+        let (hit, record) = environment.scenery.hit(&ray, 0.001, f64::INFINITY);
+        if let Some(fog) = environment.fog.as_ref() {
+            let t_hit = if hit { record.t } else { f64::INFINITY };
+            if let Some(scattered) = scatter_in_fog(&ray, t_hit, fog, rng) {
+                throughput = throughput * SampledSpectrum::from_rgb(&fog.albedo, &wavelengths);
+                ray = scattered;
+                continue;
+            }
+        }
+        if !hit {
+            let radiance = throughput * SampledSpectrum::from_rgb(&background(&ray), &wavelengths);
+            return color + wavelengths.to_rgb(&radiance);
+        }
         let material = record.material.as_ref().unwrap();
+        let transmittance = ray.transmittance(record.t);
+        let emitted = &transmittance * material.emitted(&record);
+        let radiance = &throughput * SampledSpectrum::from_rgb(&emitted, &wavelengths);
+        color = color + wavelengths.to_rgb(&radiance);
         let (scatter, attenuation, scattered) = material.scatter(&ray, &record);
-        if scatter {
-            attenuation * ray_color(scattered, world, rng, depth - 1)
+        if !scatter {
+            break;
+        }
+        let attenuation = transmittance * attenuation;
+        throughput = throughput * SampledSpectrum::from_rgb(&attenuation, &wavelengths);
+        ray = scattered;
+    }
+    color
+}
+
+/// Samples a free-flight distance through the fog, and scatters the ray in a random direction
+/// if it ends before the ray reaches `t_hit`.
+fn scatter_in_fog(ray: &Ray, t_hit: f64, fog: &Fog, rng: &mut impl rand::Rng) -> Option<Ray> {
+    let ray_length = ray.direction().length();
+    let fog_distance = -(1.0 - rng.gen::<f64>()).ln() / fog.density;
+    if fog_distance >= t_hit * ray_length {
+        return None;
+    }
+    let p = ray.at(fog_distance / ray_length);
+    Some(ray.spawn(&p, &Vector::random_unit()))
+}
+
+fn background(ray: &Ray) -> Vector {
+    let unit_direction = ray.direction().unit_vector();
+    let t = 0.5 * (unit_direction.y() + 1.0);
+    (1.0 - t) * Vector::new(1.0, 1.0, 1.0) + (t * Vector::new(0.5, 0.7, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::camera::{Camera, CameraConfig};
+    use crate::diffuse_light::DiffuseLight;
+    use crate::environments::environment::Image;
+    use crate::lambertian::Lambertian;
+    use crate::material::Material;
+    use crate::plane::Plane;
+    use crate::quad::Quad;
+    use crate::scenery::Scenery;
+
+    /// A grey floor under a square light, in an otherwise black world, with the light sampled
+    /// directly or not.
+    fn lit_floor(sample_light: bool) -> Environment {
+        let light: Rc<dyn Material> = Rc::new(DiffuseLight::new(&Vector::new(4.0, 4.0, 4.0)));
+        let square = || {
+            Quad::new(
+                &Vector::new(-0.5, 1.0, -0.5),
+                &Vector::new(1.0, 0.0, 0.0),
+                &Vector::new(0.0, 0.0, 1.0),
+                light.clone(),
+            )
+        };
+        let mut scenery = Scenery::empty();
+        scenery.add(Box::new(Plane::new(
+            &Vector::empty(),
+            &Vector::new(0.0, 1.0, 0.0),
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )));
+        scenery.add(Box::new(square()));
+        // A lid above the light, so that no sky is seen from the floor.
+        scenery.add(Box::new(Plane::new(
+            &Vector::new(0.0, 1.01, 0.0),
+            &Vector::new(0.0, -1.0, 0.0),
+            Rc::new(Lambertian::new(&Vector::empty())),
+        )));
+        let lights = if sample_light {
+            let mut lights = Scenery::empty();
+            lights.add(Box::new(square()));
+            Some(lights)
         } else {
-            Vector::new(0.0, 0.0, 0.0)
+            None
+        };
+        let config = CameraConfig {
+            location: Vector::new(0.0, 0.5, 1.0),
+            lookat: Vector::empty(),
+            up: Vector::new(0.0, 1.0, 0.0),
+            field_of_view: 40.0,
+            aspect_ratio: 1.0,
+            focus_distance: 1.0,
+            aperture: 0.0,
+        };
+        Environment {
+            scenery,
+            lights,
+            camera: Camera::from_config(config),
+            image: Image {
+                width: 1,
+                height: 1,
+                supersampling_ratio: 1,
+            },
+            fog: None,
+        }
+    }
+
+    // Sampling the light directly changes how quickly the estimate converges, not what it
+    // converges to.
+    #[test]
+    fn light_sampling_agrees_with_scattering_alone() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let ray = Ray::new(&Vector::new(0.0, 0.5, 1.0), &Vector::new(0.0, -0.5, -1.0));
+        let mean = |environment: &Environment, rng: &mut StdRng, samples: usize| {
+            (0..samples)
+                .map(|_| ray_color(ray.clone(), environment, rng, 2, None).x())
+                .sum::<f64>()
+                / samples as f64
+        };
+        let sampled = mean(&lit_floor(true), &mut rng, 2_000);
+        let scattered = mean(&lit_floor(false), &mut rng, 200_000);
+        assert!(sampled > 0.1, "{}", sampled);
+        assert!(
+            (sampled - scattered).abs() < 0.02 * scattered,
+            "{} {}",
+            sampled,
+            scattered
+        );
+    }
+
+    // Light reaches a surface through fog with probability exp(-density * distance), measured
+    // in world units rather than along the ray's parameter.
+    #[test]
+    fn fog_attenuates_with_distance() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let fog = Fog {
+            density: 0.2,
+            albedo: Vector::new(0.9, 0.9, 0.9),
+        };
+        let ray = Ray::new(&Vector::empty(), &Vector::new(0.0, 0.0, -2.0));
+        let samples = 20_000;
+        for &t_hit in &[0.5_f64, 2.0, 5.0] {
+            let reached = (0..samples)
+                .filter(|_| scatter_in_fog(&ray, t_hit, &fog, &mut rng).is_none())
+                .count();
+            let expected = (-0.2 * 2.0 * t_hit).exp();
+            let measured = reached as f64 / samples as f64;
+            assert!(
+                (measured - expected).abs() < 0.015,
+                "{} {}",
+                t_hit,
+                measured
+            );
+        }
+        // Nothing gets through fog that goes on forever.
+        assert!(scatter_in_fog(&ray, f64::INFINITY, &fog, &mut rng).is_some());
+    }
+
+    #[test]
+    fn fog_scatters_between_the_camera_and_the_hit() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let fog = Fog {
+            density: 1.0,
+            albedo: Vector::new(0.9, 0.9, 0.9),
+        };
+        let ray = Ray::new(&Vector::empty(), &Vector::new(0.0, 0.0, -1.0));
+        for _ in 0..1000 {
+            if let Some(scattered) = scatter_in_fog(&ray, 3.0, &fog, &mut rng) {
+                let z = scattered.origin().z();
+                assert!(z <= 0.0 && z > -3.0, "{}", z);
+            }
         }
-    } else {
-        let unit_direction = ray.direction().unit_vector();
-        let t = 0.5 * (unit_direction.y() + 1.0);
-        (1.0 - t) * Vector::new(1.0, 1.0, 1.0) + (t * Vector::new(0.5, 0.7, 1.0))
     }
 }
@@ -0,0 +1,189 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::fresnel;
+use crate::hittable::HitRecord;
+use crate::material::Material;
+use crate::microfacet::{self, Ggx};
+use crate::ray::Ray;
+use crate::textures::solid_color::SolidColor;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Frosted glass: a dielectric whose surface is made of GGX microfacets, which both reflect and
+/// transmit light following Walter et al. The roughness is read from the mean of a texture's
+/// channels, so it can vary over the surface.
+pub struct RoughDielectric {
+    refraction_index: f64,
+    roughness: Rc<dyn Texture>,
+}
+
+impl RoughDielectric {
+    pub fn new(refraction_index: f64, roughness: f64) -> RoughDielectric {
+        let roughness = Vector::new(roughness, roughness, roughness);
+        Self::textured(refraction_index, Rc::new(SolidColor::new(&roughness)))
+    }
+
+    pub fn textured(refraction_index: f64, roughness: Rc<dyn Texture>) -> RoughDielectric {
+        RoughDielectric {
+            refraction_index,
+            roughness,
+        }
+    }
+
+    /// Samples an incoming direction in the local shading frame, given the ratio `eta` of the
+    /// refractive index below the surface to the index above it. Reflection and transmission are
+    /// chosen in proportion to the Fresnel reflectance, so only the shadowing term is left in the
+    /// returned weight.
+    fn sample(
+        distribution: &Ggx,
+        wo: &Vector,
+        eta: f64,
+        u: (f64, f64, f64),
+    ) -> Option<(Vector, f64)> {
+        if wo.z() <= 0.0 {
+            return None;
+        }
+        let h = distribution.sample_visible_normal(wo, u.0, u.1);
+        let reflectance = fresnel::dielectric(Vector::dot(wo, &h), eta);
+        let wi = if u.2 < reflectance {
+            let wi = microfacet::reflect(wo, &h);
+            if wi.z() <= 0.0 {
+                return None;
+            }
+            wi
+        } else {
+            let wi = microfacet::refract(wo, &h, eta)?;
+            if wi.z() >= 0.0 {
+                return None;
+            }
+            wi
+        };
+        let weight = distribution.g2(wo, &wi) / distribution.g1(wo);
+        Some((wi, weight))
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, ray_in: &Ray, hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        let roughness = self
+            .roughness
+            .value(hit_record.u, hit_record.v, &hit_record.p);
+        let distribution =
+            Ggx::from_roughness((roughness.x() + roughness.y() + roughness.z()) / 3.0);
+        let eta = if hit_record.front_face {
+            self.refraction_index
+        } else {
+            1.0 / self.refraction_index
+        };
+
+        let frame = hit_record.shading_frame();
+        let wo = frame.to_local(&-ray_in.direction().unit_vector());
+        let mut rng = rand::thread_rng();
+        match Self::sample(&distribution, &wo, eta, (rng.gen(), rng.gen(), rng.gen())) {
+            Some((wi, weight)) => {
+                let direction = frame.local(wi.x(), wi.y(), wi.z());
+                // Reflections must stay on the incoming side of the real surface, and
+                // transmissions must cross it.
+                let side = Vector::dot(&direction, &hit_record.geometric_normal);
+                let scatter = (side > 0.0) == (wi.z() > 0.0);
+                let attenuation = Vector::new(weight, weight, weight);
+                (
+                    scatter,
+                    attenuation,
+                    ray_in.spawn(&hit_record.p, &direction),
+                )
+            }
+            None => (false, Vector::empty(), ray_in.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn direction(theta: f64) -> Vector {
+        Vector::new(theta.sin(), 0.0, theta.cos())
+    }
+
+    // Returns the mean reflected and transmitted weights.
+    fn albedo(roughness: f64, theta: f64, eta: f64, rng: &mut StdRng) -> (f64, f64) {
+        let distribution = Ggx::from_roughness(roughness);
+        let wo = direction(theta);
+        let samples = 20_000;
+        let (mut reflected, mut transmitted) = (0.0, 0.0);
+        for _ in 0..samples {
+            let u = (rng.gen(), rng.gen(), rng.gen());
+            if let Some((wi, weight)) = RoughDielectric::sample(&distribution, &wo, eta, u) {
+                if wi.z() > 0.0 {
+                    reflected += weight;
+                } else {
+                    transmitted += weight;
+                }
+            }
+        }
+        (reflected / samples as f64, transmitted / samples as f64)
+    }
+
+    #[test]
+    fn smooth_limit_splits_energy_by_fresnel() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for &(theta, eta) in &[(0.3, 1.5), (1.2, 1.5), (0.4, 1.0 / 1.5)] {
+            let (reflected, transmitted) = albedo(0.0, theta, eta, &mut rng);
+            let expected = fresnel::dielectric(theta.cos(), eta);
+            assert!(
+                (reflected - expected).abs() < 0.02,
+                "{} {}",
+                reflected,
+                expected
+            );
+            assert!((reflected + transmitted - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn total_internal_reflection_keeps_smooth_light_inside() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (reflected, transmitted) = albedo(0.0, 1.0, 1.0 / 1.5, &mut rng);
+        assert!((reflected - 1.0).abs() < 1e-3);
+        assert_eq!(0.0, transmitted);
+    }
+
+    #[test]
+    fn rough_surfaces_never_gain_energy() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for &roughness in &[0.2, 0.5, 0.9] {
+            for &theta in &[0.0, 0.8, 1.4] {
+                for &eta in &[1.5, 1.0 / 1.5] {
+                    let (reflected, transmitted) = albedo(roughness, theta, eta, &mut rng);
+                    let total = reflected + transmitted;
+                    assert!(
+                        total <= 1.0 && total > 0.3,
+                        "{} {} {}",
+                        roughness,
+                        theta,
+                        total
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transmitted_directions_cross_the_surface() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let distribution = Ggx::from_roughness(0.5);
+        let wo = direction(0.5);
+        for _ in 0..1000 {
+            let u = (rng.gen(), rng.gen(), 0.999);
+            if let Some((wi, _)) = RoughDielectric::sample(&distribution, &wo, 1.5, u) {
+                assert!(wi.z() < 0.0);
+                assert!((wi.length() - 1.0).abs() < 1e-9);
+            }
+        }
+    }
+}
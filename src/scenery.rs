@@ -1,6 +1,10 @@
+use rand::seq::SliceRandom;
+
+use crate::aabb::Aabb;
 use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::ray::Ray;
+use crate::vector::Vector;
 
 pub struct Scenery {
     objects: Vec<Box<dyn Hittable>>,
@@ -35,4 +39,98 @@ impl Hittable for Scenery {
 
         (hit_anything, last_hit_record)
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut objects = self.objects.iter();
+        let first = objects.next()?.bounding_box()?;
+        objects.try_fold(first, |bounds, object| {
+            Some(bounds.surrounding(&object.bounding_box()?))
+        })
+    }
+
+    /// The light that gets through every object in turn.
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let mut transmittance = 1.0;
+        for object in &self.objects {
+            transmittance *= object.transmittance(ray, t_min, t_max);
+            if transmittance == 0.0 {
+                break;
+            }
+        }
+        transmittance
+    }
+
+    /// Picks each of the objects that can be sampled with equal probability, so the density is the
+    /// mean of theirs. Objects that can't be sampled are skipped.
+    fn pdf_value(&self, origin: &Vector, direction: &Vector) -> Option<f64> {
+        let densities: Vec<f64> = self
+            .objects
+            .iter()
+            .filter_map(|object| object.pdf_value(origin, direction))
+            .collect();
+        if densities.is_empty() {
+            return None;
+        }
+        Some(densities.iter().sum::<f64>() / densities.len() as f64)
+    }
+
+    fn random(&self, origin: &Vector) -> Option<Vector> {
+        // The first object that can be sampled in a random order is equally likely to be any of
+        // them.
+        let mut order: Vec<usize> = (0..self.objects.len()).collect();
+        order.shuffle(&mut rand::thread_rng());
+        order
+            .into_iter()
+            .find_map(|index| self.objects[index].random(origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::plane::Plane;
+    use crate::quad::Quad;
+
+    #[test]
+    fn sampling_skips_objects_that_cannot_be_sampled() {
+        let grey = Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5)));
+        let quad = Quad::new(
+            &Vector::new(-0.5, -0.5, -1.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            grey.clone(),
+        );
+        let origin = Vector::empty();
+        let mut scenery = Scenery::empty();
+        assert!(scenery.random(&origin).is_none());
+        assert!(scenery
+            .pdf_value(&origin, &Vector::new(0.0, 0.0, -1.0))
+            .is_none());
+
+        scenery.add(Box::new(Plane::new(
+            &Vector::new(0.0, -1.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            grey.clone(),
+        )));
+        scenery.add(Box::new(Quad::new(
+            &Vector::new(-0.5, -0.5, -1.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            grey,
+        )));
+        for _ in 0..20 {
+            let direction = scenery.random(&origin).unwrap();
+            assert!(approx_eq!(f64, -1.0, direction.z(), epsilon = 1e-9));
+            assert!(approx_eq!(
+                f64,
+                quad.pdf_value(&origin, &direction).unwrap(),
+                scenery.pdf_value(&origin, &direction).unwrap()
+            ));
+        }
+    }
 }
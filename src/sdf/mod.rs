@@ -0,0 +1,11 @@
+pub mod operators;
+pub mod primitives;
+
+use crate::vector::Vector;
+
+/// A signed distance field: the distance from a point to the nearest surface of a shape, negative
+/// inside it. Fields that only bound the true distance from below still render, as long as they
+/// never overestimate it.
+pub trait Sdf {
+    fn distance(&self, p: &Vector) -> f64;
+}
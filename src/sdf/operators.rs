@@ -0,0 +1,238 @@
+use std::rc::Rc;
+
+use crate::sdf::Sdf;
+use crate::vector::Vector;
+
+/// Everything inside either field.
+pub struct Union {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Union {
+    // The scenes so far only combine fields with `Subtraction` and `SmoothUnion`.
+    #[allow(dead_code)]
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Union {
+        Union { a, b }
+    }
+}
+
+impl Sdf for Union {
+    fn distance(&self, p: &Vector) -> f64 {
+        self.a.distance(p).min(self.b.distance(p))
+    }
+}
+
+/// Only what is inside both fields.
+pub struct Intersection {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Intersection {
+    // The scenes so far only combine fields with `Subtraction` and `SmoothUnion`.
+    #[allow(dead_code)]
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Intersection {
+        Intersection { a, b }
+    }
+}
+
+impl Sdf for Intersection {
+    fn distance(&self, p: &Vector) -> f64 {
+        self.a.distance(p).max(self.b.distance(p))
+    }
+}
+
+/// What is inside `a` but not inside `b`.
+pub struct Subtraction {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Subtraction {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Subtraction {
+        Subtraction { a, b }
+    }
+}
+
+impl Sdf for Subtraction {
+    fn distance(&self, p: &Vector) -> f64 {
+        self.a.distance(p).max(-self.b.distance(p))
+    }
+}
+
+/// A union that blends the two fields together with a fillet where they meet, over a distance of
+/// about `smoothness`, using the polynomial smooth minimum.
+pub struct SmoothUnion {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+    smoothness: f64,
+}
+
+impl SmoothUnion {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>, smoothness: f64) -> SmoothUnion {
+        SmoothUnion { a, b, smoothness }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Vector) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        if self.smoothness <= 0.0 {
+            return a.min(b);
+        }
+        let h = (self.smoothness - (a - b).abs()).max(0.0) / self.smoothness;
+        a.min(b) - h * h * self.smoothness / 4.0
+    }
+}
+
+/// Moves a field by `offset`.
+pub struct Translate {
+    sdf: Rc<dyn Sdf>,
+    offset: Vector,
+}
+
+impl Translate {
+    pub fn new(sdf: Rc<dyn Sdf>, offset: &Vector) -> Translate {
+        Translate {
+            sdf,
+            offset: offset.clone(),
+        }
+    }
+}
+
+impl Sdf for Translate {
+    fn distance(&self, p: &Vector) -> f64 {
+        self.sdf.distance(&(p - &self.offset))
+    }
+}
+
+/// Twists a field around the y axis by `rate` radians per unit of height. Twisting stretches
+/// distances, so the field no longer bounds the true distance; render it with a smaller step
+/// scale the further points are from the axis.
+pub struct Twist {
+    sdf: Rc<dyn Sdf>,
+    rate: f64,
+}
+
+impl Twist {
+    pub fn new(sdf: Rc<dyn Sdf>, rate: f64) -> Twist {
+        Twist { sdf, rate }
+    }
+}
+
+impl Sdf for Twist {
+    fn distance(&self, p: &Vector) -> f64 {
+        let (sin, cos) = (self.rate * p.y()).sin_cos();
+        let untwisted = Vector::new(cos * p.x() + sin * p.z(), p.y(), -sin * p.x() + cos * p.z());
+        self.sdf.distance(&untwisted)
+    }
+}
+
+/// Repeats a field endlessly on a grid with the given spacing along each axis. A spacing of zero
+/// leaves that axis alone. The repeated shape should fit within one cell.
+pub struct Repeat {
+    sdf: Rc<dyn Sdf>,
+    spacing: Vector,
+}
+
+impl Repeat {
+    pub fn new(sdf: Rc<dyn Sdf>, spacing: &Vector) -> Repeat {
+        Repeat {
+            sdf,
+            spacing: spacing.clone(),
+        }
+    }
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, p: &Vector) -> f64 {
+        let wrap = |x: f64, spacing: f64| {
+            if spacing > 0.0 {
+                x - spacing * (x / spacing).round()
+            } else {
+                x
+            }
+        };
+        let cell = Vector::new(
+            wrap(p.x(), self.spacing.x()),
+            wrap(p.y(), self.spacing.y()),
+            wrap(p.z(), self.spacing.z()),
+        );
+        self.sdf.distance(&cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::sdf::primitives::Ball;
+
+    fn ball_at(x: f64) -> Rc<dyn Sdf> {
+        Rc::new(Translate::new(
+            Rc::new(Ball::new(1.0)),
+            &Vector::new(x, 0.0, 0.0),
+        ))
+    }
+
+    #[test]
+    fn boolean_operators_combine_the_insides() {
+        let (left, right) = (ball_at(-0.5), ball_at(0.5));
+        let at = |x: f64| Vector::new(x, 0.0, 0.0);
+        let union = Union::new(left.clone(), right.clone());
+        assert!(approx_eq!(f64, -0.5, union.distance(&at(1.0))));
+        let intersection = Intersection::new(left.clone(), right.clone());
+        assert!(approx_eq!(f64, 0.5, intersection.distance(&at(1.0))));
+        assert!(approx_eq!(f64, -0.5, intersection.distance(&at(0.0))));
+        let subtraction = Subtraction::new(left, right);
+        assert!(approx_eq!(f64, 0.5, subtraction.distance(&at(0.0))));
+        assert!(subtraction.distance(&at(-1.0)) < 0.0);
+    }
+
+    #[test]
+    fn smooth_union_fills_in_where_shapes_meet() {
+        let (left, right) = (ball_at(-1.2), ball_at(1.2));
+        let sharp = Union::new(left.clone(), right.clone());
+        let smooth = SmoothUnion::new(left, right, 1.0);
+        let between = Vector::new(0.0, 0.0, 0.0);
+        assert!(smooth.distance(&between) < sharp.distance(&between));
+        // Far from the seam, the shapes are untouched.
+        let far = Vector::new(-3.0, 0.0, 0.0);
+        assert!(approx_eq!(f64, sharp.distance(&far), smooth.distance(&far)));
+    }
+
+    #[test]
+    fn twist_and_repeat_move_points_before_measuring() {
+        let ball = Rc::new(Translate::new(
+            Rc::new(Ball::new(1.0)),
+            &Vector::new(2.0, 1.0, 0.0),
+        ));
+        // A quarter turn per unit of height carries the ball, one unit up, round from +x to +z.
+        let twisted = Twist::new(ball, std::f64::consts::FRAC_PI_2);
+        let center = Vector::new(0.0, 1.0, 2.0);
+        assert!(approx_eq!(
+            f64,
+            -1.0,
+            twisted.distance(&center),
+            epsilon = 1e-12
+        ));
+        let repeated = Repeat::new(Rc::new(Ball::new(0.5)), &Vector::new(3.0, 0.0, 3.0));
+        assert!(approx_eq!(
+            f64,
+            -0.5,
+            repeated.distance(&Vector::new(6.0, 0.0, -3.0))
+        ));
+        assert!(approx_eq!(
+            f64,
+            0.5,
+            repeated.distance(&Vector::new(7.0, 0.0, 0.0))
+        ));
+        assert!(approx_eq!(
+            f64,
+            1.5,
+            repeated.distance(&Vector::new(0.0, 2.0, 0.0))
+        ));
+    }
+}
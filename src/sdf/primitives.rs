@@ -0,0 +1,208 @@
+use crate::sdf::Sdf;
+use crate::vector::Vector;
+
+/// A ball of the given radius around the origin.
+pub struct Ball {
+    radius: f64,
+}
+
+impl Ball {
+    pub fn new(radius: f64) -> Ball {
+        Ball { radius }
+    }
+}
+
+impl Sdf for Ball {
+    fn distance(&self, p: &Vector) -> f64 {
+        p.length() - self.radius
+    }
+}
+
+/// A box around the origin, reaching `half_extents` along each axis, with its edges and corners
+/// rounded off to `rounding`.
+pub struct RoundedBox {
+    half_extents: Vector,
+    rounding: f64,
+}
+
+impl RoundedBox {
+    pub fn new(half_extents: &Vector, rounding: f64) -> RoundedBox {
+        RoundedBox {
+            half_extents: half_extents.clone(),
+            rounding,
+        }
+    }
+}
+
+impl Sdf for RoundedBox {
+    fn distance(&self, p: &Vector) -> f64 {
+        // Shrink the box by the rounding and inflate the result by it again.
+        let q = Vector::new(
+            p.x().abs() - self.half_extents.x() + self.rounding,
+            p.y().abs() - self.half_extents.y() + self.rounding,
+            p.z().abs() - self.half_extents.z() + self.rounding,
+        );
+        let outside = Vector::new(q.x().max(0.0), q.y().max(0.0), q.z().max(0.0)).length();
+        let inside = q.x().max(q.y()).max(q.z()).min(0.0);
+        outside + inside - self.rounding
+    }
+}
+
+/// A ring around the y axis, with a tube of radius `minor_radius` following a circle of radius
+/// `major_radius`.
+pub struct Ring {
+    major_radius: f64,
+    minor_radius: f64,
+}
+
+impl Ring {
+    pub fn new(major_radius: f64, minor_radius: f64) -> Ring {
+        Ring {
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for Ring {
+    fn distance(&self, p: &Vector) -> f64 {
+        let ring = (p.x() * p.x() + p.z() * p.z()).sqrt() - self.major_radius;
+        (ring * ring + p.y() * p.y()).sqrt() - self.minor_radius
+    }
+}
+
+/// A rod of the given radius with hemispherical ends, running between two points.
+pub struct Capsule {
+    a: Vector,
+    b: Vector,
+    radius: f64,
+}
+
+impl Capsule {
+    pub fn new(a: &Vector, b: &Vector, radius: f64) -> Capsule {
+        Capsule {
+            a: a.clone(),
+            b: b.clone(),
+            radius,
+        }
+    }
+}
+
+impl Sdf for Capsule {
+    fn distance(&self, p: &Vector) -> f64 {
+        let pa = p - &self.a;
+        let ba = &self.b - &self.a;
+        let h = (Vector::dot(&pa, &ba) / ba.length_squared()).clamp(0.0, 1.0);
+        (pa - h * ba).length() - self.radius
+    }
+}
+
+/// The Mandelbulb, a three-dimensional relative of the Mandelbrot set, at the origin. Its distance
+/// is estimated from how fast points escape under iteration, so it is only approximate.
+pub struct Mandelbulb {
+    power: f64,
+    iterations: usize,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f64, iterations: usize) -> Mandelbulb {
+        Mandelbulb { power, iterations }
+    }
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, p: &Vector) -> f64 {
+        let mut z = p.clone();
+        let mut derivative = 1.0;
+        let mut r = z.length();
+        for _ in 0..self.iterations {
+            if r > 2.0 {
+                break;
+            }
+            // Raise z to the power in spherical coordinates and add the starting point.
+            let theta = (z.y() / r).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z.z().atan2(z.x()) * self.power;
+            derivative = self.power * r.powf(self.power - 1.0) * derivative + 1.0;
+            let scaled = r.powf(self.power);
+            z = scaled
+                * Vector::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                )
+                + p;
+            r = z.length();
+        }
+        if r <= 0.0 {
+            return 0.0;
+        }
+        0.5 * r.ln() * r / derivative
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn distances_are_exact_for_simple_shapes() {
+        let ball = Ball::new(1.0);
+        assert!(approx_eq!(
+            f64,
+            2.0,
+            ball.distance(&Vector::new(0.0, 3.0, 0.0))
+        ));
+        assert!(approx_eq!(f64, -1.0, ball.distance(&Vector::empty())));
+
+        let rounded = RoundedBox::new(&Vector::new(1.0, 2.0, 3.0), 0.5);
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            rounded.distance(&Vector::new(2.0, 0.0, 0.0))
+        ));
+        assert!(approx_eq!(f64, -1.0, rounded.distance(&Vector::empty())));
+        // The corner of the sharp box lies outside the rounded one.
+        let corner = RoundedBox::new(&Vector::new(1.0, 1.0, 1.0), 0.5);
+        let distance = corner.distance(&Vector::new(1.0, 1.0, 1.0));
+        assert!(approx_eq!(
+            f64,
+            0.75_f64.sqrt() - 0.5,
+            distance,
+            epsilon = 1e-12
+        ));
+
+        let ring = Ring::new(2.0, 0.5);
+        assert!(approx_eq!(
+            f64,
+            -0.5,
+            ring.distance(&Vector::new(0.0, 0.0, 2.0))
+        ));
+        assert!(approx_eq!(f64, 1.5, ring.distance(&Vector::empty())));
+
+        let capsule = Capsule::new(&Vector::empty(), &Vector::new(0.0, 2.0, 0.0), 0.5);
+        assert!(approx_eq!(
+            f64,
+            0.5,
+            capsule.distance(&Vector::new(1.0, 1.0, 0.0))
+        ));
+        assert!(approx_eq!(
+            f64,
+            0.5,
+            capsule.distance(&Vector::new(0.0, 3.0, 0.0))
+        ));
+    }
+
+    #[test]
+    fn mandelbulb_distance_never_overshoots_its_bounding_sphere() {
+        let bulb = Mandelbulb::new(8.0, 12);
+        // The bulb lies within a radius of about 1.2, so from further out the estimate must not
+        // exceed the distance to that sphere's far side.
+        for &x in &[1.5, 2.0, 3.0] {
+            let distance = bulb.distance(&Vector::new(x, 0.3, 0.2));
+            assert!(distance > 0.0 && distance < x + 1.2, "{}", distance);
+        }
+        assert!(bulb.distance(&Vector::new(0.0, 0.1, 0.0)) < 1e-3);
+    }
+}
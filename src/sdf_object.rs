@@ -0,0 +1,166 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sdf::Sdf;
+use crate::vector::Vector;
+
+/// The most steps a ray may take through the bounds before it is taken to have missed.
+const MAX_STEPS: usize = 512;
+
+/// A shape given by a signed distance field, found by sphere tracing: stepping along the ray by
+/// the distance to the nearest surface, which can never step through it. Marching is limited to
+/// where the ray is inside `bounds`, which must contain the whole shape.
+pub struct SdfObject {
+    sdf: Rc<dyn Sdf>,
+    bounds: Aabb,
+    material: Rc<dyn Material>,
+    /// How close to the surface counts as on it, which also sets the spacing of the central
+    /// differences that give the normal.
+    epsilon: f64,
+    /// Scales each step, for fields that overestimate the distance.
+    step_scale: f64,
+}
+
+impl SdfObject {
+    pub fn new(sdf: Rc<dyn Sdf>, bounds: Aabb, material: Rc<dyn Material>) -> SdfObject {
+        SdfObject {
+            sdf,
+            bounds,
+            material,
+            epsilon: 1e-4,
+            step_scale: 1.0,
+        }
+    }
+
+    /// Takes shorter steps, for fields such as twists that can overestimate the distance.
+    pub fn with_step_scale(mut self, step_scale: f64) -> SdfObject {
+        self.step_scale = step_scale;
+        self
+    }
+
+    pub fn with_epsilon(mut self, epsilon: f64) -> SdfObject {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// The gradient of the field by central differences, which points out of the shape.
+    fn normal(&self, p: &Vector) -> Vector {
+        let h = self.epsilon;
+        let difference =
+            |offset: Vector| self.sdf.distance(&(p + &offset)) - self.sdf.distance(&(p - &offset));
+        Vector::new(
+            difference(Vector::new(h, 0.0, 0.0)),
+            difference(Vector::new(0.0, h, 0.0)),
+            difference(Vector::new(0.0, 0.0, h)),
+        )
+        .unit_vector()
+    }
+}
+
+impl Hittable for SdfObject {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        let (hit, t_enter, t_exit) = self.bounds.hit(ray, t_min, t_max);
+        if !hit {
+            return (false, HitRecord::empty());
+        }
+        let ray_length = ray.direction().length();
+        let mut t = t_enter;
+        // Rays leaving the surface start right on it, so a hit only counts once the ray has got
+        // clear of the surface at least once. Rays from outside the bounds start clear.
+        let mut clear = t_enter > t_min;
+        for _ in 0..MAX_STEPS {
+            if t > t_exit {
+                break;
+            }
+            let p = ray.at(t);
+            let distance = self.sdf.distance(&p).abs();
+            if distance < self.epsilon {
+                if clear {
+                    let mut hit_record = HitRecord {
+                        t,
+                        material: Some(self.material.clone()),
+                        ..HitRecord::empty()
+                    };
+                    hit_record.set_face_normal(ray, &self.normal(&p));
+                    hit_record.p = p;
+                    if hit_record.is_opaque() {
+                        return (true, hit_record);
+                    }
+                    clear = false;
+                }
+            } else {
+                clear = true;
+            }
+            t += (distance * self.step_scale).max(self.epsilon) / ray_length;
+        }
+        (false, HitRecord::empty())
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::sdf::primitives::{Ball, RoundedBox};
+
+    fn object(sdf: Rc<dyn Sdf>, half_size: f64) -> SdfObject {
+        let extent = Vector::new(half_size, half_size, half_size);
+        SdfObject::new(
+            sdf,
+            Aabb::new(&-&extent, &extent),
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    #[test]
+    fn traced_ball_matches_the_analytic_sphere() {
+        let ball = object(Rc::new(Ball::new(1.0)), 1.1);
+        let ray = Ray::new(&Vector::new(0.3, 0.4, 5.0), &Vector::new(0.0, 0.0, -2.0));
+        let (hit, hit_record) = ball.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        let z = (1.0_f64 - 0.25).sqrt();
+        assert!(approx_eq!(
+            f64,
+            (5.0 - z) / 2.0,
+            hit_record.t,
+            epsilon = 1e-4
+        ));
+        let expected = Vector::new(0.3, 0.4, z);
+        assert!((&hit_record.normal - expected).length() < 1e-3);
+        assert!(hit_record.front_face);
+
+        // A ray refracted into the ball starts on its surface and finds the far side.
+        let inside = Ray::new(&hit_record.p, &Vector::new(0.0, 0.0, -1.0));
+        let (hit, hit_record) = ball.hit(&inside, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, -z, hit_record.p.z(), epsilon = 1e-3));
+    }
+
+    #[test]
+    fn rays_outside_the_bounds_never_march() {
+        let rounded = object(
+            Rc::new(RoundedBox::new(&Vector::new(1.0, 1.0, 1.0), 0.2)),
+            1.0,
+        );
+        let beside = Ray::new(&Vector::new(1.5, 0.0, 5.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!rounded.hit(&beside, 0.001, f64::INFINITY).0);
+        // Near a rounded edge, the ray misses the corner a sharp box would have had.
+        let corner = Ray::new(&Vector::new(0.97, 0.97, 5.0), &Vector::new(0.0, 0.0, -1.0));
+        assert!(!rounded.hit(&corner, 0.001, f64::INFINITY).0);
+        let face = Ray::new(&Vector::new(0.5, 0.5, 5.0), &Vector::new(0.0, 0.0, -1.0));
+        let (hit, hit_record) = rounded.hit(&face, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 4.0, hit_record.t, epsilon = 1e-4));
+    }
+}
@@ -0,0 +1,299 @@
+use std::ops;
+
+use crate::vector::Vector;
+
+/// The number of wavelengths carried along each path.
+pub const SAMPLE_COUNT: usize = 4;
+
+pub const LAMBDA_MIN: f64 = 360.0;
+pub const LAMBDA_MAX: f64 = 830.0;
+
+// Integrals of the color matching functions below over [LAMBDA_MIN, LAMBDA_MAX].
+const CIE_X_INTEGRAL: f64 = 106.766;
+const CIE_Y_INTEGRAL: f64 = 106.922;
+const CIE_Z_INTEGRAL: f64 = 106.875;
+
+/// The wavelengths, in nanometers, carried along a path, along with the probability density they
+/// were sampled with. The first wavelength is the hero wavelength; the others are evenly spaced
+/// rotations of it across the visible range.
+#[derive(Clone)]
+pub struct SampledWavelengths {
+    lambda: [f64; SAMPLE_COUNT],
+    pdf: [f64; SAMPLE_COUNT],
+}
+
+impl SampledWavelengths {
+    pub fn sample_uniform(u: f64) -> SampledWavelengths {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let delta = range / SAMPLE_COUNT as f64;
+        let mut lambda = [0.0; SAMPLE_COUNT];
+        lambda[0] = LAMBDA_MIN + u * range;
+        for i in 1..SAMPLE_COUNT {
+            lambda[i] = lambda[i - 1] + delta;
+            if lambda[i] > LAMBDA_MAX {
+                lambda[i] -= range;
+            }
+        }
+        SampledWavelengths {
+            lambda,
+            pdf: [1.0 / range; SAMPLE_COUNT],
+        }
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    pub fn lambda(&self, i: usize) -> f64 {
+        self.lambda[i]
+    }
+
+    pub fn secondary_terminated(&self) -> bool {
+        self.pdf[1..].iter().all(|&pdf| pdf == 0.0)
+    }
+
+    /// Drops every wavelength but the hero, for interactions such as dispersion where the
+    /// wavelengths would otherwise need to follow different paths.
+    pub fn terminate_secondary(&mut self) {
+        if self.secondary_terminated() {
+            return;
+        }
+        for pdf in self.pdf[1..].iter_mut() {
+            *pdf = 0.0;
+        }
+        self.pdf[0] /= SAMPLE_COUNT as f64;
+    }
+
+    /// Converts radiance at these wavelengths into a linear sRGB color by Monte Carlo integration
+    /// against the CIE color matching functions.
+    pub fn to_rgb(&self, radiance: &SampledSpectrum) -> Vector {
+        let mut xyz = Vector::empty();
+        for i in 0..SAMPLE_COUNT {
+            if self.pdf[i] == 0.0 {
+                continue;
+            }
+            xyz = xyz + (radiance.0[i] / self.pdf[i]) * cie_xyz(self.lambda[i]);
+        }
+        let rgb = xyz_to_rgb(&(xyz / (SAMPLE_COUNT as f64 * CIE_Y_INTEGRAL)));
+        let white = white_rgb();
+        Vector::new(
+            rgb.x() / white.x(),
+            rgb.y() / white.y(),
+            rgb.z() / white.z(),
+        )
+    }
+}
+
+/// Spectral quantities at each of the path's sampled wavelengths.
+#[derive(Clone)]
+pub struct SampledSpectrum([f64; SAMPLE_COUNT]);
+
+impl SampledSpectrum {
+    pub fn constant(value: f64) -> SampledSpectrum {
+        SampledSpectrum([value; SAMPLE_COUNT])
+    }
+
+    pub fn from_fn(wavelengths: &SampledWavelengths, f: impl Fn(f64) -> f64) -> SampledSpectrum {
+        let mut values = [0.0; SAMPLE_COUNT];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = f(wavelengths.lambda(i));
+        }
+        SampledSpectrum(values)
+    }
+
+    /// Upsamples an RGB reflectance or illuminant to a smooth spectrum with Smits' method.
+    pub fn from_rgb(rgb: &Vector, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+        Self::from_fn(wavelengths, |lambda| rgb_to_spectrum(rgb, lambda))
+    }
+}
+
+impl_op_ex!(
+    *|a: &SampledSpectrum, b: &SampledSpectrum| -> SampledSpectrum {
+        let mut values = a.0;
+        for (value, other) in values.iter_mut().zip(b.0.iter()) {
+            *value *= other;
+        }
+        SampledSpectrum(values)
+    }
+);
+
+/// Smits' basis spectra, sampled at ten bins evenly spaced over 380nm to 720nm.
+const SMITS_LAMBDA_MIN: f64 = 380.0;
+const SMITS_LAMBDA_MAX: f64 = 720.0;
+const SMITS_WHITE: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+fn rgb_to_spectrum(rgb: &Vector, lambda: f64) -> f64 {
+    let (r, g, b) = (rgb.x(), rgb.y(), rgb.z());
+    let basis = |table: &[f64; 10]| smits_basis(table, lambda);
+    if r <= g && r <= b {
+        let value = r * basis(&SMITS_WHITE);
+        if g <= b {
+            value + (g - r) * basis(&SMITS_CYAN) + (b - g) * basis(&SMITS_BLUE)
+        } else {
+            value + (b - r) * basis(&SMITS_CYAN) + (g - b) * basis(&SMITS_GREEN)
+        }
+    } else if g <= r && g <= b {
+        let value = g * basis(&SMITS_WHITE);
+        if r <= b {
+            value + (r - g) * basis(&SMITS_MAGENTA) + (b - r) * basis(&SMITS_BLUE)
+        } else {
+            value + (b - g) * basis(&SMITS_MAGENTA) + (r - b) * basis(&SMITS_RED)
+        }
+    } else {
+        let value = b * basis(&SMITS_WHITE);
+        if r <= g {
+            value + (r - b) * basis(&SMITS_YELLOW) + (g - r) * basis(&SMITS_GREEN)
+        } else {
+            value + (g - b) * basis(&SMITS_YELLOW) + (r - g) * basis(&SMITS_RED)
+        }
+    }
+}
+
+/// Linearly interpolates between the bin centers of one of Smits' basis spectra.
+fn smits_basis(table: &[f64; 10], lambda: f64) -> f64 {
+    let bin_width = (SMITS_LAMBDA_MAX - SMITS_LAMBDA_MIN) / table.len() as f64;
+    let position = ((lambda - SMITS_LAMBDA_MIN) / bin_width - 0.5).clamp(0.0, 9.0);
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(9);
+    let t = position - lower as f64;
+    (1.0 - t) * table[lower] + t * table[upper]
+}
+
+/// The CIE 1931 color matching functions, using the multi-lobe fit of Wyman, Sloan and Shirley.
+pub fn cie_xyz(lambda: f64) -> Vector {
+    let g = |mu: f64, sigma_low: f64, sigma_high: f64| {
+        let sigma = if lambda < mu { sigma_low } else { sigma_high };
+        (-0.5 * ((lambda - mu) / sigma).powi(2)).exp()
+    };
+    Vector::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+fn xyz_to_rgb(xyz: &Vector) -> Vector {
+    let (x, y, z) = (xyz.x(), xyz.y(), xyz.z());
+    Vector::new(
+        3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+        -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+        0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+    )
+}
+
+/// The RGB color of a constant unit spectrum, which is divided out so that white stays white.
+fn white_rgb() -> Vector {
+    xyz_to_rgb(&Vector::new(
+        CIE_X_INTEGRAL / CIE_Y_INTEGRAL,
+        1.0,
+        CIE_Z_INTEGRAL / CIE_Y_INTEGRAL,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Averages the RGB estimate of a spectrum over stratified hero wavelengths.
+    fn integrate(f: impl Fn(&SampledWavelengths) -> SampledSpectrum) -> Vector {
+        let samples = 1000;
+        let mut rgb = Vector::empty();
+        for i in 0..samples {
+            let wavelengths = SampledWavelengths::sample_uniform((i as f64 + 0.5) / samples as f64);
+            rgb = rgb + wavelengths.to_rgb(&f(&wavelengths));
+        }
+        rgb / samples as f64
+    }
+
+    fn assert_close(expected: &Vector, actual: &Vector, tolerance: f64) {
+        assert!(
+            (expected.x() - actual.x()).abs() < tolerance,
+            "r: {}",
+            actual.x()
+        );
+        assert!(
+            (expected.y() - actual.y()).abs() < tolerance,
+            "g: {}",
+            actual.y()
+        );
+        assert!(
+            (expected.z() - actual.z()).abs() < tolerance,
+            "b: {}",
+            actual.z()
+        );
+    }
+
+    #[test]
+    fn hero_wavelengths_are_evenly_spaced() {
+        let wavelengths = SampledWavelengths::sample_uniform(0.9);
+        let delta = (LAMBDA_MAX - LAMBDA_MIN) / SAMPLE_COUNT as f64;
+        for i in 0..SAMPLE_COUNT {
+            let lambda = wavelengths.lambda(i);
+            assert!((LAMBDA_MIN..=LAMBDA_MAX).contains(&lambda));
+            let offset = (lambda - wavelengths.hero()).rem_euclid(LAMBDA_MAX - LAMBDA_MIN);
+            assert!((offset - i as f64 * delta).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn terminating_secondary_wavelengths_keeps_only_the_hero() {
+        let mut wavelengths = SampledWavelengths::sample_uniform(0.3);
+        let pdf = wavelengths.pdf[0];
+        assert!(!wavelengths.secondary_terminated());
+        wavelengths.terminate_secondary();
+        assert!(wavelengths.secondary_terminated());
+        assert_eq!(pdf / SAMPLE_COUNT as f64, wavelengths.pdf[0]);
+    }
+
+    #[test]
+    fn terminated_paths_still_integrate_to_white() {
+        let samples = 4000;
+        let mut rgb = Vector::empty();
+        for i in 0..samples {
+            let mut wavelengths =
+                SampledWavelengths::sample_uniform((i as f64 + 0.5) / samples as f64);
+            wavelengths.terminate_secondary();
+            rgb = rgb + wavelengths.to_rgb(&SampledSpectrum::constant(1.0));
+        }
+        assert_close(&Vector::new(1.0, 1.0, 1.0), &(rgb / samples as f64), 1e-2);
+    }
+
+    #[test]
+    fn constant_spectrum_is_white() {
+        let rgb = integrate(|_| SampledSpectrum::constant(1.0));
+        assert_close(&Vector::new(1.0, 1.0, 1.0), &rgb, 1e-3);
+    }
+
+    #[test]
+    fn upsampled_colors_round_trip() {
+        for color in &[
+            Vector::new(0.5, 0.5, 0.5),
+            Vector::new(0.8, 0.2, 0.1),
+            Vector::new(0.1, 0.7, 0.2),
+            Vector::new(0.2, 0.3, 0.9),
+        ] {
+            let rgb = integrate(|wavelengths| SampledSpectrum::from_rgb(color, wavelengths));
+            assert_close(color, &rgb, 0.1);
+        }
+    }
+}
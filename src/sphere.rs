@@ -1,6 +1,10 @@
+use rand::Rng;
+
+use crate::aabb::Aabb;
 use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
+use crate::onb::Onb;
 use crate::ray::Ray;
 use crate::vector::Vector;
 use std::rc::Rc;
@@ -27,6 +31,47 @@ impl Sphere {
     pub fn radius(&self) -> f64 {
         self.radius
     }
+
+    /// The cosine of the half-angle of the cone the sphere subtends from `origin`, or `None` if
+    /// `origin` is inside the sphere.
+    fn cos_theta_max(&self, origin: &Vector) -> Option<f64> {
+        let distance_squared = (self.center() - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return None;
+        }
+        Some((1.0 - radius_squared / distance_squared).sqrt())
+    }
+
+    /// Maps a point on the unit sphere to texture coordinates, with `u` running around the y axis
+    /// starting from -x, and `v` running from the bottom pole to the top.
+    pub fn get_uv(p: &Vector) -> (f64, f64) {
+        let theta = (-p.y()).acos();
+        let phi = (-p.z()).atan2(p.x()) + std::f64::consts::PI;
+        (
+            phi / (2.0 * std::f64::consts::PI),
+            theta / std::f64::consts::PI,
+        )
+    }
+
+    /// The partial derivatives of the point `offset` from the center with respect to the
+    /// texture coordinates from `get_uv`.
+    pub fn get_partial_derivatives(offset: &Vector) -> (Vector, Vector) {
+        let pi = std::f64::consts::PI;
+        let dpdu = 2.0 * pi * Vector::new(offset.z(), 0.0, -offset.x());
+        let ring_radius = (offset.x() * offset.x() + offset.z() * offset.z()).sqrt();
+        if ring_radius < 1e-12 {
+            // At the poles `u` is degenerate.
+            return (dpdu, Vector::new(pi * offset.length(), 0.0, 0.0));
+        }
+        let dpdv = pi
+            * Vector::new(
+                -offset.x() * offset.y() / ring_radius,
+                ring_radius,
+                -offset.y() * offset.z() / ring_radius,
+            );
+        (dpdu, dpdv)
+    }
 }
 
 impl Hittable for Sphere {
@@ -43,6 +88,8 @@ impl Hittable for Sphere {
             if temp < t_max && temp > t_min {
                 let p = ray.at(temp);
                 let outward_normal = (&p - self.center()) / self.radius();
+                let (u, v) = Self::get_uv(&outward_normal);
+                let (dpdu, dpdv) = Self::get_partial_derivatives(&(&p - self.center()));
                 /*let mut hit_record = HitRecord::new(
                     &p,
                     &outward_normal,
@@ -51,18 +98,25 @@ impl Hittable for Sphere {
                 );*/
                 let mut hit_record = HitRecord {
                     t: temp,
-                    p: p,
-                    normal: Vector::new(0.0, 0.0, 0.0),
-                    front_face: false,
+                    u,
+                    v,
+                    p,
+                    dpdu,
+                    dpdv,
                     material: Some(self.material.clone()),
+                    ..HitRecord::empty()
                 };
                 hit_record.set_face_normal(ray, &outward_normal);
-                return (true, hit_record);
+                if hit_record.is_opaque() {
+                    return (true, hit_record);
+                }
             }
             let temp = (-half_b + root) / a;
             if temp < t_max && temp > t_min {
                 let p = ray.at(temp);
                 let outward_normal = (&p - self.center()) / self.radius();
+                let (u, v) = Self::get_uv(&outward_normal);
+                let (dpdu, dpdv) = Self::get_partial_derivatives(&(&p - self.center()));
                 /*let mut hit_record = HitRecord::new(
                     &p,
                     &outward_normal,
@@ -71,16 +125,139 @@ impl Hittable for Sphere {
                 );*/
                 let mut hit_record = HitRecord {
                     t: temp,
-                    p: p,
-                    normal: Vector::new(0.0, 0.0, 0.0),
-                    front_face: false,
+                    u,
+                    v,
+                    p,
+                    dpdu,
+                    dpdv,
                     material: Some(self.material.clone()),
+                    ..HitRecord::empty()
                 };
                 hit_record.set_face_normal(ray, &outward_normal);
-                return (true, hit_record);
+                if hit_record.is_opaque() {
+                    return (true, hit_record);
+                }
             }
         }
 
         (false, HitRecord::empty())
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let radius = self.radius.abs();
+        let extent = Vector::new(radius, radius, radius);
+        Some(Aabb::new(
+            &(self.center() - &extent),
+            &(self.center() + &extent),
+        ))
+    }
+
+    /// Directions are sampled uniformly within the cone that the sphere subtends from `origin`.
+    /// From inside the sphere there is no such cone, and it can't be sampled.
+    fn pdf_value(&self, origin: &Vector, direction: &Vector) -> Option<f64> {
+        let cos_theta_max = self.cos_theta_max(origin)?;
+        let ray = Ray::new(origin, direction);
+        if !self.hit(&ray, 0.001, f64::INFINITY).0 {
+            return Some(0.0);
+        }
+        Some(1.0 / (2.0 * std::f64::consts::PI * (1.0 - cos_theta_max)))
+    }
+
+    fn random(&self, origin: &Vector) -> Option<Vector> {
+        let cos_theta_max = self.cos_theta_max(origin)?;
+        let direction = self.center() - origin;
+        let mut rng = rand::thread_rng();
+        let (r1, r2) = (rng.gen::<f64>(), rng.gen::<f64>());
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * std::f64::consts::PI * r1;
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+        Some(Onb::from_w(&direction).local(phi.cos() * sin_theta, phi.sin() * sin_theta, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn uv_coordinates_follow_the_sphere() {
+        let cases = [
+            (Vector::new(1., 0., 0.), 0.5, 0.5),
+            (Vector::new(0., 1., 0.), 0.5, 1.0),
+            (Vector::new(0., 0., -1.), 0.75, 0.5),
+            (Vector::new(0., 0., 1.), 0.25, 0.5),
+            (Vector::new(0., -1., 0.), 0.5, 0.0),
+        ];
+        for (p, u, v) in cases.iter() {
+            let (actual_u, actual_v) = Sphere::get_uv(p);
+            assert!(approx_eq!(f64, *u, actual_u, epsilon = 1e-12));
+            assert!(approx_eq!(f64, *v, actual_v, epsilon = 1e-12));
+        }
+    }
+
+    // Averaging 1/pdf over sampled directions estimates the solid angle of the sphere's cone.
+    #[test]
+    fn pdf_integrates_to_the_subtended_solid_angle() {
+        let sphere = Sphere::new(
+            &Vector::new(0.0, 0.0, -2.0),
+            1.0,
+            Rc::new(crate::lambertian::Lambertian::new(&Vector::empty())),
+        );
+        let origin = Vector::empty();
+        let samples = 10_000;
+        let total: f64 = (0..samples)
+            .map(|_| {
+                let direction = sphere.random(&origin).unwrap();
+                1.0 / sphere.pdf_value(&origin, &direction).unwrap()
+            })
+            .sum();
+        let cos_theta_max = (0.75_f64).sqrt();
+        let expected = 2.0 * std::f64::consts::PI * (1.0 - cos_theta_max);
+        assert!(approx_eq!(
+            f64,
+            expected,
+            total / samples as f64,
+            epsilon = 1e-9
+        ));
+    }
+
+    #[test]
+    fn cannot_be_sampled_from_inside() {
+        let sphere = Sphere::new(
+            &Vector::new(0.0, 0.0, -2.0),
+            1.0,
+            Rc::new(crate::lambertian::Lambertian::new(&Vector::empty())),
+        );
+        let inside = Vector::new(0.0, 0.5, -2.0);
+        assert!(sphere.random(&inside).is_none());
+        assert!(sphere
+            .pdf_value(&inside, &Vector::new(0.0, 1.0, 0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn partial_derivatives_match_finite_differences() {
+        let pi = std::f64::consts::PI;
+        let radius = 2.0;
+        // Inverts get_uv for a sphere of the given radius.
+        let point = |u: f64, v: f64| {
+            let (phi, theta) = (2.0 * pi * u, pi * v);
+            radius
+                * Vector::new(
+                    -theta.sin() * phi.cos(),
+                    -theta.cos(),
+                    theta.sin() * phi.sin(),
+                )
+        };
+        let (u, v, h) = (0.3, 0.6, 1e-6);
+        let (dpdu, dpdv) = Sphere::get_partial_derivatives(&point(u, v));
+        let dpdu_numeric = (point(u + h, v) - point(u - h, v)) / (2.0 * h);
+        let dpdv_numeric = (point(u, v + h) - point(u, v - h)) / (2.0 * h);
+        assert!((&dpdu - dpdu_numeric).length() < 1e-6);
+        assert!((&dpdv - dpdv_numeric).length() < 1e-6);
+        // The derivatives are oriented so that their cross product faces outward.
+        assert!(Vector::dot(&Vector::cross(&dpdu, &dpdv), &point(u, v)) > 0.0);
+    }
 }
@@ -0,0 +1,272 @@
+use rand::Rng;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::dielectric::Dielectric;
+use crate::henyey_greenstein::HenyeyGreenstein;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// The most scattering events in one walk before the light is considered absorbed.
+const MAX_EVENTS: usize = 1024;
+
+/// A translucent solid such as skin, wax or marble, bounded by a closed `Hittable`. Light enters
+/// through a smooth dielectric surface, then takes a random walk through a scattering medium until
+/// it leaves through the same surface. The mean free path and single-scattering albedo are given
+/// for each of red, green and blue.
+pub struct Subsurface {
+    boundary: Box<dyn Hittable>,
+    surface: Rc<Dielectric>,
+    extinction: Vector,
+    albedo: Vector,
+    phase_function: Rc<dyn Material>,
+}
+
+impl Subsurface {
+    pub fn new(
+        boundary: Box<dyn Hittable>,
+        refraction_index: f64,
+        mean_free_path: &Vector,
+        albedo: &Vector,
+    ) -> Subsurface {
+        Subsurface {
+            boundary,
+            surface: Rc::new(Dielectric::new(refraction_index)),
+            extinction: Vector::new(
+                1.0 / mean_free_path.x(),
+                1.0 / mean_free_path.y(),
+                1.0 / mean_free_path.z(),
+            ),
+            albedo: albedo.clone(),
+            phase_function: Rc::new(HenyeyGreenstein::new(&Vector::new(1.0, 1.0, 1.0), 0.0)),
+        }
+    }
+
+    /// Makes the medium scatter light forwards for positive `g`, or backwards for negative `g`.
+    pub fn with_anisotropy(mut self, g: f64) -> Subsurface {
+        self.phase_function = Rc::new(HenyeyGreenstein::new(&Vector::new(1.0, 1.0, 1.0), g));
+        self
+    }
+
+    fn is_inside(&self, ray: &Ray) -> bool {
+        let interior = self.surface.interior();
+        ray.interiors()
+            .iter()
+            .any(|inside| Rc::ptr_eq(inside, interior))
+    }
+
+    fn transmittance(&self, distance: f64) -> Vector {
+        Vector::new(
+            (-self.extinction.x() * distance).exp(),
+            (-self.extinction.y() * distance).exp(),
+            (-self.extinction.z() * distance).exp(),
+        )
+    }
+
+    /// Follows a ray starting inside the medium until it leaves. Returns the hit where it crossed
+    /// the surface, the ray it left along and its weight, and the distance along `ray` to the
+    /// first event. Returns `None` if the light is absorbed.
+    fn walk(&self, ray: &Ray) -> Option<(HitRecord, Ray, Vector, f64)> {
+        let mut rng = rand::thread_rng();
+        // Free flights are sampled with the extinction of one color for the whole walk. Each
+        // color's path throughput is then weighted against the density of the path under every
+        // color, which keeps colors with very different free paths from adding noise.
+        let sigma = channel(&self.extinction, rng.gen_range(0, 3));
+        let mut throughput = Vector::new(1.0, 1.0, 1.0);
+        let mut pdf = Vector::new(1.0, 1.0, 1.0);
+        let mut current = ray.clone();
+        let mut first_t = None;
+        for _ in 0..MAX_EVENTS {
+            let (hit, boundary) = self.boundary.hit(&current, 0.001, f64::INFINITY);
+            if !hit {
+                return None;
+            }
+            let ray_length = current.direction().length();
+            let distance = -(1.0 - rng.gen::<f64>()).ln() / sigma;
+            let t = distance / ray_length;
+
+            if t < boundary.t {
+                first_t.get_or_insert(t);
+                let transmittance = self.transmittance(distance);
+                let density = &self.extinction * &transmittance;
+                throughput = throughput * &self.albedo * &density;
+                pdf = pdf * &density;
+                let event = HitRecord {
+                    t,
+                    p: current.at(t),
+                    front_face: true,
+                    ..HitRecord::empty()
+                };
+                current = self.phase_function.scatter(&current, &event).2;
+            } else {
+                let first_t = *first_t.get_or_insert(boundary.t);
+                let transmittance = self.transmittance(boundary.t * ray_length);
+                throughput = throughput * &transmittance;
+                pdf = pdf * &transmittance;
+                let (scatter, attenuation, scattered) = self.surface.scatter(&current, &boundary);
+                if !scatter {
+                    return None;
+                }
+                throughput = throughput * attenuation;
+                if !self.is_inside(&scattered) {
+                    let weight = throughput / mean(&pdf);
+                    return Some((boundary, scattered, weight, first_t));
+                }
+                current = scattered;
+            }
+            // Only the ratio matters, so rescale both to keep them from underflowing.
+            let scale = mean(&pdf);
+            if scale <= 0.0 {
+                return None;
+            }
+            throughput = throughput / scale;
+            pdf = pdf / scale;
+        }
+        None
+    }
+}
+
+fn channel(v: &Vector, i: usize) -> f64 {
+    match i {
+        0 => v.x(),
+        1 => v.y(),
+        _ => v.z(),
+    }
+}
+
+fn mean(v: &Vector) -> f64 {
+    (v.x() + v.y() + v.z()) / 3.0
+}
+
+/// Hands back the outcome of a walk that has already been taken.
+struct Escaped {
+    weight: Vector,
+    ray: Ray,
+}
+
+impl Material for Escaped {
+    fn scatter(&self, _ray_in: &Ray, _hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        (true, self.weight.clone(), self.ray.clone())
+    }
+}
+
+/// Absorbs everything, for walks that never find their way out.
+struct Absorbed;
+
+impl Material for Absorbed {
+    fn scatter(&self, ray_in: &Ray, _hit_record: &HitRecord) -> (bool, Vector, Ray) {
+        (false, Vector::empty(), ray_in.clone())
+    }
+}
+
+impl Hittable for Subsurface {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        if !self.is_inside(ray) {
+            let (hit, mut hit_record) = self.boundary.hit(ray, t_min, t_max);
+            if hit {
+                hit_record.material = Some(self.surface.clone());
+            }
+            return (hit, hit_record);
+        }
+
+        // The walk is taken all at once, so that long walks aren't cut short by the renderer's
+        // bounce limit. The hit reports the exit point, at the distance of the walk's first event.
+        match self.walk(ray) {
+            Some((exit, scattered, weight, t)) if t >= t_min && t < t_max => {
+                let hit_record = HitRecord {
+                    t,
+                    material: Some(Rc::new(Escaped {
+                        weight,
+                        ray: scattered,
+                    })),
+                    ..exit
+                };
+                (true, hit_record)
+            }
+            Some(_) => (false, HitRecord::empty()),
+            None => {
+                let hit_record = HitRecord {
+                    t: t_min,
+                    p: ray.at(t_min),
+                    material: Some(Rc::new(Absorbed)),
+                    ..HitRecord::empty()
+                };
+                (true, hit_record)
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::sphere::Sphere;
+
+    fn ball(mean_free_path: &Vector, albedo: &Vector) -> Subsurface {
+        let sphere = Sphere::new(
+            &Vector::empty(),
+            1.0,
+            Rc::new(Lambertian::new(&Vector::empty())),
+        );
+        Subsurface::new(Box::new(sphere), 1.33, mean_free_path, albedo)
+    }
+
+    // Shoots rays at the ball and returns the mean weight of the light leaving it.
+    fn albedo(subsurface: &Subsurface) -> Vector {
+        let samples = 4_000;
+        let mut total = Vector::empty();
+        for i in 0..samples {
+            let x = (i as f64 + 0.5) / samples as f64 - 0.5;
+            let mut ray = Ray::new(&Vector::new(x, 0.0, 5.0), &Vector::new(0.0, 0.0, -1.0));
+            let mut weight = Vector::new(1.0, 1.0, 1.0);
+            loop {
+                let (hit, hit_record) = subsurface.hit(&ray, 0.001, f64::INFINITY);
+                if !hit {
+                    total = total + weight;
+                    break;
+                }
+                let material = hit_record.material.clone().unwrap();
+                let (scatter, attenuation, scattered) = material.scatter(&ray, &hit_record);
+                if !scatter {
+                    break;
+                }
+                weight = weight * attenuation;
+                ray = scattered;
+            }
+        }
+        total / samples as f64
+    }
+
+    #[test]
+    fn walks_without_absorption_conserve_energy() {
+        let subsurface = ball(&Vector::new(0.5, 0.2, 0.1), &Vector::new(1.0, 1.0, 1.0));
+        let albedo = albedo(&subsurface);
+        for &value in &[albedo.x(), albedo.y(), albedo.z()] {
+            assert!(
+                (value - 1.0).abs() < 0.05,
+                "{} {} {}",
+                albedo.x(),
+                albedo.y(),
+                albedo.z()
+            );
+        }
+    }
+
+    #[test]
+    fn absorption_darkens_colors_with_short_free_paths_most() {
+        let subsurface = ball(&Vector::new(0.5, 0.5, 0.05), &Vector::new(0.9, 0.9, 0.9));
+        let albedo = albedo(&subsurface);
+        assert!(albedo.x() < 0.95, "{}", albedo.x());
+        // Light with a short free path scatters many more times before it finds its way out,
+        // losing some of itself each time.
+        assert!(albedo.z() < albedo.x(), "{} {}", albedo.z(), albedo.x());
+    }
+}
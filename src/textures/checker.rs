@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Alternates between two textures on a grid of unit squares in texture space.
+pub struct Checker {
+    even: Rc<dyn Texture>,
+    odd: Rc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(even: Rc<dyn Texture>, odd: Rc<dyn Texture>) -> Checker {
+        Checker { even, odd }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, u: f64, v: f64, p: &Vector) -> Vector {
+        let parity = (u.floor() + v.floor()) as i64;
+        if parity % 2 == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::textures::solid_color::SolidColor;
+
+    #[test]
+    fn alternates_between_squares() {
+        let checker = Checker::new(
+            Rc::new(SolidColor::new(&Vector::new(1., 1., 1.))),
+            Rc::new(SolidColor::new(&Vector::new(0., 0., 0.))),
+        );
+        let p = Vector::empty();
+        assert_eq!(1., checker.value(0.5, 0.5, &p).x());
+        assert_eq!(0., checker.value(1.5, 0.5, &p).x());
+        assert_eq!(0., checker.value(0.5, -0.5, &p).x());
+        assert_eq!(1., checker.value(-0.5, -0.5, &p).x());
+    }
+}
@@ -0,0 +1,62 @@
+use crate::vector::Vector;
+
+/// Maps a scalar onto a color by linearly interpolating between sorted color stops.
+pub struct ColorRamp {
+    stops: Vec<(f64, Vector)>,
+}
+
+impl ColorRamp {
+    pub fn new(stops: Vec<(f64, Vector)>) -> ColorRamp {
+        assert!(!stops.is_empty());
+        let mut stops = stops;
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        ColorRamp { stops }
+    }
+
+    pub fn between(low: &Vector, high: &Vector) -> ColorRamp {
+        Self::new(vec![(0.0, low.clone()), (1.0, high.clone())])
+    }
+
+    pub fn at(&self, t: f64) -> Vector {
+        let first = &self.stops[0];
+        if t <= first.0 {
+            return first.1.clone();
+        }
+        for pair in self.stops.windows(2) {
+            let (t0, c0) = &pair[0];
+            let (t1, c1) = &pair[1];
+            if t <= *t1 {
+                let s = (t - t0) / (t1 - t0);
+                return (1.0 - s) * c0 + s * c1;
+            }
+        }
+        self.stops[self.stops.len() - 1].1.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn interpolates_between_stops() {
+        let ramp = ColorRamp::new(vec![
+            (1.0, Vector::new(0., 0., 1.)),
+            (0.0, Vector::new(1., 0., 0.)),
+            (0.5, Vector::new(0., 1., 0.)),
+        ]);
+        assert_eq!(1., ramp.at(0.0).x());
+        assert_eq!(1., ramp.at(0.5).y());
+        assert!(approx_eq!(f64, 0.5, ramp.at(0.75).y()));
+        assert!(approx_eq!(f64, 0.5, ramp.at(0.75).z()));
+    }
+
+    #[test]
+    fn clamps_outside_stops() {
+        let ramp = ColorRamp::between(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.));
+        assert_eq!(0., ramp.at(-2.0).x());
+        assert_eq!(1., ramp.at(3.0).x());
+    }
+}
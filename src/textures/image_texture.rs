@@ -0,0 +1,129 @@
+use std::io::Error;
+use std::path::Path;
+
+use crate::io::read_ppm;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// How texture coordinates outside of `[0, 1]` are mapped back onto the image.
+#[derive(Clone, Copy)]
+pub enum WrapMode {
+    Repeat,
+    // Every image in the scenes so far wraps around a sphere or tiles, so only the tests clamp.
+    #[allow(dead_code)]
+    Clamp,
+}
+
+/// A bilinearly filtered image. The first row of pixels is the top of the image, at `v = 1`.
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector>,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    pub fn new(width: usize, height: usize, pixels: Vec<Vector>, wrap: WrapMode) -> ImageTexture {
+        assert!(
+            width > 0 && height > 0,
+            "an image texture needs at least one pixel"
+        );
+        assert_eq!(width * height, pixels.len());
+        ImageTexture {
+            width,
+            height,
+            pixels,
+            wrap,
+        }
+    }
+
+    /// Loads a PPM image, undoing the gamma encoding applied when images are written.
+    pub fn load(path: &Path, wrap: WrapMode) -> Result<ImageTexture, Error> {
+        let (width, height, pixels) = read_ppm(path)?;
+        let pixels = pixels.iter().map(|pixel| pixel * pixel).collect();
+        Ok(Self::new(width, height, pixels, wrap))
+    }
+
+    fn texel(&self, x: i64, y: i64) -> &Vector {
+        let x = Self::wrap_index(x, self.width, self.wrap);
+        let y = Self::wrap_index(y, self.height, self.wrap);
+        &self.pixels[y * self.width + x]
+    }
+
+    fn wrap_index(index: i64, size: usize, wrap: WrapMode) -> usize {
+        match wrap {
+            WrapMode::Repeat => index.rem_euclid(size as i64) as usize,
+            WrapMode::Clamp => index.clamp(0, size as i64 - 1) as usize,
+        }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Vector) -> Vector {
+        let (u, v) = match self.wrap {
+            WrapMode::Repeat => (u, v),
+            WrapMode::Clamp => (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0)),
+        };
+        // Texel centers sit at half-integer coordinates.
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = (1.0 - fx) * self.texel(x0, y0) + fx * self.texel(x0 + 1, y0);
+        let bottom = (1.0 - fx) * self.texel(x0, y0 + 1) + fx * self.texel(x0 + 1, y0 + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    fn make_texture(wrap: WrapMode) -> ImageTexture {
+        ImageTexture::new(
+            2,
+            1,
+            vec![Vector::new(0., 0., 0.), Vector::new(1., 1., 1.)],
+            wrap,
+        )
+    }
+
+    #[test]
+    fn samples_texel_centers_exactly() {
+        let texture = make_texture(WrapMode::Clamp);
+        let p = Vector::empty();
+        assert_eq!(0., texture.value(0.25, 0.5, &p).x());
+        assert_eq!(1., texture.value(0.75, 0.5, &p).x());
+    }
+
+    #[test]
+    fn interpolates_between_texels() {
+        let texture = make_texture(WrapMode::Clamp);
+        assert!(approx_eq!(
+            f64,
+            0.5,
+            texture.value(0.5, 0.5, &Vector::empty()).x()
+        ));
+    }
+
+    #[test]
+    fn clamp_mode_extends_edge_texels() {
+        let texture = make_texture(WrapMode::Clamp);
+        let p = Vector::empty();
+        assert_eq!(0., texture.value(-3.0, 0.5, &p).x());
+        assert_eq!(1., texture.value(7.0, 0.5, &p).x());
+    }
+
+    #[test]
+    fn repeat_mode_tiles_the_image() {
+        let texture = make_texture(WrapMode::Repeat);
+        let p = Vector::empty();
+        assert_eq!(1., texture.value(1.75, 0.5, &p).x());
+        assert_eq!(0., texture.value(-0.75, 0.5, &p).x());
+        // Between the last texel and the first texel of the next tile.
+        assert!(approx_eq!(f64, 0.5, texture.value(1.0, 0.5, &p).x()));
+    }
+}
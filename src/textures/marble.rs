@@ -0,0 +1,76 @@
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::perlin::Perlin;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Veins across the z axis, distorted by turbulence. `frequency` is how fast the veins cycle
+/// along z, in radians per unit, and only applies along z; the turbulence is sampled at the
+/// point as it is.
+pub struct Marble {
+    noise: Perlin,
+    frequency: f64,
+    octaves: u32,
+    distortion: f64,
+    ramp: ColorRamp,
+}
+
+impl Marble {
+    pub fn new(
+        seed: u64,
+        frequency: f64,
+        octaves: u32,
+        distortion: f64,
+        ramp: ColorRamp,
+    ) -> Marble {
+        Marble {
+            noise: Perlin::new(seed),
+            frequency,
+            octaves,
+            distortion,
+            ramp,
+        }
+    }
+}
+
+impl Texture for Marble {
+    fn value(&self, _u: f64, _v: f64, p: &Vector) -> Vector {
+        let turbulence = self.noise.turbulence(p, self.octaves);
+        let phase = self.frequency * p.z() + self.distortion * turbulence;
+        self.ramp.at(0.5 * (1.0 + phase.sin()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    fn marble(seed: u64) -> Marble {
+        let ramp = ColorRamp::between(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.));
+        Marble::new(seed, 2.0, 5, 4.0, ramp)
+    }
+
+    // Perlin noise vanishes on the integer lattice, leaving only the veins.
+    #[test]
+    fn veins_cycle_along_z_only() {
+        let marble = marble(3);
+        for z in -3..3 {
+            let expected = 0.5 * (1.0 + (2.0 * z as f64).sin());
+            for &(x, y) in &[(0, 0), (4, -1), (-2, 7)] {
+                let p = Vector::new(x as f64, y as f64, z as f64);
+                assert!(approx_eq!(f64, expected, marble.value(0., 0., &p).x()));
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_marble() {
+        let p = Vector::new(0.3, 1.7, -0.6);
+        assert_eq!(
+            marble(5).value(0., 0., &p).x(),
+            marble(5).value(0., 0., &p).x()
+        );
+        assert!(marble(5).value(0., 0., &p).x() != marble(6).value(0., 0., &p).x());
+    }
+}
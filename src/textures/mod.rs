@@ -0,0 +1,10 @@
+pub mod checker;
+pub mod color_ramp;
+pub mod image_texture;
+pub mod marble;
+pub mod perlin;
+pub mod solid_color;
+pub mod texture;
+pub mod turbulence;
+pub mod uv_transform;
+pub mod wood;
@@ -0,0 +1,132 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::vector::Vector;
+
+const POINT_COUNT: usize = 256;
+
+/// Gradient noise on an integer lattice, generated from a seed so scenes are reproducible.
+pub struct Perlin {
+    gradients: Vec<Vector>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Perlin {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vector::random_range(&mut rng, -1.0, 1.0).unit_vector())
+            .collect();
+        Perlin {
+            gradients,
+            perm_x: Self::generate_perm(&mut rng),
+            perm_y: Self::generate_perm(&mut rng),
+            perm_z: Self::generate_perm(&mut rng),
+        }
+    }
+
+    /// Noise in `[-1, 1]`, which is zero at every lattice point.
+    pub fn noise(&self, p: &Vector) -> f64 {
+        let (i, u) = Self::split(p.x());
+        let (j, v) = Self::split(p.y());
+        let (k, w) = Self::split(p.z());
+
+        // Hermite smoothing hides the lattice when interpolating.
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+
+        let mut accum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient = &self.gradients[self.perm_x[((i + di) & 255) as usize]
+                        ^ self.perm_y[((j + dj) & 255) as usize]
+                        ^ self.perm_z[((k + dk) & 255) as usize]];
+                    let (fi, fj, fk) = (di as f64, dj as f64, dk as f64);
+                    let weight = Vector::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * Vector::dot(gradient, &weight);
+                }
+            }
+        }
+        accum
+    }
+
+    /// Sums octaves of noise, each with double the frequency and half the amplitude of the last.
+    pub fn turbulence(&self, p: &Vector, octaves: u32) -> f64 {
+        let mut accum = 0.0;
+        let mut p = p.clone();
+        let mut weight = 1.0;
+        for _ in 0..octaves {
+            accum += weight * self.noise(&p);
+            weight *= 0.5;
+            p = p * 2.0;
+        }
+        accum.abs()
+    }
+
+    fn split(coordinate: f64) -> (i64, f64) {
+        let floor = coordinate.floor();
+        (floor as i64, coordinate - floor)
+    }
+
+    fn generate_perm(rng: &mut StdRng) -> Vec<usize> {
+        let mut perm: Vec<usize> = (0..POINT_COUNT).collect();
+        perm.shuffle(rng);
+        perm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_noise() {
+        let a = Perlin::new(7);
+        let b = Perlin::new(7);
+        let p = Vector::new(1.3, -2.7, 0.4);
+        assert_eq!(a.noise(&p), b.noise(&p));
+    }
+
+    #[test]
+    fn different_seeds_give_different_noise() {
+        let a = Perlin::new(1);
+        let b = Perlin::new(2);
+        let p = Vector::new(1.3, -2.7, 0.4);
+        assert!(a.noise(&p) != b.noise(&p));
+    }
+
+    #[test]
+    fn noise_vanishes_on_the_lattice() {
+        let perlin = Perlin::new(3);
+        assert_eq!(0.0, perlin.noise(&Vector::new(4.0, -2.0, 9.0)));
+    }
+
+    #[test]
+    fn noise_is_bounded_and_continuous() {
+        let perlin = Perlin::new(5);
+        let mut previous = perlin.noise(&Vector::new(0.0, 0.5, 0.25));
+        for i in 1..2000 {
+            let value = perlin.noise(&Vector::new(i as f64 * 0.005, 0.5, 0.25));
+            assert!((-1.0..=1.0).contains(&value));
+            assert!((value - previous).abs() < 0.05);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn turbulence_is_non_negative() {
+        let perlin = Perlin::new(5);
+        for i in 0..100 {
+            let p = Vector::new(i as f64 * 0.37, i as f64 * 0.11, -(i as f64) * 0.23);
+            assert!(perlin.turbulence(&p, 7) >= 0.0);
+        }
+    }
+}
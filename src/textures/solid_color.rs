@@ -0,0 +1,25 @@
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+pub struct SolidColor {
+    color: Vector,
+}
+
+impl SolidColor {
+    pub fn new(color: &Vector) -> SolidColor {
+        SolidColor {
+            color: color.clone(),
+        }
+    }
+
+    /// A texture holding the same value in every channel, for scalar parameters.
+    pub fn uniform(value: f64) -> SolidColor {
+        Self::new(&Vector::new(value, value, value))
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: &Vector) -> Vector {
+        self.color.clone()
+    }
+}
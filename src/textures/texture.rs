@@ -0,0 +1,5 @@
+use crate::vector::Vector;
+
+pub trait Texture {
+    fn value(&self, u: f64, v: f64, p: &Vector) -> Vector;
+}
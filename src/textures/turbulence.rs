@@ -0,0 +1,69 @@
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::perlin::Perlin;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Raw multi-octave turbulence, mapped through a color ramp.
+pub struct Turbulence {
+    noise: Perlin,
+    frequency: f64,
+    octaves: u32,
+    ramp: ColorRamp,
+}
+
+impl Turbulence {
+    pub fn new(seed: u64, frequency: f64, octaves: u32, ramp: ColorRamp) -> Turbulence {
+        Turbulence {
+            noise: Perlin::new(seed),
+            frequency,
+            octaves,
+            ramp,
+        }
+    }
+}
+
+impl Texture for Turbulence {
+    fn value(&self, _u: f64, _v: f64, p: &Vector) -> Vector {
+        self.ramp
+            .at(self.noise.turbulence(&(self.frequency * p), self.octaves))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    fn ramp() -> ColorRamp {
+        ColorRamp::between(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.))
+    }
+
+    #[test]
+    fn frequency_scales_the_point() {
+        let texture = Turbulence::new(4, 2.5, 6, ramp());
+        let perlin = Perlin::new(4);
+        for i in 0..20 {
+            let p = Vector::new(i as f64 * 0.13, 0.4 - i as f64 * 0.07, i as f64 * 0.05);
+            let expected = perlin.turbulence(&(2.5 * &p), 6).min(1.0);
+            assert!(approx_eq!(f64, expected, texture.value(0., 0., &p).x()));
+        }
+    }
+
+    #[test]
+    fn lattice_points_take_the_start_of_the_ramp() {
+        let texture = Turbulence::new(4, 1.0, 6, ramp());
+        assert_eq!(0.0, texture.value(0., 0., &Vector::new(2.0, -1.0, 3.0)).x());
+    }
+
+    #[test]
+    fn same_seed_gives_same_turbulence() {
+        let p = Vector::new(0.3, 1.7, -0.6);
+        let texture = |seed| Turbulence::new(seed, 1.5, 5, ramp());
+        assert_eq!(
+            texture(5).value(0., 0., &p).x(),
+            texture(5).value(0., 0., &p).x()
+        );
+        assert!(texture(5).value(0., 0., &p).x() != texture(6).value(0., 0., &p).x());
+    }
+}
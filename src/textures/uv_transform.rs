@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Scales and then offsets the texture coordinates used to look up another texture.
+pub struct UvTransform {
+    texture: Rc<dyn Texture>,
+    scale: (f64, f64),
+    offset: (f64, f64),
+}
+
+impl UvTransform {
+    pub fn new(texture: Rc<dyn Texture>, scale: (f64, f64), offset: (f64, f64)) -> UvTransform {
+        UvTransform {
+            texture,
+            scale,
+            offset,
+        }
+    }
+}
+
+impl Texture for UvTransform {
+    fn value(&self, u: f64, v: f64, p: &Vector) -> Vector {
+        self.texture.value(
+            u * self.scale.0 + self.offset.0,
+            v * self.scale.1 + self.offset.1,
+            p,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shows the texture coordinates it is looked up with as a color.
+    struct Coordinates;
+
+    impl Texture for Coordinates {
+        fn value(&self, u: f64, v: f64, _p: &Vector) -> Vector {
+            Vector::new(u, v, 0.)
+        }
+    }
+
+    #[test]
+    fn scales_then_offsets_coordinates() {
+        let texture = UvTransform::new(Rc::new(Coordinates), (4., 2.), (0.5, -1.));
+        let value = texture.value(0.25, 0.75, &Vector::empty());
+        assert_eq!(1.5, value.x());
+        assert_eq!(0.5, value.y());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::textures::color_ramp::ColorRamp;
+use crate::textures::perlin::Perlin;
+use crate::textures::texture::Texture;
+use crate::vector::Vector;
+
+/// Growth rings around the y axis, wobbled by turbulence. `ring_frequency` is how many rings
+/// there are per unit of distance from the axis.
+pub struct Wood {
+    noise: Perlin,
+    ring_frequency: f64,
+    octaves: u32,
+    distortion: f64,
+    ramp: ColorRamp,
+}
+
+impl Wood {
+    pub fn new(
+        seed: u64,
+        ring_frequency: f64,
+        octaves: u32,
+        distortion: f64,
+        ramp: ColorRamp,
+    ) -> Wood {
+        Wood {
+            noise: Perlin::new(seed),
+            ring_frequency,
+            octaves,
+            distortion,
+            ramp,
+        }
+    }
+}
+
+impl Texture for Wood {
+    fn value(&self, _u: f64, _v: f64, p: &Vector) -> Vector {
+        let radius = (p.x() * p.x() + p.z() * p.z()).sqrt();
+        let turbulence = self.noise.turbulence(p, self.octaves);
+        let rings = self.ring_frequency * radius + self.distortion * turbulence;
+        self.ramp.at(rings - rings.floor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+
+    fn wood(seed: u64) -> Wood {
+        let ramp = ColorRamp::between(&Vector::new(0., 0., 0.), &Vector::new(1., 1., 1.));
+        Wood::new(seed, 0.3, 4, 1.5, ramp)
+    }
+
+    // On the integer lattice the noise vanishes, so the color only depends on the distance
+    // from the axis.
+    #[test]
+    fn rings_follow_the_distance_from_the_axis() {
+        let wood = wood(2);
+        for &y in &[-2.0, 0.0, 5.0] {
+            let at = |x: f64, z: f64| wood.value(0., 0., &Vector::new(x, y, z)).x();
+            assert!(approx_eq!(f64, 0.5, at(3.0, 4.0), epsilon = 1e-12));
+            assert!(approx_eq!(f64, 0.5, at(0.0, -5.0), epsilon = 1e-12));
+            assert!(approx_eq!(f64, 0.3, at(1.0, 0.0), epsilon = 1e-12));
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_wood() {
+        let p = Vector::new(0.3, 1.7, -0.6);
+        assert_eq!(wood(5).value(0., 0., &p).x(), wood(5).value(0., 0., &p).x());
+        assert!(wood(5).value(0., 0., &p).x() != wood(6).value(0., 0., &p).x());
+    }
+}
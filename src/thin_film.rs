@@ -0,0 +1,247 @@
+use std::f64::consts::PI;
+
+use crate::vector::Vector;
+
+/// The wavelengths, in nanometers, at which red, green and blue are evaluated when rendering in
+/// RGB. Interference fringes are only approximated this way; thick films alias.
+pub const RGB_WAVELENGTHS: [f64; 3] = [630.0, 532.0, 465.0];
+
+/// A thin transparent film on a surface, such as soap or oil, whose reflections interfere to
+/// produce iridescent colors.
+pub struct ThinFilm {
+    /// The thickness of the film, in nanometers.
+    thickness: f64,
+    refraction_index: f64,
+}
+
+impl ThinFilm {
+    pub fn new(thickness: f64, refraction_index: f64) -> ThinFilm {
+        ThinFilm {
+            thickness,
+            refraction_index,
+        }
+    }
+
+    /// The reflectance of the film at `wavelength`, for light arriving at an angle with cosine
+    /// `cos_theta_i` to the normal from a medium with index `eta_i`, over a substrate with
+    /// complex index `eta + ik`. Sums the light bouncing inside the film following Airy, and
+    /// averages both polarizations.
+    pub fn reflectance(
+        &self,
+        cos_theta_i: f64,
+        eta_i: f64,
+        eta: f64,
+        k: f64,
+        wavelength: f64,
+    ) -> f64 {
+        let cos0 = Complex::real(cos_theta_i.clamp(0.0, 1.0));
+        let sin2 = Complex::real(1.0 - cos_theta_i * cos_theta_i);
+        let n0 = Complex::real(eta_i);
+        let n1 = Complex::real(self.refraction_index);
+        let n2 = Complex::new(eta, k);
+        // Snell's law gives the cosines inside the film and the substrate, which turn complex
+        // beyond the critical angle or in absorbing media.
+        let cosine_in = |n: &Complex| {
+            let ratio = n0.div(n);
+            Complex::real(1.0).sub(&ratio.mul(&ratio).mul(&sin2)).sqrt()
+        };
+        let cos1 = cosine_in(&n1);
+        let cos2 = cosine_in(&n2);
+
+        let phase = Complex::real(4.0 * PI * self.thickness / wavelength).mul(&n1.mul(&cos1));
+        // e^(i phase)
+        let shift = Complex::new(-phase.im, phase.re).exp();
+        let airy = |r01: Complex, r12: Complex| {
+            let r12 = r12.mul(&shift);
+            let r = r01.add(&r12).div(&Complex::real(1.0).add(&r01.mul(&r12)));
+            r.norm_squared()
+        };
+        let s = airy(
+            amplitude(&n0, &cos0, &n1, &cos1),
+            amplitude(&n1, &cos1, &n2, &cos2),
+        );
+        let p = airy(
+            amplitude(&n1, &cos0, &n0, &cos1),
+            amplitude(&n2, &cos1, &n1, &cos2),
+        );
+        (0.5 * (s + p)).clamp(0.0, 1.0)
+    }
+
+    /// The reflectance for red, green and blue, over a substrate whose complex index is given for
+    /// each color.
+    pub fn reflectance_rgb(
+        &self,
+        cos_theta_i: f64,
+        eta_i: f64,
+        eta: &Vector,
+        k: &Vector,
+    ) -> Vector {
+        let channel = |i: usize, eta: f64, k: f64| {
+            self.reflectance(cos_theta_i, eta_i, eta, k, RGB_WAVELENGTHS[i])
+        };
+        Vector::new(
+            channel(0, eta.x(), k.x()),
+            channel(1, eta.y(), k.y()),
+            channel(2, eta.z(), k.z()),
+        )
+    }
+}
+
+/// Interpolates a quantity given for red, green and blue to any wavelength, holding it constant
+/// beyond the ends of the RGB wavelengths.
+pub fn rgb_at(rgb: &Vector, wavelength: f64) -> f64 {
+    let [red, green, blue] = RGB_WAVELENGTHS;
+    if wavelength >= red {
+        rgb.x()
+    } else if wavelength >= green {
+        let t = (wavelength - green) / (red - green);
+        rgb.y() + t * (rgb.x() - rgb.y())
+    } else if wavelength >= blue {
+        let t = (wavelength - blue) / (green - blue);
+        rgb.z() + t * (rgb.y() - rgb.z())
+    } else {
+        rgb.z()
+    }
+}
+
+/// The Fresnel amplitude coefficient `(a cosᵢ - b cosₜ) / (a cosᵢ + b cosₜ)`. Passing the indices
+/// of incidence and transmission as `a` and `b` gives the s-polarized coefficient; swapping them
+/// gives the p-polarized one.
+fn amplitude(a: &Complex, cos_i: &Complex, b: &Complex, cos_t: &Complex) -> Complex {
+    let incident = a.mul(cos_i);
+    let transmitted = b.mul(cos_t);
+    incident.sub(&transmitted).div(&incident.add(&transmitted))
+}
+
+#[derive(Clone, Copy)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    fn real(re: f64) -> Complex {
+        Complex { re, im: 0.0 }
+    }
+
+    fn add(&self, other: &Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn sub(&self, other: &Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    fn mul(&self, other: &Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    fn div(&self, other: &Complex) -> Complex {
+        let denominator = other.norm_squared();
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+
+    fn norm_squared(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The principal square root.
+    fn sqrt(&self) -> Complex {
+        let norm = self.norm_squared().sqrt();
+        let re = (0.5 * (norm + self.re)).max(0.0).sqrt();
+        let im = (0.5 * (norm - self.re)).max(0.0).sqrt();
+        Complex::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    fn exp(&self) -> Complex {
+        let magnitude = self.re.exp();
+        Complex::new(magnitude * self.im.cos(), magnitude * self.im.sin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::fresnel;
+
+    #[test]
+    fn vanishing_film_leaves_dielectric_fresnel() {
+        let film = ThinFilm::new(0.0, 1.33);
+        for &cos in &[1.0, 0.6, 0.2] {
+            assert!(approx_eq!(
+                f64,
+                fresnel::dielectric(cos, 1.5),
+                film.reflectance(cos, 1.0, 1.5, 0.0, 550.0),
+                epsilon = 1e-9
+            ));
+        }
+    }
+
+    #[test]
+    fn vanishing_film_leaves_conductor_fresnel() {
+        let film = ThinFilm::new(0.0, 1.5);
+        for &cos in &[1.0, 0.6, 0.2] {
+            assert!(approx_eq!(
+                f64,
+                fresnel::conductor(cos, 0.2, 3.9),
+                film.reflectance(cos, 1.0, 0.2, 3.9, 550.0),
+                epsilon = 1e-9
+            ));
+        }
+    }
+
+    // A film of the same index as the air around it is no film at all, however thick.
+    #[test]
+    fn film_matching_incident_medium_is_invisible() {
+        let film = ThinFilm::new(350.0, 1.0);
+        assert!(approx_eq!(
+            f64,
+            fresnel::dielectric(0.7, 1.5),
+            film.reflectance(0.7, 1.0, 1.5, 0.0, 480.0),
+            epsilon = 1e-9
+        ));
+    }
+
+    // A quarter-wave layer with the geometric mean index cancels its own reflection.
+    #[test]
+    fn quarter_wave_coating_is_antireflective() {
+        let substrate: f64 = 1.5;
+        let index = substrate.sqrt();
+        let wavelength = 550.0;
+        let film = ThinFilm::new(wavelength / (4.0 * index), index);
+        assert!(film.reflectance(1.0, 1.0, substrate, 0.0, wavelength) < 1e-9);
+        // Other wavelengths are only partially cancelled, which is what gives films their colors.
+        let blue = film.reflectance(1.0, 1.0, substrate, 0.0, 400.0);
+        assert!(blue > 1e-3 && blue < fresnel::dielectric(1.0, substrate));
+    }
+
+    #[test]
+    fn rgb_quantities_interpolate_between_channel_wavelengths() {
+        let rgb = Vector::new(3.0, 2.0, 1.0);
+        assert_eq!(3.0, rgb_at(&rgb, 700.0));
+        assert_eq!(2.0, rgb_at(&rgb, RGB_WAVELENGTHS[1]));
+        assert!(approx_eq!(f64, 2.5, rgb_at(&rgb, 581.0)));
+        assert_eq!(1.0, rgb_at(&rgb, 380.0));
+    }
+
+    #[test]
+    fn soap_film_reflects_wavelengths_differently() {
+        let film = ThinFilm::new(400.0, 1.33);
+        let rgb = film.reflectance_rgb(0.9, 1.0, &Vector::new(1.0, 1.0, 1.0), &Vector::empty());
+        let spread = rgb.x().max(rgb.y()).max(rgb.z()) - rgb.x().min(rgb.y()).min(rgb.z());
+        assert!(spread > 0.02, "{} {} {}", rgb.x(), rgb.y(), rgb.z());
+        assert!(rgb.x() < 0.25 && rgb.y() < 0.25 && rgb.z() < 0.25);
+    }
+}
@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::material::Material;
+use crate::math;
+use crate::ray::Ray;
+use crate::vector::Vector;
+
+/// A ring around `center`, lying flat in the plane perpendicular to the y axis. The tube of
+/// radius `minor_radius` follows a circle of radius `major_radius`. `u` runs around the ring and
+/// `v` runs around the tube, starting from its outer equator.
+pub struct Torus {
+    center: Vector,
+    major_radius: f64,
+    minor_radius: f64,
+    material: Rc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: &Vector,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Rc<dyn Material>,
+    ) -> Torus {
+        Torus {
+            center: center.clone(),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+
+    fn record_at(&self, ray: &Ray, t: f64) -> HitRecord {
+        let p = ray.at(t);
+        let local = &p - &self.center;
+        let (x, y, z) = (local.x(), local.y(), local.z());
+        let ring_distance = (x * x + z * z).sqrt();
+        // The nearest point on the circle through the middle of the tube.
+        let spine = if ring_distance > 0.0 {
+            Vector::new(x, 0.0, z) * (self.major_radius / ring_distance)
+        } else {
+            Vector::new(self.major_radius, 0.0, 0.0)
+        };
+        let outward_normal = (&local - &spine).unit_vector();
+        let phi = (-z).atan2(x).rem_euclid(2.0 * PI);
+        let theta = y
+            .atan2(ring_distance - self.major_radius)
+            .rem_euclid(2.0 * PI);
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let (cos_phi, sin_phi) = if ring_distance > 0.0 {
+            (x / ring_distance, -z / ring_distance)
+        } else {
+            (1.0, 0.0)
+        };
+        let r = self.minor_radius;
+        let mut hit_record = HitRecord {
+            t,
+            u: phi / (2.0 * PI),
+            v: theta / (2.0 * PI),
+            p,
+            dpdu: 2.0 * PI * Vector::new(z, 0.0, -x),
+            dpdv: 2.0
+                * PI
+                * Vector::new(
+                    -r * sin_theta * cos_phi,
+                    r * cos_theta,
+                    r * sin_theta * sin_phi,
+                ),
+            material: Some(self.material.clone()),
+            ..HitRecord::empty()
+        };
+        hit_record.set_face_normal(ray, &outward_normal);
+        hit_record
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // The quartic is badly conditioned for rays starting far away, so solve it along a unit
+        // direction starting from where the ray reaches the torus's bounding sphere.
+        let length = ray.direction().length();
+        let direction = ray.direction() / length;
+        let to_center = ray.origin() - &self.center;
+        let bound = self.major_radius + self.minor_radius;
+        let half_b = Vector::dot(&to_center, &direction);
+        let discriminant = half_b * half_b - (to_center.length_squared() - bound * bound);
+        if discriminant < 0.0 {
+            return (false, HitRecord::empty());
+        }
+        let start = (-half_b - discriminant.sqrt()).max(0.0);
+        let origin = &to_center + start * &direction;
+
+        // (|p|² + R² - r²)² = 4R² (x² + z²), for p = origin + s * direction.
+        let major2 = self.major_radius * self.major_radius;
+        let n = Vector::dot(&origin, &direction);
+        let k = origin.length_squared() + major2 - self.minor_radius * self.minor_radius;
+        let flat_d2 = direction.x() * direction.x() + direction.z() * direction.z();
+        let flat_od = origin.x() * direction.x() + origin.z() * direction.z();
+        let flat_o2 = origin.x() * origin.x() + origin.z() * origin.z();
+        let roots = math::solve_quartic(
+            1.0,
+            4.0 * n,
+            4.0 * n * n + 2.0 * k - 4.0 * major2 * flat_d2,
+            4.0 * n * k - 8.0 * major2 * flat_od,
+            k * k - 4.0 * major2 * flat_o2,
+        );
+        for s in roots {
+            let t = (start + s) / length;
+            if t <= t_min || t >= t_max {
+                continue;
+            }
+            let hit_record = self.record_at(ray, t);
+            if hit_record.is_opaque() {
+                return (true, hit_record);
+            }
+        }
+        (false, HitRecord::empty())
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vector::new(outer, self.minor_radius, outer);
+        Some(Aabb::new(
+            &(&self.center - &extent),
+            &(&self.center + &extent),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+
+    fn ring() -> Torus {
+        Torus::new(
+            &Vector::new(0.0, 1.0, 0.0),
+            2.0,
+            0.5,
+            Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5))),
+        )
+    }
+
+    // How far a point is from the surface of the ring.
+    fn surface_distance(torus: &Torus, p: &Vector) -> f64 {
+        let local = p - &torus.center;
+        let ring = (local.x() * local.x() + local.z() * local.z()).sqrt() - torus.major_radius;
+        (ring * ring + local.y() * local.y()).sqrt() - torus.minor_radius
+    }
+
+    #[test]
+    fn rays_hit_the_tube_and_pass_through_the_hole() {
+        let torus = ring();
+        let down = |x: f64| Ray::new(&Vector::new(x, 5.0, 0.0), &Vector::new(0.0, -1.0, 0.0));
+        let (hit, hit_record) = torus.hit(&down(2.0), 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, 3.5, hit_record.t, epsilon = 1e-12));
+        assert!(hit_record.front_face);
+        assert!(approx_eq!(f64, 1.0, hit_record.normal.y(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.25, hit_record.v, epsilon = 1e-12));
+        assert!(!torus.hit(&down(0.0), 0.001, f64::INFINITY).0);
+        assert!(!torus.hit(&down(1.2), 0.001, f64::INFINITY).0);
+
+        // From inside the tube, the ray leaves through its far wall.
+        let inside = Ray::new(&Vector::new(2.0, 1.0, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        let (hit, hit_record) = torus.hit(&inside, 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(!hit_record.front_face);
+        assert!(approx_eq!(f64, 2.5, hit_record.p.x(), epsilon = 1e-12));
+    }
+
+    #[test]
+    fn grazing_rays_hit_just_inside_the_tube_and_miss_just_outside() {
+        let torus = ring();
+        let across = |y: f64| Ray::new(&Vector::new(-10.0, y, 0.0), &Vector::new(1.0, 0.0, 0.0));
+        // Skimming the top of the tube, barely inside.
+        let (hit, hit_record) = torus.hit(&across(1.5 - 1e-6), 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, -2.0, hit_record.p.x(), epsilon = 2e-3));
+        assert!(surface_distance(&torus, &hit_record.p).abs() < 1e-9);
+        assert!(!torus.hit(&across(1.5 + 1e-6), 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn silhouette_hits_from_far_away_are_accurate() {
+        let torus = ring();
+        let far = 1e4;
+        for &offset in &[2.5 - 1e-4, 2.4, 1.6, 1.5 + 1e-4] {
+            let ray = Ray::new(&Vector::new(offset, 1.0, far), &Vector::new(0.0, 0.0, -1.0));
+            let (hit, hit_record) = torus.hit(&ray, 0.001, f64::INFINITY);
+            assert!(hit, "{}", offset);
+            assert!(surface_distance(&torus, &hit_record.p).abs() < 1e-9);
+            assert!(hit_record.front_face);
+        }
+        let beyond = Ray::new(
+            &Vector::new(2.5 + 1e-4, 1.0, far),
+            &Vector::new(0.0, 0.0, -1.0),
+        );
+        assert!(!torus.hit(&beyond, 0.001, f64::INFINITY).0);
+    }
+
+    #[test]
+    fn partial_derivatives_match_the_normal() {
+        let torus = ring();
+        let ray = Ray::new(&Vector::new(1.0, 5.0, 1.8), &Vector::new(0.1, -1.0, 0.0));
+        let (hit, hit_record) = torus.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        let cross = Vector::cross(&hit_record.dpdu, &hit_record.dpdv).unit_vector();
+        assert!(approx_eq!(
+            f64,
+            1.0,
+            Vector::dot(&cross, &hit_record.normal),
+            epsilon = 1e-9
+        ));
+    }
+}
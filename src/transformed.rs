@@ -0,0 +1,255 @@
+use std::rc::Rc;
+
+use crate::aabb::Aabb;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::ray::Ray;
+use crate::vector::{AnimatedTransform, Transform, Vector};
+
+/// How many times over the shutter interval a moving instance's bounds are sampled.
+const MOTION_SAMPLES: usize = 32;
+
+/// An instance of another object, moved, rotated or scaled by an affine transform. The object is
+/// shared, so that many instances of the same geometry cost little more than one.
+pub struct Transformed<H: Hittable + ?Sized> {
+    object: Rc<H>,
+    transform: AnimatedTransform,
+}
+
+impl<H: Hittable + ?Sized> Transformed<H> {
+    pub fn new(object: Rc<H>, transform: Transform) -> Transformed<H> {
+        Transformed {
+            object,
+            transform: AnimatedTransform::fixed(transform),
+        }
+    }
+
+    /// An instance that moves from `start` at time zero to `end` at time one.
+    pub fn moving(object: Rc<H>, start: Transform, end: Transform) -> Transformed<H> {
+        Transformed {
+            object,
+            transform: AnimatedTransform::new(start, end),
+        }
+    }
+
+    /// The box around the object under a single transform.
+    fn bounds_under(&self, bounds: &Aabb, transform: &Transform) -> Aabb {
+        let (min, max) = (bounds.minimum(), bounds.maximum());
+        let corners: Vec<Vector> = (0..8)
+            .map(|i| {
+                let pick = |bit: usize, low: f64, high: f64| if i & bit == 0 { low } else { high };
+                transform.point(&Vector::new(
+                    pick(1, min.x(), max.x()),
+                    pick(2, min.y(), max.y()),
+                    pick(4, min.z(), max.z()),
+                ))
+            })
+            .collect();
+        Aabb::from_points(&corners)
+    }
+}
+
+impl<H: Hittable + ?Sized> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> (bool, HitRecord) {
+        // The direction is not normalized in object space, so distances along the ray match.
+        let transform = self.transform.at(ray.time());
+        let to_object = transform.inverse();
+        let object_ray = ray.spawn(
+            &to_object.point(ray.origin()),
+            &to_object.vector(ray.direction()),
+        );
+        let (hit, hit_record) = self.object.hit(&object_ray, t_min, t_max);
+        if !hit {
+            return (false, hit_record);
+        }
+        let hit_record = HitRecord {
+            p: transform.point(&hit_record.p),
+            normal: transform.normal(&hit_record.normal).unit_vector(),
+            geometric_normal: transform.normal(&hit_record.geometric_normal).unit_vector(),
+            dpdu: transform.vector(&hit_record.dpdu),
+            dpdv: transform.vector(&hit_record.dpdv),
+            ..hit_record
+        };
+        (true, hit_record)
+    }
+
+    fn transmittance(&self, ray: &Ray, t_min: f64, t_max: f64) -> f64 {
+        let to_object = self.transform.at(ray.time()).inverse();
+        let object_ray = ray.spawn(
+            &to_object.point(ray.origin()),
+            &to_object.vector(ray.direction()),
+        );
+        self.object.transmittance(&object_ray, t_min, t_max)
+    }
+
+    /// For moving instances, the boxes at evenly spaced times from zero to one, widened by the
+    /// furthest any corner moves between samples, so that the box covers the whole motion.
+    fn bounding_box(&self) -> Option<Aabb> {
+        let bounds = self.object.bounding_box()?;
+        if !self.transform.is_moving() {
+            return Some(self.bounds_under(&bounds, &self.transform.at(0.0)));
+        }
+        let samples: Vec<Aabb> = (0..=MOTION_SAMPLES)
+            .map(|i| {
+                let time = i as f64 / MOTION_SAMPLES as f64;
+                self.bounds_under(&bounds, &self.transform.at(time))
+            })
+            .collect();
+        let step = samples
+            .windows(2)
+            .map(|pair| {
+                let low = pair[1].minimum() - pair[0].minimum();
+                let high = pair[1].maximum() - pair[0].maximum();
+                low.length().max(high.length())
+            })
+            .fold(0.0, f64::max);
+        let swept = samples[1..]
+            .iter()
+            .fold(samples[0].clone(), |swept, bounds| {
+                swept.surrounding(bounds)
+            });
+        let margin = Vector::new(step, step, step);
+        Some(Aabb::new(
+            &(swept.minimum() - &margin),
+            &(swept.maximum() + &margin),
+        ))
+    }
+
+    /// The object's density for the matching direction in object space, scaled by how the
+    /// transform stretches solid angle: a linear map `A` takes the unit direction `ω` to a density
+    /// scaled by `|det A⁻¹| / |A⁻¹ω|³`. Moving instances are sampled where they start.
+    fn pdf_value(&self, origin: &Vector, direction: &Vector) -> Option<f64> {
+        let to_object = self.transform.at(0.0).inverse();
+        let direction = direction.unit_vector();
+        let object_direction = to_object.vector(&direction);
+        let density = self
+            .object
+            .pdf_value(&to_object.point(origin), &object_direction)?;
+        let stretch = object_direction.length();
+        Some(
+            density * to_object.matrix().linear_determinant().abs() / (stretch * stretch * stretch),
+        )
+    }
+
+    fn random(&self, origin: &Vector) -> Option<Vector> {
+        let transform = self.transform.at(0.0);
+        let object_origin = transform.inverse().point(origin);
+        Some(transform.vector(&self.object.random(&object_origin)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::approx_eq;
+
+    use super::*;
+    use crate::lambertian::Lambertian;
+    use crate::quad::Quad;
+    use crate::sphere::Sphere;
+
+    fn grey() -> Rc<Lambertian> {
+        Rc::new(Lambertian::new(&Vector::new(0.5, 0.5, 0.5)))
+    }
+
+    #[test]
+    fn hits_follow_the_transformed_object() {
+        let sphere = Rc::new(Sphere::new(&Vector::empty(), 1.0, grey()));
+        let transform = Transform::scaling(&Vector::new(2.0, 1.0, 1.0))
+            .unwrap()
+            .then(&Transform::translation(&Vector::new(0.0, 0.0, -5.0)));
+        let ellipsoid = Transformed::new(sphere, transform);
+
+        let ray = Ray::new(&Vector::new(1.0, 0.0, 0.0), &Vector::new(0.0, 0.0, -1.0));
+        let (hit, hit_record) = ellipsoid.hit(&ray, 0.001, f64::INFINITY);
+        assert!(hit);
+        // The ellipse x²/4 + z² = 1 around z = -5 is crossed at z = -5 + √3/2.
+        let z = 0.75_f64.sqrt();
+        assert!(approx_eq!(f64, 5.0 - z, hit_record.t, epsilon = 1e-9));
+        assert!(approx_eq!(f64, -5.0 + z, hit_record.p.z(), epsilon = 1e-9));
+        // Its normal follows the gradient (x / 4, y, z), not the scaled sphere normal.
+        let expected = Vector::new(0.25, 0.0, z).unit_vector();
+        assert!((&hit_record.normal - expected).length() < 1e-9);
+        assert!(hit_record.front_face);
+    }
+
+    #[test]
+    fn bounding_box_covers_the_rotated_object() {
+        let sphere: Rc<dyn Hittable> = Rc::new(Sphere::new(&Vector::empty(), 1.0, grey()));
+        let transform = Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 45.0);
+        let rotated = Transformed::new(sphere, transform);
+        let bounds = rotated.bounding_box().unwrap();
+        let half_diagonal = 2.0_f64.sqrt();
+        assert!(approx_eq!(
+            f64,
+            half_diagonal,
+            bounds.maximum().x(),
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(
+            f64,
+            -half_diagonal,
+            bounds.minimum().y(),
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(f64, 1.0, bounds.maximum().z(), epsilon = 1e-9));
+    }
+
+    #[test]
+    fn moving_instances_follow_the_ray_time() {
+        let sphere = Rc::new(Sphere::new(&Vector::empty(), 1.0, grey()));
+        let spinning = Transformed::moving(
+            sphere,
+            Transform::translation(&Vector::new(3.0, 0.0, 0.0)),
+            Transform::translation(&Vector::new(3.0, 0.0, 0.0))
+                .then(&Transform::rotation(&Vector::new(0.0, 1.0, 0.0), 90.0)),
+        );
+        // Starting on the x axis, the ball swings round to the negative z axis.
+        let down_z = Ray::new(&Vector::new(0.0, 0.0, -10.0), &Vector::new(0.0, 0.0, 1.0));
+        assert!(
+            !spinning
+                .hit(&down_z.clone().with_time(0.0), 0.001, f64::INFINITY)
+                .0
+        );
+        let (hit, hit_record) = spinning.hit(&down_z.with_time(1.0), 0.001, f64::INFINITY);
+        assert!(hit);
+        assert!(approx_eq!(f64, -4.0, hit_record.p.z(), epsilon = 1e-9));
+        // The bounds cover the whole quarter turn, including halfway round.
+        let bounds = spinning.bounding_box().unwrap();
+        let halfway = 3.0 * std::f64::consts::FRAC_1_SQRT_2;
+        assert!(bounds.maximum().x() >= 4.0);
+        assert!(bounds.minimum().z() <= -4.0);
+        assert!(bounds.maximum().x() >= halfway + 1.0);
+        assert!(bounds.minimum().z() <= -halfway - 1.0);
+    }
+
+    // A stretched quad is another quad, so its densities must agree with the real thing's.
+    #[test]
+    fn pdf_accounts_for_stretching() {
+        let unit = Rc::new(Quad::new(
+            &Vector::new(0.0, 0.0, 0.0),
+            &Vector::new(1.0, 0.0, 0.0),
+            &Vector::new(0.0, 1.0, 0.0),
+            grey(),
+        ));
+        let transform = Transform::scaling(&Vector::new(3.0, 2.0, 1.0))
+            .unwrap()
+            .then(&Transform::rotation(&Vector::new(1.0, 1.0, 0.0), 30.0));
+        let stretched = Transformed::new(unit, transform.clone());
+        let direct = Quad::new(
+            &Vector::empty(),
+            &transform.vector(&Vector::new(1.0, 0.0, 0.0)),
+            &transform.vector(&Vector::new(0.0, 1.0, 0.0)),
+            grey(),
+        );
+        let origin = Vector::new(1.0, 2.0, 4.0);
+        for _ in 0..20 {
+            let direction = stretched.random(&origin).unwrap();
+            assert!(approx_eq!(
+                f64,
+                direct.pdf_value(&origin, &direction).unwrap(),
+                stretched.pdf_value(&origin, &direction).unwrap(),
+                epsilon = 1e-6
+            ));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use rand::Rng;
+use std::borrow::Cow;
 use std::clone::Clone;
 use std::ops;
 
@@ -111,6 +112,424 @@ impl_op_ex!(*|a: &Vector, b: f64| -> Vector { Vector(a.0 * b, a.1 * b, a.2 * b)
 impl_op_ex!(*|a: f64, b: &Vector| -> Vector { Vector(a * b.0, a * b.1, a * b.2) });
 impl_op_ex!(/ |a: &Vector, b: f64| -> Vector { (1.0 / b) * a });
 
+/// A 4x4 matrix acting on points and vectors in homogeneous coordinates, stored by rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix([[f64; 4]; 4]);
+
+impl Matrix {
+    pub fn new(rows: [[f64; 4]; 4]) -> Matrix {
+        Matrix(rows)
+    }
+
+    pub fn identity() -> Matrix {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Matrix(rows)
+    }
+
+    pub fn at(&self, row: usize, column: usize) -> f64 {
+        self.0[row][column]
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut rows = [[0.0; 4]; 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.0[j][i];
+            }
+        }
+        Matrix(rows)
+    }
+
+    /// The inverse, found by Gauss-Jordan elimination with partial pivoting, or `None` if the
+    /// matrix is singular.
+    pub fn inverse(&self) -> Option<Matrix> {
+        let mut a = self.0;
+        let mut inverse = Matrix::identity().0;
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+                .unwrap();
+            if a[pivot][column].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+            let scale = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = a[row][column];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[column][j];
+                    inverse[row][j] -= factor * inverse[column][j];
+                }
+            }
+        }
+        Some(Matrix(inverse))
+    }
+
+    /// The determinant of the upper-left 3x3 block, which scales volumes.
+    pub fn linear_determinant(&self) -> f64 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Applies the matrix to a point, dividing through by the homogeneous coordinate.
+    pub fn transform_point(&self, p: &Vector) -> Vector {
+        let m = &self.0;
+        let row = |i: usize| m[i][0] * p.0 + m[i][1] * p.1 + m[i][2] * p.2 + m[i][3];
+        let w = row(3);
+        Vector(row(0) / w, row(1) / w, row(2) / w)
+    }
+
+    /// Applies the matrix to a direction, ignoring translation.
+    pub fn transform_vector(&self, v: &Vector) -> Vector {
+        let m = &self.0;
+        let row = |i: usize| m[i][0] * v.0 + m[i][1] * v.1 + m[i][2] * v.2;
+        Vector(row(0), row(1), row(2))
+    }
+}
+
+impl_op_ex!(*|a: &Matrix, b: &Matrix| -> Matrix {
+    let mut rows = [[0.0; 4]; 4];
+    for (i, row) in rows.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a.0[i][k] * b.0[k][j]).sum();
+        }
+    }
+    Matrix(rows)
+});
+
+/// An invertible affine transform, kept together with its inverse so that either direction is
+/// cheap to apply.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    matrix: Matrix,
+    inverse: Matrix,
+}
+
+impl Transform {
+    /// Wraps `matrix`, or returns `None` if it cannot be inverted.
+    pub fn new(matrix: Matrix) -> Option<Transform> {
+        let inverse = matrix.inverse()?;
+        Some(Transform { matrix, inverse })
+    }
+
+    pub fn identity() -> Transform {
+        Transform {
+            matrix: Matrix::identity(),
+            inverse: Matrix::identity(),
+        }
+    }
+
+    pub fn translation(offset: &Vector) -> Transform {
+        let translate = |x: f64, y: f64, z: f64| {
+            Matrix([
+                [1.0, 0.0, 0.0, x],
+                [0.0, 1.0, 0.0, y],
+                [0.0, 0.0, 1.0, z],
+                [0.0, 0.0, 0.0, 1.0],
+            ])
+        };
+        Transform {
+            matrix: translate(offset.0, offset.1, offset.2),
+            inverse: translate(-offset.0, -offset.1, -offset.2),
+        }
+    }
+
+    /// Scales by a separate factor along each axis. Returns `None` if any factor is zero.
+    pub fn scaling(factors: &Vector) -> Option<Transform> {
+        Transform::new(Matrix([
+            [factors.0, 0.0, 0.0, 0.0],
+            [0.0, factors.1, 0.0, 0.0],
+            [0.0, 0.0, factors.2, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]))
+    }
+
+    /// Rotates counterclockwise, looking down `axis` towards the origin, by `degrees`.
+    pub fn rotation(axis: &Vector, degrees: f64) -> Transform {
+        let a = axis.unit_vector();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        let matrix = Matrix([
+            [
+                t * a.0 * a.0 + cos,
+                t * a.0 * a.1 - sin * a.2,
+                t * a.0 * a.2 + sin * a.1,
+                0.0,
+            ],
+            [
+                t * a.0 * a.1 + sin * a.2,
+                t * a.1 * a.1 + cos,
+                t * a.1 * a.2 - sin * a.0,
+                0.0,
+            ],
+            [
+                t * a.0 * a.2 - sin * a.1,
+                t * a.1 * a.2 + sin * a.0,
+                t * a.2 * a.2 + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        // Rotations are orthogonal, so the inverse is the transpose.
+        Transform {
+            inverse: matrix.transpose(),
+            matrix,
+        }
+    }
+
+    /// This transform followed by `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            matrix: &next.matrix * &self.matrix,
+            inverse: &self.inverse * &next.inverse,
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        Transform {
+            matrix: self.inverse.clone(),
+            inverse: self.matrix.clone(),
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn point(&self, p: &Vector) -> Vector {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector(&self, v: &Vector) -> Vector {
+        self.matrix.transform_vector(v)
+    }
+
+    /// Transforms a surface normal, which must stay perpendicular to the transformed surface and
+    /// so goes by the inverse transpose. The result is not normalized.
+    pub fn normal(&self, n: &Vector) -> Vector {
+        self.inverse.transpose().transform_vector(n)
+    }
+}
+
+/// A transform that changes over time, between `start` at time zero and `end` at time one. The
+/// two are split into translation, rotation and scale, which are interpolated separately so that
+/// rotating objects turn rather than shrink through the middle of their motion.
+#[derive(Clone)]
+pub struct AnimatedTransform {
+    start: Transform,
+    end: Transform,
+    motion: Option<(Decomposed, Decomposed)>,
+}
+
+#[derive(Clone)]
+struct Decomposed {
+    translation: Vector,
+    /// A unit quaternion, as `(w, x, y, z)`.
+    rotation: [f64; 4],
+    scale: Matrix,
+}
+
+impl AnimatedTransform {
+    pub fn new(start: Transform, end: Transform) -> AnimatedTransform {
+        let motion = if start.matrix == end.matrix {
+            None
+        } else {
+            Some((decompose(&start.matrix), decompose(&end.matrix)))
+        };
+        AnimatedTransform { start, end, motion }
+    }
+
+    /// A transform that stays the same throughout.
+    pub fn fixed(transform: Transform) -> AnimatedTransform {
+        AnimatedTransform {
+            start: transform.clone(),
+            end: transform,
+            motion: None,
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.motion.is_some()
+    }
+
+    /// The transform at `time`, which is held at either end outside of zero to one.
+    pub fn at(&self, time: f64) -> Cow<'_, Transform> {
+        let (from, to) = match &self.motion {
+            None => return Cow::Borrowed(&self.start),
+            Some(motion) => motion,
+        };
+        if time <= 0.0 {
+            return Cow::Borrowed(&self.start);
+        }
+        if time >= 1.0 {
+            return Cow::Borrowed(&self.end);
+        }
+        let translation = &from.translation + time * (&to.translation - &from.translation);
+        let rotation = slerp(&from.rotation, &to.rotation, time);
+        let mut scale = [[0.0; 4]; 4];
+        for (i, row) in scale.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = from.scale.0[i][j] + time * (to.scale.0[i][j] - from.scale.0[i][j]);
+            }
+        }
+        let mut matrix = quaternion_to_matrix(&rotation) * Matrix(scale);
+        matrix.0[0][3] = translation.0;
+        matrix.0[1][3] = translation.1;
+        matrix.0[2][3] = translation.2;
+        Cow::Owned(Transform::new(matrix).unwrap_or_else(|| self.start.clone()))
+    }
+}
+
+/// Splits an affine matrix into a translation, then a rotation, then a symmetric scale, using the
+/// polar decomposition of its linear part.
+fn decompose(matrix: &Matrix) -> Decomposed {
+    let translation = Vector(matrix.0[0][3], matrix.0[1][3], matrix.0[2][3]);
+    let mut linear = matrix.clone();
+    for i in 0..3 {
+        linear.0[i][3] = 0.0;
+        linear.0[3][i] = 0.0;
+    }
+    linear.0[3][3] = 1.0;
+
+    // Averaging a matrix with its inverse transpose converges to its nearest orthogonal matrix.
+    let mut rotation = linear.clone();
+    for _ in 0..100 {
+        let inverse_transpose = match rotation.inverse() {
+            Some(inverse) => inverse.transpose(),
+            None => break,
+        };
+        let mut next = rotation.clone();
+        let mut change: f64 = 0.0;
+        for i in 0..3 {
+            for j in 0..3 {
+                next.0[i][j] = 0.5 * (rotation.0[i][j] + inverse_transpose.0[i][j]);
+                change = change.max((next.0[i][j] - rotation.0[i][j]).abs());
+            }
+        }
+        rotation = next;
+        if change < 1e-12 {
+            break;
+        }
+    }
+    // Mirror images come out as improper rotations; fold the flip into the scale instead.
+    let mut scale = rotation.transpose() * &linear;
+    if rotation.linear_determinant() < 0.0 {
+        for i in 0..3 {
+            for j in 0..3 {
+                rotation.0[i][j] = -rotation.0[i][j];
+                scale.0[i][j] = -scale.0[i][j];
+            }
+        }
+    }
+    Decomposed {
+        translation,
+        rotation: matrix_to_quaternion(&rotation),
+        scale,
+    }
+}
+
+fn matrix_to_quaternion(m: &Matrix) -> [f64; 4] {
+    let m = &m.0;
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    if trace > 0.0 {
+        let s = 0.5 / (trace + 1.0).sqrt();
+        [
+            0.25 / s,
+            (m[2][1] - m[1][2]) * s,
+            (m[0][2] - m[2][0]) * s,
+            (m[1][0] - m[0][1]) * s,
+        ]
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = 2.0 * (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt();
+        [
+            (m[2][1] - m[1][2]) / s,
+            0.25 * s,
+            (m[0][1] + m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+        ]
+    } else if m[1][1] > m[2][2] {
+        let s = 2.0 * (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt();
+        [
+            (m[0][2] - m[2][0]) / s,
+            (m[0][1] + m[1][0]) / s,
+            0.25 * s,
+            (m[1][2] + m[2][1]) / s,
+        ]
+    } else {
+        let s = 2.0 * (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt();
+        [
+            (m[1][0] - m[0][1]) / s,
+            (m[0][2] + m[2][0]) / s,
+            (m[1][2] + m[2][1]) / s,
+            0.25 * s,
+        ]
+    }
+}
+
+fn quaternion_to_matrix(q: &[f64; 4]) -> Matrix {
+    let [w, x, y, z] = *q;
+    Matrix([
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+            0.0,
+        ],
+        [
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+            0.0,
+        ],
+        [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+            0.0,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// Spherical linear interpolation between unit quaternions, the short way around.
+fn slerp(a: &[f64; 4], b: &[f64; 4], t: f64) -> [f64; 4] {
+    let mut cos_theta: f64 = (0..4).map(|i| a[i] * b[i]).sum();
+    let mut b = *b;
+    if cos_theta < 0.0 {
+        cos_theta = -cos_theta;
+        b.iter_mut().for_each(|value| *value = -*value);
+    }
+    let (wa, wb) = if cos_theta > 0.9995 {
+        (1.0 - t, t)
+    } else {
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        (
+            ((1.0 - t) * theta).sin() / sin_theta,
+            (t * theta).sin() / sin_theta,
+        )
+    };
+    let mut q = [0.0; 4];
+    for i in 0..4 {
+        q[i] = wa * a[i] + wb * b[i];
+    }
+    let norm = q.iter().map(|value| value * value).sum::<f64>().sqrt();
+    q.map(|value| value / norm)
+}
+
 #[cfg(test)]
 mod tests {
     use float_cmp::approx_eq;
@@ -137,6 +556,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn test_vector_length() {
         let vector = Vector::new(1., 1., 1.);
         let length = vector.length();
@@ -225,6 +645,71 @@ mod tests {
         assert_eq!(12., result.z());
     }
 
+    #[test]
+    fn matrix_times_its_inverse_is_identity() {
+        let matrix = Matrix::new([
+            [2.0, 0.5, 0.0, 1.0],
+            [0.0, 0.0, 3.0, -2.0],
+            [1.0, 1.0, 1.0, 0.5],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let product = &matrix * matrix.inverse().unwrap();
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!(approx_eq!(f64, expected, product.at(i, j), epsilon = 1e-12));
+            }
+        }
+        let singular = Matrix::new([[1.0; 4]; 4]);
+        assert!(singular.inverse().is_none());
+    }
+
+    #[test]
+    fn transforms_compose_in_order() {
+        let transform = Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 90.0)
+            .then(&Transform::translation(&Vector::new(1.0, 0.0, 0.0)));
+        let p = transform.point(&Vector::new(1.0, 0.0, 0.0));
+        assert!(approx_eq!(f64, 1.0, p.x(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 1.0, p.y(), epsilon = 1e-12));
+        // Directions are rotated but not moved.
+        let v = transform.vector(&Vector::new(1.0, 0.0, 0.0));
+        assert!(approx_eq!(f64, 0.0, v.x(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 1.0, v.y(), epsilon = 1e-12));
+        let back = transform.inverse().point(&p);
+        assert!(approx_eq!(f64, 1.0, back.x(), epsilon = 1e-12));
+        assert!(approx_eq!(f64, 0.0, back.y(), epsilon = 1e-12));
+    }
+
+    #[test]
+    fn normals_stay_perpendicular_under_scaling() {
+        let transform = Transform::scaling(&Vector::new(4.0, 1.0, 1.0)).unwrap();
+        // The diagonal x = y and its normal.
+        let tangent = transform.vector(&Vector::new(1.0, 1.0, 0.0));
+        let normal = transform.normal(&Vector::new(1.0, -1.0, 0.0));
+        assert!(approx_eq!(f64, 0.0, Vector::dot(&tangent, &normal)));
+        assert!(Transform::scaling(&Vector::new(1.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn animated_transforms_turn_through_the_middle() {
+        let start = Transform::translation(&Vector::new(1.0, 0.0, 0.0));
+        let end = Transform::scaling(&Vector::new(2.0, 2.0, 2.0))
+            .unwrap()
+            .then(&Transform::rotation(&Vector::new(0.0, 0.0, 1.0), 90.0))
+            .then(&Transform::translation(&Vector::new(3.0, 0.0, 0.0)));
+        let animated = AnimatedTransform::new(start.clone(), end.clone());
+        let p = Vector::new(1.0, 0.0, 0.0);
+        // The ends are reproduced exactly.
+        assert!((animated.at(0.0).point(&p) - start.point(&p)).length() < 1e-9);
+        assert!((animated.at(1.0).point(&p) - end.point(&p)).length() < 1e-9);
+        // Halfway, the point has turned 45 degrees and grown by half, rather than cutting the
+        // corner as interpolating the matrices would.
+        let middle = animated.at(0.5).point(&p);
+        let offset = &middle - Vector::new(2.0, 0.0, 0.0);
+        assert!(approx_eq!(f64, 1.5, offset.length(), epsilon = 1e-9));
+        assert!(approx_eq!(f64, offset.x(), offset.y(), epsilon = 1e-9));
+    }
+
     #[test]
     fn test_vector_scalar_divide() {
         let vec1 = Vector::new(2., 4., 8.);